@@ -0,0 +1,183 @@
+//! Conversion of norad data to JSON, using the same field names as ufoLib2.
+
+use iondrive::norad;
+use serde_json::{json, Map, Value};
+
+/// Converts value to a JSON integer if it can be considered one, otherwise a
+/// JSON float with the shortest decimal representation of the `f32`.
+fn number(v: f32) -> Value {
+    if (v - v.round()).abs() < f32::EPSILON {
+        json!(v as i64)
+    } else {
+        v.to_string()
+            .parse::<f64>()
+            .map_or(Value::Null, |v| json!(v))
+    }
+}
+
+fn color(color: Option<&norad::Color>) -> Value {
+    color.map_or(Value::Null, |c| json!(c.to_rgba_string()))
+}
+
+fn identifier(identifier: Option<&norad::Identifier>) -> Value {
+    identifier.map_or(Value::Null, |i| json!(i.as_str()))
+}
+
+fn plist_value(value: &plist::Value) -> Value {
+    match value {
+        plist::Value::String(s) => json!(s),
+        plist::Value::Boolean(b) => json!(b),
+        plist::Value::Data(d) => json!(d),
+        plist::Value::Date(d) => json!(d.to_xml_format()),
+        plist::Value::Real(r) => json!(r),
+        plist::Value::Integer(i) => i
+            .as_signed()
+            .map(|i| json!(i))
+            .or_else(|| i.as_unsigned().map(|i| json!(i)))
+            .unwrap_or(Value::Null),
+        plist::Value::Uid(u) => json!(u.get()),
+        plist::Value::Array(a) => Value::Array(a.iter().map(plist_value).collect()),
+        plist::Value::Dictionary(d) => plist_dict(d),
+        _ => Value::Null,
+    }
+}
+
+fn plist_dict(dict: &plist::Dictionary) -> Value {
+    Value::Object(
+        dict.iter()
+            .map(|(k, v)| (k.clone(), plist_value(v)))
+            .collect(),
+    )
+}
+
+fn guideline(guideline: &norad::Guideline) -> Value {
+    let (x, y, angle) = match guideline.line {
+        norad::Line::Vertical(x) => (number(x), Value::Null, Value::Null),
+        norad::Line::Horizontal(y) => (Value::Null, number(y), Value::Null),
+        norad::Line::Angle { x, y, degrees } => (number(x), number(y), number(degrees)),
+    };
+    json!({
+        "name": guideline.name,
+        "x": x,
+        "y": y,
+        "angle": angle,
+        "color": color(guideline.color.as_ref()),
+        "identifier": identifier(guideline.identifier()),
+    })
+}
+
+fn point(point: &norad::ContourPoint) -> Value {
+    let typ = match point.typ {
+        norad::PointType::OffCurve => Value::Null,
+        ref typ => json!(typ.to_string()),
+    };
+    json!({
+        "x": number(point.x),
+        "y": number(point.y),
+        "type": typ,
+        "smooth": point.smooth,
+        "name": point.name,
+        "identifier": identifier(point.identifier()),
+    })
+}
+
+fn glyph(glyph: &norad::Glyph) -> Value {
+    let anchors: Vec<Value> = glyph
+        .anchors
+        .iter()
+        .map(|a| {
+            json!({
+                "x": number(a.x),
+                "y": number(a.y),
+                "name": a.name,
+                "color": color(a.color.as_ref()),
+                "identifier": identifier(a.identifier()),
+            })
+        })
+        .collect();
+    let contours: Vec<Value> = glyph
+        .contours
+        .iter()
+        .map(|c| {
+            json!({
+                "points": c.points.iter().map(point).collect::<Vec<Value>>(),
+                "identifier": identifier(c.identifier()),
+            })
+        })
+        .collect();
+    let components: Vec<Value> = glyph
+        .components
+        .iter()
+        .map(|c| {
+            let t = &c.transform;
+            json!({
+                "baseGlyph": c.base.as_ref(),
+                "transformation": [
+                    number(t.x_scale),
+                    number(t.xy_scale),
+                    number(t.yx_scale),
+                    number(t.y_scale),
+                    number(t.x_offset),
+                    number(t.y_offset),
+                ],
+                "identifier": identifier(c.identifier()),
+            })
+        })
+        .collect();
+    json!({
+        "name": glyph.name.as_ref(),
+        "width": number(glyph.width),
+        "height": number(glyph.height),
+        "unicodes": glyph.codepoints.iter().map(|c| *c as u32).collect::<Vec<u32>>(),
+        "note": glyph.note,
+        "lib": plist_dict(&glyph.lib),
+        "anchors": anchors,
+        "contours": contours,
+        "components": components,
+        "guidelines": glyph.guidelines.iter().map(guideline).collect::<Vec<Value>>(),
+    })
+}
+
+fn layer(layer: &norad::Layer) -> Value {
+    let glyphs: Map<String, Value> = layer
+        .iter()
+        .map(|g| (g.name.to_string(), glyph(g)))
+        .collect();
+    json!({
+        "name": layer.name().as_ref(),
+        "color": color(layer.color.as_ref()),
+        "lib": plist_dict(&layer.lib),
+        "glyphs": glyphs,
+    })
+}
+
+/// Converts a complete font to JSON.
+pub fn font(font: &norad::Font) -> Result<Value, serde_json::Error> {
+    let info = match font.font_info.as_ref() {
+        Some(info) => match serde_json::to_value(info)? {
+            Value::Object(fields) => fields.into_iter().filter(|(_, v)| !v.is_null()).collect(),
+            other => other,
+        },
+        None => Value::Null,
+    };
+    let kerning: Map<String, Value> = font
+        .kerning
+        .iter()
+        .flatten()
+        .map(|(first, seconds)| {
+            let seconds: Map<String, Value> = seconds
+                .iter()
+                .map(|(second, value)| (second.clone(), number(*value)))
+                .collect();
+            (first.clone(), Value::Object(seconds))
+        })
+        .collect();
+    Ok(json!({
+        "info": info,
+        "lib": plist_dict(&font.lib),
+        "groups": font.groups.clone().unwrap_or_default(),
+        "kerning": kerning,
+        "features": font.features.as_deref().unwrap_or(""),
+        "layers": font.layers.iter().map(layer).collect::<Vec<Value>>(),
+    }))
+}
@@ -0,0 +1,294 @@
+//! Command-line tool to inspect, validate and convert UFOs.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+mod dump;
+
+#[derive(Parser)]
+#[command(name = "iondrive", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check a UFO for problems; exits with an error status if any are found.
+    Validate { ufo: PathBuf },
+    /// Print a summary of a UFO.
+    Info { ufo: PathBuf },
+    /// Print the complete contents of a UFO as JSON.
+    Dump {
+        /// Print JSON, which is also the default and only format.
+        #[arg(long)]
+        json: bool,
+        ufo: PathBuf,
+    },
+    /// Show the differences between two UFOs; exits with an error status if
+    /// there are any.
+    Diff { old: PathBuf, new: PathBuf },
+    /// Merge two edited versions of a UFO; exits with an error status if
+    /// there are conflicts, for which our version is kept.
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Write the result here instead of updating ours.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Merge one file of a UFO as a git merge driver, writing the result to
+    /// `ours`. Configure it with
+    /// `git config merge.ufo.driver "iondrive merge-driver %O %A %B %P"`.
+    MergeDriver {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// The path of the file in the repository.
+        path: PathBuf,
+    },
+    /// Rewrite a UFO in norad's normalized formatting.
+    Normalize {
+        ufo: PathBuf,
+        /// Write the result here instead of replacing the UFO.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a UFO with only some of the glyphs, plus `.notdef` and the
+    /// components they use.
+    Subset {
+        ufo: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Comma-separated names of the glyphs to keep.
+        #[arg(long, value_delimiter = ',')]
+        glyphs: Vec<String>,
+        /// Comma-separated hexadecimal codepoints of the glyphs to keep.
+        #[arg(long, value_delimiter = ',', value_parser = parse_codepoint)]
+        unicodes: Vec<u32>,
+    },
+}
+
+fn parse_codepoint(arg: &str) -> Result<u32, std::num::ParseIntError> {
+    let arg = arg.trim_start_matches("U+").trim_start_matches("u+");
+    u32::from_str_radix(arg, 16)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Validate { ufo } => validate(&ufo),
+        Command::Info { ufo } => info(&ufo),
+        Command::Dump { ufo, .. } => dump(&ufo),
+        Command::Diff { old, new } => diff(&old, &new),
+        Command::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        } => merge(&base, &ours, &theirs, output.as_deref()),
+        Command::MergeDriver {
+            base,
+            ours,
+            theirs,
+            path,
+        } => merge_driver(&base, &ours, &theirs, &path),
+        Command::Normalize { ufo, output } => normalize(&ufo, output.as_deref()),
+        Command::Subset {
+            ufo,
+            output,
+            glyphs,
+            unicodes,
+        } => subset(&ufo, &output, &glyphs, &unicodes),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn validate(path: &Path) -> Result<ExitCode, iondrive::Error> {
+    let report = iondrive::validate::validate_ufo(path)?;
+    for issue in &report.components {
+        println!("error: {}", issue);
+    }
+    for issue in &report.kerning {
+        let severity = if issue.is_warning() {
+            "warning"
+        } else {
+            "error"
+        };
+        println!("{}: {}", severity, issue);
+    }
+    for warning in &report.lib {
+        println!("warning: {}", warning);
+    }
+    for issue in &report.features {
+        println!("error: {}", issue);
+    }
+    if report.has_errors() {
+        Ok(ExitCode::FAILURE)
+    } else {
+        if report.is_empty() {
+            println!("{}: ok", path.display());
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn info(path: &Path) -> Result<ExitCode, iondrive::Error> {
+    let font = iondrive::load(path)?;
+    let default_layer = font.default_layer();
+    println!("{}", path.display());
+    if let Some(info) = font.font_info.as_ref() {
+        let name: Vec<&str> = [info.family_name.as_deref(), info.style_name.as_deref()]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        if !name.is_empty() {
+            println!("Name: {}", name.join(" "));
+        }
+    }
+    println!("Glyphs: {}", default_layer.len());
+    println!("Layers:");
+    for layer in font.layers.iter() {
+        println!("  {}: {} glyphs", layer.name(), layer.len());
+    }
+
+    let codepoints: BTreeSet<u32> = default_layer
+        .iter()
+        .flat_map(|g| g.codepoints.iter().map(|c| *c as u32))
+        .collect();
+    let encoded = default_layer
+        .iter()
+        .filter(|g| !g.codepoints.is_empty())
+        .count();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for cp in codepoints.iter().copied() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cp => *end = cp,
+            _ => ranges.push((cp, cp)),
+        }
+    }
+    println!(
+        "Unicode: {} codepoints in {} ranges, {} encoded and {} unencoded glyphs",
+        codepoints.len(),
+        ranges.len(),
+        encoded,
+        default_layer.len() - encoded
+    );
+
+    let pairs: usize = font.kerning.iter().flatten().map(|(_, v)| v.len()).sum();
+    let glyph_pairs = iondrive::kerning::KerningLookup::from_font(&font)
+        .flatten()
+        .len();
+    println!(
+        "Kerning: {} pairs, {} glyph pairs with groups expanded",
+        pairs, glyph_pairs
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn dump(path: &Path) -> Result<ExitCode, iondrive::Error> {
+    let font = iondrive::load(path)?;
+    let stdout = std::io::stdout();
+    let result = dump::font(&font).and_then(|json| {
+        let mut out = stdout.lock();
+        serde_json::to_writer_pretty(&mut out, &json)?;
+        writeln!(out).map_err(serde_json::Error::io)
+    });
+    match result {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        Err(error) if error.io_error_kind() == Some(std::io::ErrorKind::BrokenPipe) => {
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+fn diff(old: &Path, new: &Path) -> Result<ExitCode, iondrive::Error> {
+    let diff = iondrive::diff::diff_ufos(old, new)?;
+    if diff.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    match writeln!(std::io::stdout(), "{}", diff) {
+        Err(error) if error.kind() != std::io::ErrorKind::BrokenPipe => Err(error.into()),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// Reports `conflicts` and returns the exit code for them.
+fn report_conflicts(conflicts: &[iondrive::merge::Conflict]) -> ExitCode {
+    for conflict in conflicts {
+        eprintln!("conflict: {}", conflict);
+    }
+    if conflicts.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn merge(
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    output: Option<&Path>,
+) -> Result<ExitCode, iondrive::Error> {
+    let conflicts = iondrive::merge::merge_ufos(base, ours, theirs, output.unwrap_or(ours))?;
+    Ok(report_conflicts(&conflicts))
+}
+
+fn merge_driver(
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    path: &Path,
+) -> Result<ExitCode, iondrive::Error> {
+    let (merged, conflicts) =
+        iondrive::merge::merge_file(path, &fs::read(base)?, &fs::read(ours)?, &fs::read(theirs)?)?;
+    fs::write(ours, merged)?;
+    Ok(report_conflicts(&conflicts))
+}
+
+fn normalize(path: &Path, output: Option<&Path>) -> Result<ExitCode, iondrive::Error> {
+    let mut font = iondrive::load(path)?;
+    match output {
+        Some(output) if output != path => {
+            iondrive::save_with_sources(&mut font, output, &[path])?;
+        }
+        _ => {
+            iondrive::save(&font, path)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn subset(
+    path: &Path,
+    output: &Path,
+    glyphs: &[String],
+    unicodes: &[u32],
+) -> Result<ExitCode, iondrive::Error> {
+    let subset = iondrive::subset::subset_ufo(path, output, glyphs, unicodes)?;
+    for name in &subset.missing {
+        eprintln!("warning: glyph '{}' is not in the font", name);
+    }
+    for issue in &subset.features {
+        eprintln!("warning: {}", issue);
+    }
+    Ok(ExitCode::SUCCESS)
+}
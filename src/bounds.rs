@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use kurbo::{Rect, Shape};
+use norad::{Glyph, GlyphName, Layer};
+use rayon::prelude::*;
+
+use crate::decompose::decompose_glyph;
+use crate::Error;
+
+/// Returns the tight bounding box of `glyph`'s outline, including its
+/// components, or `None` if the glyph has no outline.
+pub fn glyph_bounds(layer: &Layer, glyph: &Glyph) -> Result<Option<Rect>, Error> {
+    let mut bounds: Option<Rect> = None;
+    for contour in decompose_glyph(layer, glyph)? {
+        if contour.points.is_empty() {
+            continue;
+        }
+        let rect = if contour.points.len() == 1 {
+            let point = &contour.points[0];
+            Rect::new(
+                point.x as f64,
+                point.y as f64,
+                point.x as f64,
+                point.y as f64,
+            )
+        } else {
+            contour.to_kurbo()?.bounding_box()
+        };
+        bounds = Some(bounds.map_or(rect, |b| b.union(rect)));
+    }
+    Ok(bounds)
+}
+
+/// Returns the bounds of all glyphs in `layer`, computed in parallel.
+pub fn layer_bounds(layer: &Layer) -> Result<BTreeMap<GlyphName, Option<Rect>>, Error> {
+    let glyphs: Vec<_> = layer.iter().collect();
+    glyphs
+        .par_iter()
+        .map(|g| glyph_bounds(layer, g).map(|bounds| (g.name.clone(), bounds)))
+        .collect()
+}
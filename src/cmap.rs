@@ -0,0 +1,229 @@
+//! Mapping codepoints to glyphs, as in a font's `cmap` table.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use norad::Layer;
+use rayon::prelude::*;
+
+use crate::Error;
+
+/// A problem found while building a cmap.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CmapIssue {
+    /// Several glyphs have the same codepoint. The first one is mapped.
+    Duplicate { codepoint: u32, glyphs: Vec<String> },
+    /// A surrogate or a value above U+10FFFF, which are not characters.
+    Invalid { glyph: String, codepoint: u32 },
+    /// The glyph's name implies a codepoint the glyph doesn't have, e.g. a
+    /// glyph `uni0041` with U+0061.
+    NameMismatch {
+        glyph: String,
+        implied: u32,
+        codepoints: Vec<u32>,
+    },
+}
+
+impl CmapIssue {
+    /// A short, stable name for the kind of issue.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CmapIssue::Duplicate { .. } => "duplicate",
+            CmapIssue::Invalid { .. } => "invalid",
+            CmapIssue::NameMismatch { .. } => "name_mismatch",
+        }
+    }
+}
+
+fn format_codepoints(codepoints: &[u32]) -> String {
+    let codepoints: Vec<String> = codepoints.iter().map(|c| format!("U+{:04X}", c)).collect();
+    codepoints.join(", ")
+}
+
+impl Display for CmapIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmapIssue::Duplicate { codepoint, glyphs } => write!(
+                f,
+                "U+{:04X} is claimed by several glyphs: {}",
+                codepoint,
+                glyphs.join(", ")
+            ),
+            CmapIssue::Invalid { glyph, codepoint } => write!(
+                f,
+                "glyph '{}' has invalid codepoint U+{:04X}",
+                glyph, codepoint
+            ),
+            CmapIssue::NameMismatch {
+                glyph,
+                implied,
+                codepoints,
+            } => write!(
+                f,
+                "glyph '{}' is named for U+{:04X} but has {}",
+                glyph,
+                implied,
+                format_codepoints(codepoints)
+            ),
+        }
+    }
+}
+
+/// A mapping from codepoints to glyph names, with the problems found while
+/// building it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cmap {
+    pub mapping: BTreeMap<u32, String>,
+    pub issues: Vec<CmapIssue>,
+}
+
+fn is_valid(codepoint: u32) -> bool {
+    char::from_u32(codepoint).is_some()
+}
+
+fn agl_names() -> &'static HashMap<&'static str, u32> {
+    static NAMES: OnceLock<HashMap<&'static str, u32>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        glyph_names::GLYPH_NAME_PAIRS
+            .iter()
+            .map(|(c, name)| (*name, *c as u32))
+            .collect()
+    })
+}
+
+/// Parses `digits` as uppercase hexadecimal of a valid codepoint.
+fn hex_codepoint(digits: &str) -> Option<u32> {
+    if !digits
+        .bytes()
+        .all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b))
+    {
+        return None;
+    }
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .filter(|c| is_valid(*c))
+}
+
+/// Returns the codepoint a glyph name stands for following the Adobe Glyph
+/// List specification: `uniXXXX`, `uXXXX` to `uXXXXXX`, or a name from the
+/// Adobe Glyph List For New Fonts. Names with a suffix like `a.sc` or of
+/// ligatures like `f_i` imply no codepoint.
+pub fn implied_codepoint(name: &str) -> Option<u32> {
+    if name.contains(['.', '_']) {
+        return None;
+    }
+    if let Some(digits) = name.strip_prefix("uni") {
+        if digits.len() == 4 {
+            return hex_codepoint(digits);
+        }
+    }
+    if let Some(digits) = name.strip_prefix('u') {
+        if (4..=6).contains(&digits.len()) {
+            if let Some(codepoint) = hex_codepoint(digits) {
+                return Some(codepoint);
+            }
+        }
+    }
+    agl_names().get(name).copied()
+}
+
+/// Builds a cmap from glyph names and their codepoints, as written in the
+/// font, so possibly invalid.
+///
+/// Of several glyphs with the same codepoint, the one whose name sorts first
+/// is mapped.
+pub fn build_cmap(glyphs: impl IntoIterator<Item = (String, Vec<u32>)>) -> Cmap {
+    let mut glyphs: Vec<_> = glyphs.into_iter().collect();
+    glyphs.sort();
+    let mut claims: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    let mut issues = Vec::new();
+    for (glyph, codepoints) in glyphs {
+        for &codepoint in &codepoints {
+            if is_valid(codepoint) {
+                let claim = claims.entry(codepoint).or_default();
+                if !claim.contains(&glyph) {
+                    claim.push(glyph.clone());
+                }
+            } else {
+                issues.push(CmapIssue::Invalid {
+                    glyph: glyph.clone(),
+                    codepoint,
+                });
+            }
+        }
+        if let Some(implied) = implied_codepoint(&glyph) {
+            if !codepoints.is_empty() && !codepoints.contains(&implied) {
+                issues.push(CmapIssue::NameMismatch {
+                    glyph,
+                    implied,
+                    codepoints,
+                });
+            }
+        }
+    }
+
+    let mut mapping = BTreeMap::new();
+    for (codepoint, glyphs) in claims {
+        if glyphs.len() > 1 {
+            issues.push(CmapIssue::Duplicate {
+                codepoint,
+                glyphs: glyphs.clone(),
+            });
+        }
+        mapping.insert(codepoint, glyphs.into_iter().next().unwrap_or_default());
+    }
+    Cmap { mapping, issues }
+}
+
+/// Builds the cmap of `layer`.
+pub fn layer_cmap(layer: &Layer) -> Cmap {
+    build_cmap(layer.iter().map(|glyph| {
+        let codepoints = glyph.codepoints.iter().map(|c| *c as u32).collect();
+        (glyph.name.to_string(), codepoints)
+    }))
+}
+
+/// Returns the directory of the layer `name`, or of the default layer.
+fn layer_dir(ufo: &Path, name: Option<&str>) -> Result<String, Error> {
+    let path = ufo.join("layercontents.plist");
+    let layers: Vec<(String, String)> = if path.exists() {
+        plist::from_file(path)?
+    } else {
+        vec![("public.default".to_string(), "glyphs".to_string())]
+    };
+    let layer = match name {
+        Some(name) => layers.into_iter().find(|(n, _)| n == name),
+        None => layers.into_iter().find(|(_, dir)| dir == "glyphs"),
+    };
+    layer
+        .map(|(_, dir)| dir)
+        .ok_or_else(|| Error::MissingLayer(name.unwrap_or("public.default").to_string()))
+}
+
+/// Builds the cmap of a layer of the UFO at `path`, the default layer
+/// unless `layer` is given.
+///
+/// Only the codepoints of the glyphs are read from a UFO directory, which is
+/// faster than loading it and keeps invalid values that would make loading
+/// fail, so they can be reported.
+pub fn ufo_cmap(path: impl AsRef<Path>, layer: Option<&str>) -> Result<Cmap, Error> {
+    let path = path.as_ref();
+    if !path.is_dir() {
+        let font = crate::LoadOptions::none().layers(true).load(path)?;
+        return Ok(layer_cmap(crate::layer_or_default(&font, layer)?));
+    }
+    let dir = path.join(layer_dir(path, layer)?);
+    let contents: BTreeMap<String, String> = plist::from_file(dir.join("contents.plist"))?;
+    let glyphs = contents
+        .into_par_iter()
+        .map(|(name, file)| {
+            let file = dir.join(file);
+            let codepoints = crate::glif::codepoints(&fs::read(&file)?, &file)?;
+            Ok((name, codepoints))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(build_cmap(glyphs))
+}
@@ -0,0 +1,771 @@
+//! Combining two fonts, such as a Latin font and a Greek or Cyrillic
+//! extension, into one.
+//!
+//! Unlike [`crate::merge`], there is no common ancestor: everything "theirs"
+//! has that "ours" doesn't is added, and what both have but define
+//! differently is a conflict, resolved by a [`ConflictPolicy`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+use norad::{Font, Glyph, Line, Plist};
+use plist::Value;
+
+use crate::kerning::KERN1_PREFIX;
+use crate::merge::overlapping_groups;
+use crate::public_lib::{
+    ordered_glyphs, FontLib, GLYPH_ORDER_KEY, SKIP_EXPORT_GLYPHS_KEY, VERTICAL_ORIGIN_KEY,
+};
+use crate::rename::{self, rename_kerning};
+use crate::subset::remove_references;
+
+/// How to resolve a conflict.
+///
+/// Only glyphs and groups can be renamed; for the other conflicts,
+/// `Rename` keeps ours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep ours and drop theirs.
+    Keep,
+    /// Replace ours with theirs.
+    Replace,
+    /// Add theirs under a new name, made from the old one and this suffix.
+    Rename(String),
+}
+
+impl ConflictPolicy {
+    /// The resolution of a conflict about something that can't be renamed.
+    fn resolution(&self) -> Resolution {
+        match self {
+            ConflictPolicy::Replace => Resolution::TookTheirs,
+            _ => Resolution::KeptOurs,
+        }
+    }
+}
+
+/// Something both fonts define differently.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Glyph(String),
+    /// A codepoint of differently named glyphs.
+    Codepoint {
+        codepoint: char,
+        ours: String,
+        theirs: String,
+    },
+    /// The color or lib of a layer.
+    Layer(String),
+    Kerning {
+        first: String,
+        second: String,
+    },
+    Group(String),
+    /// Members of a kerning group of theirs that a kerning group of ours on
+    /// the same side has.
+    GroupMembers {
+        group: String,
+        glyphs: Vec<String>,
+    },
+    /// A key of the font lib.
+    Lib(String),
+    Features,
+}
+
+/// How a conflict was resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    KeptOurs,
+    TookTheirs,
+    /// Theirs was added under this name.
+    Renamed(String),
+}
+
+impl Resolution {
+    /// A short, stable name for the resolution.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Resolution::KeptOurs => "kept_ours",
+            Resolution::TookTheirs => "took_theirs",
+            Resolution::Renamed(_) => "renamed",
+        }
+    }
+}
+
+/// A conflict and how it was resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub item: Item,
+    pub resolution: Resolution,
+}
+
+impl Conflict {
+    /// A short, stable name for the kind of conflict.
+    pub fn kind(&self) -> &'static str {
+        match self.item {
+            Item::Glyph(_) => "glyph",
+            Item::Codepoint { .. } => "codepoint",
+            Item::Layer(_) => "layer",
+            Item::Kerning { .. } => "kerning",
+            Item::Group(_) | Item::GroupMembers { .. } => "group",
+            Item::Lib(_) => "lib",
+            Item::Features => "features",
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.item {
+            Item::Glyph(name) => write!(f, "glyph '{}'", name)?,
+            Item::Codepoint {
+                codepoint,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "codepoint U+{:04X} of our '{}' and their '{}'",
+                *codepoint as u32, ours, theirs
+            )?,
+            Item::Layer(name) => write!(f, "layer '{}'", name)?,
+            Item::Kerning { first, second } => write!(f, "kerning pair ({}, {})", first, second)?,
+            Item::Group(name) => write!(f, "group '{}'", name)?,
+            Item::GroupMembers { group, glyphs } => {
+                write!(f, "glyphs {} of group '{}'", glyphs.join(", "), group)?
+            }
+            Item::Lib(key) => write!(f, "lib key '{}'", key)?,
+            Item::Features => write!(f, "features")?,
+        }
+        match &self.resolution {
+            Resolution::KeptOurs => write!(f, ": kept ours"),
+            Resolution::TookTheirs => write!(f, ": took theirs"),
+            Resolution::Renamed(name) => write!(f, ": renamed theirs to '{}'", name),
+        }
+    }
+}
+
+/// The result of combining two fonts.
+#[derive(Debug, Clone)]
+pub struct Combined {
+    pub font: Font,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Returns the units per em of `font`, if set.
+fn units_per_em(font: &Font) -> Option<f64> {
+    font.font_info
+        .as_ref()
+        .and_then(|info| info.units_per_em.as_ref())
+        .map(|upm| upm.get())
+        .filter(|upm| *upm > 0.0)
+}
+
+/// Scales the outlines, metrics, anchors, guidelines and image of `glyph`.
+fn scale_glyph(glyph: &mut Glyph, factor: f32) {
+    glyph.width *= factor;
+    glyph.height *= factor;
+    for point in glyph.contours.iter_mut().flat_map(|c| c.points.iter_mut()) {
+        point.x *= factor;
+        point.y *= factor;
+    }
+    for component in &mut glyph.components {
+        component.transform.x_offset *= factor;
+        component.transform.y_offset *= factor;
+    }
+    for anchor in &mut glyph.anchors {
+        anchor.x *= factor;
+        anchor.y *= factor;
+    }
+    for guideline in &mut glyph.guidelines {
+        match &mut guideline.line {
+            Line::Vertical(v) | Line::Horizontal(v) => *v *= factor,
+            Line::Angle { x, y, .. } => {
+                *x *= factor;
+                *y *= factor;
+            }
+        }
+    }
+    if let Some(image) = &mut glyph.image {
+        let t = &mut image.transform;
+        for value in [
+            &mut t.x_scale,
+            &mut t.xy_scale,
+            &mut t.yx_scale,
+            &mut t.y_scale,
+            &mut t.x_offset,
+            &mut t.y_offset,
+        ] {
+            *value *= factor;
+        }
+    }
+    if let Some(origin) = glyph.lib.get_mut(VERTICAL_ORIGIN_KEY) {
+        if let Some(y) = origin.as_real() {
+            *origin = Value::Real(y * factor as f64);
+        } else if let Some(y) = origin.as_signed_integer() {
+            *origin = Value::Real(y as f64 * factor as f64);
+        }
+    }
+}
+
+/// Scales the glyphs of every layer and the kerning of `font`.
+fn scale_font(font: &mut Font, factor: f32) {
+    let names: Vec<String> = font.layers.names().map(|n| n.to_string()).collect();
+    for name in names {
+        if let Some(layer) = font.layers.get_mut(&name) {
+            layer
+                .iter_mut()
+                .for_each(|glyph| scale_glyph(glyph, factor));
+        }
+    }
+    for value in font
+        .kerning
+        .iter_mut()
+        .flat_map(|k| k.values_mut())
+        .flat_map(|s| s.values_mut())
+    {
+        *value *= factor;
+    }
+}
+
+/// Returns `name` with `suffix`, and a number if needed to make it unique
+/// among `taken`, which it is then added to.
+fn unique_name(name: &str, suffix: &str, taken: &mut BTreeSet<String>) -> String {
+    let mut unique = format!("{}{}", name, suffix);
+    let mut i = 2;
+    while !taken.insert(unique.clone()) {
+        unique = format!("{}{}{}", name, suffix, i);
+        i += 1;
+    }
+    unique
+}
+
+/// Returns the name of the layer of `ours` that the layer `name` of `theirs`
+/// goes into: ours' default layer for theirs, or else the same name.
+fn target_layer(ours: &Font, theirs: &Font, name: &str) -> String {
+    if theirs.layers.default_layer().name().as_ref() == name {
+        ours.layers.default_layer().name().to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the glyph names that both fonts have in a matching layer, with
+/// different glyphs.
+fn conflicting_glyphs(ours: &Font, theirs: &Font) -> BTreeSet<String> {
+    let mut conflicting = BTreeSet::new();
+    for layer in theirs.layers.iter() {
+        let target = match ours.layers.get(&target_layer(ours, theirs, layer.name())) {
+            Some(target) => target,
+            None => continue,
+        };
+        for glyph in layer.iter() {
+            if target.get_glyph(&*glyph.name).is_some_and(|g| g != glyph) {
+                conflicting.insert(glyph.name.to_string());
+            }
+        }
+    }
+    conflicting
+}
+
+/// Resolves the glyph conflicts, so that the glyph names the fonts share
+/// afterwards stand for the same glyphs.
+fn resolve_glyphs(
+    ours: &mut Font,
+    theirs: &mut Font,
+    policy: &ConflictPolicy,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let conflicting = conflicting_glyphs(ours, theirs);
+    if conflicting.is_empty() {
+        return;
+    }
+    let (loser, suffix) = match policy {
+        ConflictPolicy::Keep => (Some(&mut *theirs), None),
+        ConflictPolicy::Replace => (Some(&mut *ours), None),
+        ConflictPolicy::Rename(suffix) => (None, Some(suffix)),
+    };
+    if let Some(loser) = loser {
+        let names: Vec<String> = loser.layers.names().map(|n| n.to_string()).collect();
+        for name in names {
+            if let Some(layer) = loser.layers.get_mut(&name) {
+                conflicting.iter().for_each(|glyph| {
+                    layer.remove_glyph(glyph);
+                });
+            }
+        }
+        remove_references(loser, &conflicting);
+        conflicts.extend(conflicting.into_iter().map(|glyph| Conflict {
+            item: Item::Glyph(glyph),
+            resolution: policy.resolution(),
+        }));
+        return;
+    }
+
+    let suffix = suffix.unwrap();
+    let mut taken: BTreeSet<String> = ours
+        .layers
+        .iter()
+        .chain(theirs.layers.iter())
+        .flat_map(|layer| layer.iter().map(|g| g.name.to_string()))
+        .collect();
+    let rename: BTreeMap<String, String> = conflicting
+        .into_iter()
+        .map(|name| {
+            let new = unique_name(&name, suffix, &mut taken);
+            (name, new)
+        })
+        .collect();
+    rename::apply(theirs, &rename);
+    conflicts.extend(rename.into_iter().map(|(old, new)| Conflict {
+        item: Item::Glyph(old),
+        resolution: Resolution::Renamed(new),
+    }));
+}
+
+/// Adds the glyphs of `theirs` to the matching layers of `ours`, creating
+/// the layers ours doesn't have. Returns the names of the glyphs added to the
+/// default layer.
+fn add_glyphs(
+    ours: &mut Font,
+    theirs: &Font,
+    policy: &ConflictPolicy,
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<String> {
+    let mut added = Vec::new();
+    let default = ours.layers.default_layer().name().to_string();
+    for layer in theirs.layers.iter() {
+        let name = target_layer(ours, theirs, layer.name());
+        if let Some(target) = ours.layers.get(&name) {
+            if target.color != layer.color || target.lib != layer.lib {
+                conflicts.push(Conflict {
+                    item: Item::Layer(name.clone()),
+                    resolution: policy.resolution(),
+                });
+            }
+        }
+        let is_new = ours.layers.get(&name).is_none();
+        let target = ours.layers.get_or_create(&name);
+        if is_new || *policy == ConflictPolicy::Replace {
+            target.color = layer.color.clone();
+            target.lib = layer.lib.clone();
+        }
+        for glyph in layer.iter() {
+            if !target.contains_glyph(&glyph.name) {
+                target.insert_glyph(glyph.clone());
+                if name == default {
+                    added.push(glyph.name.to_string());
+                }
+            }
+        }
+    }
+    added
+}
+
+/// Resolves codepoints that the added glyphs of the default layer share with
+/// other glyphs, by removing them from one of the two.
+fn resolve_codepoints(
+    ours: &mut Font,
+    added: &[String],
+    policy: &ConflictPolicy,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let added_set: BTreeSet<&str> = added.iter().map(|n| n.as_str()).collect();
+    let layer = ours.layers.default_layer_mut();
+    let cmap: HashMap<char, String> = layer
+        .iter()
+        .filter(|g| !added_set.contains(&*g.name))
+        .flat_map(|g| g.codepoints.iter().map(move |c| (*c, g.name.to_string())))
+        .collect();
+    let mut clashes = Vec::new();
+    for name in added {
+        let glyph = match layer.get_glyph(name.as_str()) {
+            Some(glyph) => glyph,
+            None => continue,
+        };
+        for codepoint in &glyph.codepoints {
+            if let Some(existing) = cmap.get(codepoint) {
+                clashes.push((*codepoint, existing.clone(), name.clone()));
+            }
+        }
+    }
+    let resolution = policy.resolution();
+    for (codepoint, existing, name) in clashes {
+        let loser = match resolution {
+            Resolution::TookTheirs => &existing,
+            _ => &name,
+        };
+        if let Some(glyph) = layer.get_glyph_mut(loser.as_str()) {
+            glyph.codepoints.retain(|c| *c != codepoint);
+        }
+        conflicts.push(Conflict {
+            item: Item::Codepoint {
+                codepoint,
+                ours: existing,
+                theirs: name,
+            },
+            resolution: resolution.clone(),
+        });
+    }
+}
+
+/// Adds the groups of `theirs` to `ours`. Their kerning is updated for the
+/// renamed groups, and loses the pairs with groups whose members ours keeps.
+fn combine_groups(
+    ours: &mut Font,
+    theirs: &mut Font,
+    policy: &ConflictPolicy,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let their_groups = match theirs.groups.take() {
+        Some(groups) => groups,
+        None => return,
+    };
+    let our_groups = ours.groups.get_or_insert_with(Default::default);
+    let mut taken: BTreeSet<String> = our_groups
+        .keys()
+        .chain(their_groups.keys())
+        .cloned()
+        .collect();
+    let mut rename = BTreeMap::new();
+    let mut dropped = BTreeSet::new();
+    let mut added = BTreeSet::new();
+    for (name, members) in their_groups {
+        let our_members = match our_groups.get_mut(&name) {
+            None => {
+                our_groups.insert(name.clone(), members);
+                added.insert(name);
+                continue;
+            }
+            Some(our_members) if *our_members == members => continue,
+            Some(our_members) => our_members,
+        };
+        let resolution = match policy {
+            ConflictPolicy::Keep => {
+                dropped.insert(name.clone());
+                Resolution::KeptOurs
+            }
+            ConflictPolicy::Replace => {
+                *our_members = members;
+                added.insert(name.clone());
+                Resolution::TookTheirs
+            }
+            ConflictPolicy::Rename(suffix) => {
+                let new = unique_name(&name, suffix, &mut taken);
+                our_groups.insert(new.clone(), members);
+                rename.insert(name.clone(), new.clone());
+                added.insert(new.clone());
+                Resolution::Renamed(new)
+            }
+        };
+        conflicts.push(Conflict {
+            item: Item::Group(name),
+            resolution,
+        });
+    }
+
+    // A glyph can only be in one kerning group per side, so theirs lose the
+    // members that one of ours on the same side has.
+    let overlapping = overlapping_groups(our_groups);
+    let ours_members: BTreeSet<(bool, &str)> = our_groups
+        .iter()
+        .filter(|(name, _)| overlapping.contains(*name) && !added.contains(*name))
+        .flat_map(|(name, members)| {
+            let first = name.starts_with(KERN1_PREFIX);
+            members.iter().map(move |m| (first, &**m))
+        })
+        .collect();
+    let shared: Vec<(String, Vec<String>)> = overlapping
+        .intersection(&added)
+        .map(|name| {
+            let first = name.starts_with(KERN1_PREFIX);
+            let members = our_groups[name]
+                .iter()
+                .map(|m| m.to_string())
+                .filter(|m| ours_members.contains(&(first, m.as_str())))
+                .collect();
+            (name.clone(), members)
+        })
+        .collect();
+    let mut emptied = BTreeSet::new();
+    for (name, shared) in shared {
+        if let Some(members) = our_groups.get_mut(&name) {
+            members.retain(|m| !shared.iter().any(|s| s == &**m));
+            if members.is_empty() {
+                our_groups.remove(&name);
+                emptied.insert(name.clone());
+            }
+        }
+        conflicts.push(Conflict {
+            item: Item::GroupMembers {
+                group: name,
+                glyphs: shared,
+            },
+            resolution: Resolution::KeptOurs,
+        });
+    }
+
+    if let Some(kerning) = theirs.kerning.as_mut() {
+        kerning.retain(|first, _| !dropped.contains(first));
+        for seconds in kerning.values_mut() {
+            seconds.retain(|second, _| !dropped.contains(second));
+        }
+        rename_kerning(kerning, &rename);
+        kerning.retain(|first, _| !emptied.contains(first));
+        for seconds in kerning.values_mut() {
+            seconds.retain(|second, _| !emptied.contains(second));
+        }
+        kerning.retain(|_, seconds| !seconds.is_empty());
+    }
+}
+
+/// Adds the kerning pairs of `theirs` to `ours`.
+fn combine_kerning(
+    ours: &mut Font,
+    theirs: &mut Font,
+    policy: &ConflictPolicy,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let their_kerning = match theirs.kerning.take() {
+        Some(kerning) => kerning,
+        None => return,
+    };
+    let our_kerning = ours.kerning.get_or_insert_with(Default::default);
+    for (first, seconds) in their_kerning {
+        let our_seconds = our_kerning.entry(first.clone()).or_default();
+        for (second, value) in seconds {
+            match our_seconds.get_mut(&second) {
+                None => {
+                    our_seconds.insert(second, value);
+                }
+                Some(our_value) if *our_value == value => {}
+                Some(our_value) => {
+                    if *policy == ConflictPolicy::Replace {
+                        *our_value = value;
+                    }
+                    conflicts.push(Conflict {
+                        item: Item::Kerning {
+                            first: first.clone(),
+                            second,
+                        },
+                        resolution: policy.resolution(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Adds the keys of their font lib to ours. Dictionaries, such as
+/// `public.postscriptNames`, are combined entry by entry and
+/// `public.skipExportGlyphs` as a list; `public.glyphOrder` is left for
+/// [`combine_glyph_order`].
+fn combine_lib(ours: &mut Plist, theirs: Plist, policy: &ConflictPolicy) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let replace = *policy == ConflictPolicy::Replace;
+    for (key, value) in theirs {
+        if key == GLYPH_ORDER_KEY {
+            continue;
+        }
+        let our_value = match ours.get_mut(&key) {
+            None => {
+                ours.insert(key, value);
+                continue;
+            }
+            Some(our_value) if *our_value == value => continue,
+            Some(our_value) => our_value,
+        };
+        let conflicting = match (our_value, value) {
+            (Value::Array(our_items), Value::Array(items)) if key == SKIP_EXPORT_GLYPHS_KEY => {
+                for item in items {
+                    if !our_items.contains(&item) {
+                        our_items.push(item);
+                    }
+                }
+                false
+            }
+            (Value::Dictionary(our_dict), Value::Dictionary(dict)) => {
+                let mut conflicting = false;
+                for (entry, value) in dict {
+                    match our_dict.get(&entry) {
+                        None => {
+                            our_dict.insert(entry, value);
+                        }
+                        Some(our_value) if *our_value == value => {}
+                        Some(_) => {
+                            conflicting = true;
+                            if replace {
+                                our_dict.insert(entry, value);
+                            }
+                        }
+                    }
+                }
+                conflicting
+            }
+            (our_value, value) => {
+                if replace {
+                    *our_value = value;
+                }
+                true
+            }
+        };
+        if conflicting {
+            conflicts.push(Conflict {
+                item: Item::Lib(key),
+                resolution: policy.resolution(),
+            });
+        }
+    }
+    conflicts
+}
+
+/// Sets the `public.glyphOrder` of `ours`, if either font has one: the
+/// default layer's glyphs of the font whose conflicting glyphs won, ordered
+/// by [`ordered_glyphs`], followed by the other font's glyphs that aren't
+/// listed yet.
+fn combine_glyph_order(ours: &mut Font, theirs: &Font, policy: &ConflictPolicy) {
+    if !ours.lib.contains_key(GLYPH_ORDER_KEY) && !theirs.lib.contains_key(GLYPH_ORDER_KEY) {
+        return;
+    }
+    let order = |font: &Font| {
+        let glyph_order = FontLib::new(&font.lib).0.glyph_order.unwrap_or_default();
+        ordered_glyphs(font.default_layer(), &glyph_order)
+            .into_iter()
+            .map(|g| g.name.to_string())
+            .collect::<Vec<_>>()
+    };
+    let (mut first, second) = match policy {
+        ConflictPolicy::Replace => (order(theirs), order(ours)),
+        _ => (order(ours), order(theirs)),
+    };
+    let mut seen: BTreeSet<String> = first.iter().cloned().collect();
+    first.extend(second.into_iter().filter(|name| seen.insert(name.clone())));
+    ours.lib.insert(
+        GLYPH_ORDER_KEY.to_string(),
+        Value::Array(first.into_iter().map(Value::String).collect()),
+    );
+}
+
+/// Adds the glyphs, layers, kerning, groups and lib of `theirs` to `ours`.
+///
+/// Glyphs with the same name in a matching layer conflict if they differ;
+/// theirs' default layer matches ours and the other layers match by name.
+/// A conflicting glyph is resolved for all layers at once, and its kerning,
+/// group memberships and entries in the well-known lib keys go with it: with
+/// [`ConflictPolicy::Keep`] theirs are dropped, with
+/// [`ConflictPolicy::Replace`] ours are, and with [`ConflictPolicy::Rename`]
+/// their glyph is renamed along with the references to it. A codepoint that
+/// an added glyph shares with another glyph is then removed from the glyph
+/// that lost.
+///
+/// Groups, kerning pairs, the layers' colors and libs and the font lib keys
+/// conflict when both fonts have them with different values. Renamed groups
+/// keep their kerning; the kerning of their groups that lost is dropped. The
+/// kerning groups added from theirs lose the glyphs that a kerning group of
+/// ours on the same side has, which is a conflict kept by ours.
+/// `public.glyphOrder` combines both orders rather than conflicting.
+///
+/// If the fonts' `unitsPerEm` differ, their glyphs and kerning are scaled to
+/// ours first, without rounding. The fontinfo of ours is kept, as are its
+/// features unless the policy is `Replace`.
+pub fn combine_fonts(ours: &Font, theirs: &Font, policy: &ConflictPolicy) -> Combined {
+    let mut conflicts = Vec::new();
+    let mut font = ours.clone();
+    let mut theirs = theirs.clone();
+    if let (Some(our_upm), Some(their_upm)) = (units_per_em(ours), units_per_em(&theirs)) {
+        if our_upm != their_upm {
+            scale_font(&mut theirs, (our_upm / their_upm) as f32);
+        }
+    }
+
+    resolve_glyphs(&mut font, &mut theirs, policy, &mut conflicts);
+    let added = add_glyphs(&mut font, &theirs, policy, &mut conflicts);
+    resolve_codepoints(&mut font, &added, policy, &mut conflicts);
+    combine_groups(&mut font, &mut theirs, policy, &mut conflicts);
+    combine_kerning(&mut font, &mut theirs, policy, &mut conflicts);
+    combine_glyph_order(&mut font, &theirs, policy);
+    conflicts.extend(combine_lib(
+        &mut font.lib,
+        std::mem::take(&mut theirs.lib),
+        policy,
+    ));
+
+    match (&font.features, theirs.features) {
+        (None, features) => font.features = features,
+        (Some(ours), Some(features)) if *ours != features => {
+            if *policy == ConflictPolicy::Replace {
+                font.features = Some(features);
+            }
+            conflicts.push(Conflict {
+                item: Item::Features,
+                resolution: policy.resolution(),
+            });
+        }
+        _ => {}
+    }
+    Combined { font, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use norad::Groups;
+
+    use super::*;
+
+    fn font(glyphs: &[&str], groups: &[(&str, &[&str])]) -> Font {
+        let mut font = Font::new();
+        for name in glyphs {
+            font.default_layer_mut()
+                .insert_glyph(Glyph::new_named(*name));
+        }
+        let groups: Groups = groups
+            .iter()
+            .map(|(name, members)| {
+                (
+                    name.to_string(),
+                    members.iter().map(|&m| m.into()).collect(),
+                )
+            })
+            .collect();
+        font.groups = Some(groups);
+        font
+    }
+
+    #[test]
+    fn added_groups_lose_glyphs_of_ours() {
+        let ours = font(&["A"], &[("public.kern1.A", &["A"])]);
+        let theirs = font(
+            &["A", "Alpha"],
+            &[
+                ("public.kern1.round", &["A", "Alpha"]),
+                ("public.kern1.A2", &["A"]),
+                ("public.kern2.A", &["A"]),
+            ],
+        );
+
+        let combined = combine_fonts(&ours, &theirs, &ConflictPolicy::Keep);
+
+        let groups = combined.font.groups.unwrap();
+        let members = |name: &str| groups.get(name).map(|g| g.iter().map(|m| &**m).collect());
+        assert_eq!(members("public.kern1.A"), Some(vec!["A"]));
+        assert_eq!(members("public.kern1.round"), Some(vec!["Alpha"]));
+        assert_eq!(members("public.kern1.A2"), None);
+        assert_eq!(members("public.kern2.A"), Some(vec!["A"]));
+        let items: Vec<_> = combined.conflicts.iter().map(|c| &c.item).collect();
+        assert_eq!(
+            items,
+            [
+                &Item::GroupMembers {
+                    group: "public.kern1.A2".to_string(),
+                    glyphs: vec!["A".to_string()],
+                },
+                &Item::GroupMembers {
+                    group: "public.kern1.round".to_string(),
+                    glyphs: vec!["A".to_string()],
+                },
+            ]
+        );
+        assert!(combined
+            .conflicts
+            .iter()
+            .all(|c| c.resolution == Resolution::KeptOurs));
+    }
+}
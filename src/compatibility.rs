@@ -0,0 +1,316 @@
+//! Interpolation compatibility of glyphs across the masters of a design
+//! space.
+//!
+//! Every master is compared with the first one that has the glyph: the
+//! number of contours and their points, the point types, the components and
+//! the anchors must match for the glyph to interpolate.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use norad::{Contour, Font, Glyph, PointType};
+use rayon::prelude::*;
+
+use crate::Error;
+
+/// A way in which a glyph of a master differs from the same glyph of the
+/// first master that has it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// The glyph is missing from this master but present in another one.
+    MissingGlyph,
+    ContourCount {
+        expected: usize,
+        found: usize,
+    },
+    PointCount {
+        contour: usize,
+        expected: usize,
+        found: usize,
+    },
+    PointType {
+        contour: usize,
+        point: usize,
+        expected: PointType,
+        found: PointType,
+    },
+    ComponentCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The component at this index references another base glyph, which
+    /// includes components in a different order.
+    ComponentBase {
+        component: usize,
+        expected: String,
+        found: String,
+    },
+    /// The glyph has a different set of anchor names.
+    Anchors {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+    /// A closed contour likely starts at another point: the outline matches
+    /// the first master best when it starts at `point`.
+    StartPoint {
+        contour: usize,
+        point: usize,
+    },
+}
+
+impl Problem {
+    /// A short, stable name for the kind of problem.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Problem::MissingGlyph => "missing_glyph",
+            Problem::ContourCount { .. } => "contour_count",
+            Problem::PointCount { .. } => "point_count",
+            Problem::PointType { .. } => "point_type",
+            Problem::ComponentCount { .. } => "component_count",
+            Problem::ComponentBase { .. } => "component_base",
+            Problem::Anchors { .. } => "anchors",
+            Problem::StartPoint { .. } => "start_point",
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::MissingGlyph => write!(f, "glyph is missing"),
+            Problem::ContourCount { expected, found } => {
+                write!(f, "{} contours, expected {}", found, expected)
+            }
+            Problem::PointCount {
+                contour,
+                expected,
+                found,
+            } => write!(
+                f,
+                "contour {} has {} points, expected {}",
+                contour, found, expected
+            ),
+            Problem::PointType {
+                contour,
+                point,
+                expected,
+                found,
+            } => write!(
+                f,
+                "contour {} point {} is a {} point, expected {}",
+                contour, point, found, expected
+            ),
+            Problem::ComponentCount { expected, found } => {
+                write!(f, "{} components, expected {}", found, expected)
+            }
+            Problem::ComponentBase {
+                component,
+                expected,
+                found,
+            } => write!(
+                f,
+                "component {} is '{}', expected '{}'",
+                component, found, expected
+            ),
+            Problem::Anchors { expected, found } => write!(
+                f,
+                "anchors [{}], expected [{}]",
+                found.join(", "),
+                expected.join(", ")
+            ),
+            Problem::StartPoint { contour, point } => write!(
+                f,
+                "contour {} probably should start at point {}",
+                contour, point
+            ),
+        }
+    }
+}
+
+/// An incompatibility of a glyph in one master.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incompatibility {
+    pub glyph: String,
+    /// The index of the master in the list that was checked.
+    pub master: usize,
+    pub problem: Problem,
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "glyph '{}' in master {}: {}",
+            self.glyph, self.master, self.problem
+        )
+    }
+}
+
+fn anchor_names(glyph: &Glyph) -> Vec<String> {
+    let mut names: Vec<String> = glyph
+        .anchors
+        .iter()
+        .map(|a| a.name.clone().unwrap_or_default())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Returns the points of `contour` relative to their center.
+fn centered(contour: &Contour) -> Vec<(f64, f64)> {
+    let n = contour.points.len() as f64;
+    let (sx, sy) = contour
+        .points
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), p| (sx + p.x as f64, sy + p.y as f64));
+    contour
+        .points
+        .iter()
+        .map(|p| (p.x as f64 - sx / n, p.y as f64 - sy / n))
+        .collect()
+}
+
+/// Returns the point `contour` should start at to best match `reference`, if
+/// it is not its first point. Both contours must be closed and have the same
+/// point types.
+fn better_start(reference: &Contour, contour: &Contour) -> Option<usize> {
+    let (a, b) = (centered(reference), centered(contour));
+    let n = a.len();
+    let cost = |shift: usize| -> f64 {
+        (0..n)
+            .map(|i| {
+                let (p, q) = (a[i], b[(i + shift) % n]);
+                (p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)
+            })
+            .sum()
+    };
+    let types_match = |shift: usize| {
+        (0..n).all(|i| reference.points[i].typ == contour.points[(i + shift) % n].typ)
+    };
+    let current = cost(0);
+    let (best, best_cost) = (1..n)
+        .filter(|&shift| types_match(shift))
+        .map(|shift| (shift, cost(shift)))
+        .min_by(|x, y| x.1.total_cmp(&y.1))?;
+    // Only report clear cases; shapes that differ a lot between masters
+    // don't have a meaningful best match.
+    (best_cost < current * 0.5).then_some(best)
+}
+
+fn check_contours(reference: &Glyph, glyph: &Glyph, problems: &mut Vec<Problem>) {
+    if reference.contours.len() != glyph.contours.len() {
+        problems.push(Problem::ContourCount {
+            expected: reference.contours.len(),
+            found: glyph.contours.len(),
+        });
+        return;
+    }
+    for (i, (a, b)) in reference.contours.iter().zip(&glyph.contours).enumerate() {
+        if a.points.len() != b.points.len() {
+            problems.push(Problem::PointCount {
+                contour: i,
+                expected: a.points.len(),
+                found: b.points.len(),
+            });
+            continue;
+        }
+        let count = problems.len();
+        for (point, (p, q)) in a.points.iter().zip(&b.points).enumerate() {
+            if p.typ != q.typ {
+                problems.push(Problem::PointType {
+                    contour: i,
+                    point,
+                    expected: p.typ.clone(),
+                    found: q.typ.clone(),
+                });
+            }
+        }
+        if problems.len() > count {
+            continue;
+        }
+        let closed = a.points.first().is_some_and(|p| p.typ != PointType::Move);
+        if let Some(point) = closed.then(|| better_start(a, b)).flatten() {
+            problems.push(Problem::StartPoint { contour: i, point });
+        }
+    }
+}
+
+/// Returns the problems with interpolating `glyph` with `reference`, the same
+/// glyph in another master.
+pub fn check_glyph(reference: &Glyph, glyph: &Glyph) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    check_contours(reference, glyph, &mut problems);
+
+    let (a, b) = (&reference.components, &glyph.components);
+    if a.len() != b.len() {
+        problems.push(Problem::ComponentCount {
+            expected: a.len(),
+            found: b.len(),
+        });
+    } else {
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            if a.base != b.base {
+                problems.push(Problem::ComponentBase {
+                    component: i,
+                    expected: a.base.to_string(),
+                    found: b.base.to_string(),
+                });
+            }
+        }
+    }
+
+    let (expected, found) = (anchor_names(reference), anchor_names(glyph));
+    if expected != found {
+        problems.push(Problem::Anchors { expected, found });
+    }
+    problems
+}
+
+/// Checks that the glyphs of the default layers of `masters` are compatible
+/// for interpolation, comparing every master with the first one that has the
+/// glyph. Returns the incompatibilities sorted by glyph name and master.
+pub fn check_fonts(masters: &[&Font]) -> Vec<Incompatibility> {
+    let names: BTreeSet<&str> = masters
+        .iter()
+        .flat_map(|font| font.default_layer().iter().map(|g| g.name.as_ref()))
+        .collect();
+    let names: Vec<&str> = names.into_iter().collect();
+    names
+        .par_iter()
+        .flat_map_iter(|&name| {
+            let glyphs: Vec<Option<&Glyph>> = masters
+                .iter()
+                .map(|font| font.default_layer().get_glyph(name).map(|g| &**g))
+                .collect();
+            // The glyph is in at least one master, as its name was found.
+            let first = glyphs.iter().position(Option::is_some).unwrap_or(0);
+            let mut found = Vec::new();
+            for (master, glyph) in glyphs.iter().enumerate() {
+                let problems = match (glyph, glyphs[first]) {
+                    (None, _) => vec![Problem::MissingGlyph],
+                    (Some(glyph), Some(reference)) if master != first => {
+                        check_glyph(reference, glyph)
+                    }
+                    _ => Vec::new(),
+                };
+                found.extend(problems.into_iter().map(|problem| Incompatibility {
+                    glyph: name.to_string(),
+                    master,
+                    problem,
+                }));
+            }
+            found
+        })
+        .collect()
+}
+
+/// Loads the UFOs at `paths` in parallel and checks them with
+/// [`check_fonts`].
+pub fn check_ufos<P: AsRef<Path> + Sync>(paths: &[P]) -> Result<Vec<Incompatibility>, Error> {
+    let fonts: Vec<Font> = paths
+        .par_iter()
+        .map(|path| crate::load(path.as_ref()))
+        .collect::<Result<_, _>>()?;
+    Ok(check_fonts(&fonts.iter().collect::<Vec<_>>()))
+}
@@ -0,0 +1,173 @@
+//! The graph of component references between the glyphs of a layer.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use norad::Layer;
+
+use crate::decompose::DecomposeError;
+
+/// Which glyphs use which others as components, in both directions.
+///
+/// Answers questions like "which glyphs use `acutecomb`?" or "what must be
+/// rebuilt if `o` changes?", and orders glyphs so that base glyphs come before
+/// the composites using them.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentGraph {
+    /// The base glyphs of every glyph, in component order and without
+    /// duplicates. Glyphs without components have an empty list.
+    bases: BTreeMap<String, Vec<String>>,
+    /// The glyphs using each glyph as a component, including glyphs that are
+    /// referenced but missing.
+    users: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ComponentGraph {
+    /// Builds the graph of the glyphs in `layer`.
+    pub fn new(layer: &Layer) -> Self {
+        Self::from_components(layer.iter().map(|glyph| {
+            let bases = glyph.components.iter().map(|c| c.base.to_string());
+            (glyph.name.to_string(), bases.collect())
+        }))
+    }
+
+    /// Builds a graph from glyph names and the base glyphs of their components.
+    pub fn from_components(components: impl IntoIterator<Item = (String, Vec<String>)>) -> Self {
+        let mut graph = ComponentGraph::default();
+        for (glyph, bases) in components {
+            let mut unique: Vec<String> = Vec::new();
+            for base in bases {
+                if !unique.contains(&base) {
+                    unique.push(base);
+                }
+            }
+            for base in &unique {
+                graph
+                    .users
+                    .entry(base.clone())
+                    .or_default()
+                    .insert(glyph.clone());
+            }
+            graph.bases.insert(glyph, unique);
+        }
+        graph
+    }
+
+    /// Returns true if `glyph` is in the graph, i.e. in the layer.
+    pub fn contains(&self, glyph: &str) -> bool {
+        self.bases.contains_key(glyph)
+    }
+
+    /// Returns the glyphs `glyph` uses as components, in component order.
+    pub fn bases(&self, glyph: &str) -> &[String] {
+        self.bases.get(glyph).map_or(&[], |b| b.as_slice())
+    }
+
+    /// Returns the glyphs using `glyph` as a component.
+    pub fn users(&self, glyph: &str) -> BTreeSet<&str> {
+        self.users
+            .get(glyph)
+            .into_iter()
+            .flatten()
+            .map(|u| u.as_str())
+            .collect()
+    }
+
+    /// Returns the glyphs `glyph` depends on through nested components.
+    pub fn all_bases(&self, glyph: &str) -> BTreeSet<&str> {
+        self.reachable(glyph, |g| {
+            self.bases(g).iter().map(|b| b.as_str()).collect()
+        })
+    }
+
+    /// Returns the glyphs using `glyph` directly or through nested
+    /// components, i.e. the composites to rebuild when `glyph` changes.
+    pub fn all_users(&self, glyph: &str) -> BTreeSet<&str> {
+        self.reachable(glyph, |g| self.users(g).into_iter().collect())
+    }
+
+    fn reachable<'a>(
+        &'a self,
+        glyph: &str,
+        next: impl Fn(&str) -> Vec<&'a str>,
+    ) -> BTreeSet<&'a str> {
+        let mut found = BTreeSet::new();
+        let mut stack = next(glyph);
+        while let Some(g) = stack.pop() {
+            if found.insert(g) {
+                stack.extend(next(g));
+            }
+        }
+        found
+    }
+
+    /// Returns the components referencing glyphs that are not in the layer,
+    /// as `(glyph, base)` pairs.
+    pub fn missing_bases(&self) -> Vec<(&str, &str)> {
+        self.bases
+            .iter()
+            .flat_map(|(glyph, bases)| {
+                bases
+                    .iter()
+                    .filter(|b| !self.contains(b))
+                    .map(move |b| (glyph.as_str(), b.as_str()))
+            })
+            .collect()
+    }
+
+    /// Visits the glyphs depth-first, appending each one to `order` after its
+    /// bases and recording every chain of components that leads back to a
+    /// glyph on the current path.
+    fn visit<'a>(
+        &'a self,
+        glyph: &'a str,
+        path: &mut Vec<&'a str>,
+        done: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if done.contains(glyph) {
+            return;
+        }
+        if let Some(start) = path.iter().position(|g| *g == glyph) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|g| g.to_string()).collect();
+            cycle.push(glyph.to_string());
+            cycles.push(cycle);
+            return;
+        }
+        path.push(glyph);
+        for base in self.bases(glyph) {
+            if self.contains(base) {
+                self.visit(base, path, done, order, cycles);
+            }
+        }
+        path.pop();
+        done.insert(glyph);
+        order.push(glyph);
+    }
+
+    fn walk(&self) -> (Vec<&str>, Vec<Vec<String>>) {
+        let (mut done, mut order, mut cycles) = (BTreeSet::new(), Vec::new(), Vec::new());
+        for glyph in self.bases.keys() {
+            self.visit(glyph, &mut Vec::new(), &mut done, &mut order, &mut cycles);
+        }
+        (order, cycles)
+    }
+
+    /// Returns the chains of components that refer back to themselves. The
+    /// first and last names of each chain are the same glyph.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        self.walk().1
+    }
+
+    /// Returns all glyphs ordered so that every glyph comes after the glyphs
+    /// it uses as components. Fails if there is a component cycle.
+    pub fn topological_order(&self) -> Result<Vec<&str>, DecomposeError> {
+        let (order, cycles) = self.walk();
+        match cycles.into_iter().next() {
+            Some(cycle) => Err(DecomposeError::Cycle(
+                cycle.into_iter().map(|g| g.into()).collect(),
+            )),
+            None => Ok(order),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use norad::{AffineTransform, Contour, ContourPoint, Glyph, GlyphName, Layer};
+use rayon::prelude::*;
+
+/// Errors that can occur while resolving component references.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecomposeError {
+    /// A component references a glyph that is not in the layer.
+    MissingBase { glyph: GlyphName, base: GlyphName },
+    /// A chain of components that refers back to itself. The first and last
+    /// names of the chain are the same glyph.
+    Cycle(Vec<GlyphName>),
+}
+
+impl std::fmt::Display for DecomposeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecomposeError::MissingBase { glyph, base } => write!(
+                f,
+                "glyph '{}' references missing base glyph '{}'",
+                glyph, base
+            ),
+            DecomposeError::Cycle(chain) => write!(
+                f,
+                "component cycle: {}",
+                chain
+                    .iter()
+                    .map(|n| n.as_ref())
+                    .collect::<Vec<&str>>()
+                    .join(" -> ")
+            ),
+        }
+    }
+}
+
+/// Which glyphs of a layer should be decomposed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecomposeFilter {
+    /// Decompose every glyph that has components.
+    All,
+    /// Decompose only glyphs with at least one flipped component (negative
+    /// determinant) or glyphs that mix contours and components.
+    FlippedOrMixed,
+}
+
+impl DecomposeFilter {
+    fn matches(&self, glyph: &Glyph) -> bool {
+        if glyph.components.is_empty() {
+            return false;
+        }
+        match self {
+            DecomposeFilter::All => true,
+            DecomposeFilter::FlippedOrMixed => {
+                !glyph.contours.is_empty()
+                    || glyph.components.iter().any(|c| is_flipped(&c.transform))
+            }
+        }
+    }
+}
+
+/// Returns true if the transformation mirrors the outline, i.e. the
+/// determinant of its 2x2 part is negative.
+pub fn is_flipped(t: &AffineTransform) -> bool {
+    t.x_scale * t.y_scale - t.xy_scale * t.yx_scale < 0.0
+}
+
+/// Returns the transformation that applies `inner` first and then `outer`.
+fn compose(outer: &AffineTransform, inner: &AffineTransform) -> AffineTransform {
+    let (o, i) = (outer, inner);
+    AffineTransform {
+        x_scale: o.x_scale * i.x_scale + o.yx_scale * i.xy_scale,
+        xy_scale: o.xy_scale * i.x_scale + o.y_scale * i.xy_scale,
+        yx_scale: o.x_scale * i.yx_scale + o.yx_scale * i.y_scale,
+        y_scale: o.xy_scale * i.yx_scale + o.y_scale * i.y_scale,
+        x_offset: o.x_scale * i.x_offset + o.yx_scale * i.y_offset + o.x_offset,
+        y_offset: o.xy_scale * i.x_offset + o.y_scale * i.y_offset + o.y_offset,
+    }
+}
+
+/// Copies a contour with `transform` applied to all its points.
+///
+/// Identifiers and libs are dropped, as the same base contour can end up in a
+/// glyph several times.
+fn transform_contour(contour: &Contour, transform: &AffineTransform) -> Contour {
+    let points = contour
+        .points
+        .iter()
+        .map(|p| {
+            let mut point = ContourPoint::new(
+                p.x,
+                p.y,
+                p.typ.clone(),
+                p.smooth,
+                p.name.clone(),
+                None,
+                None,
+            );
+            point.transform(*transform);
+            point
+        })
+        .collect();
+    Contour::new(points, None, None)
+}
+
+fn flatten_into(
+    layer: &Layer,
+    glyph: &Glyph,
+    transform: &AffineTransform,
+    chain: &mut Vec<GlyphName>,
+    out: &mut Vec<Contour>,
+) -> Result<(), DecomposeError> {
+    for component in &glyph.components {
+        if let Some(start) = chain.iter().position(|n| *n == component.base) {
+            let mut cycle = chain[start..].to_vec();
+            cycle.push(component.base.clone());
+            return Err(DecomposeError::Cycle(cycle));
+        }
+        let missing = || DecomposeError::MissingBase {
+            glyph: glyph.name.clone(),
+            base: component.base.clone(),
+        };
+        let base = layer.get_glyph(&*component.base).ok_or_else(missing)?;
+        let transform = compose(transform, &component.transform);
+        out.extend(
+            base.contours
+                .iter()
+                .map(|c| transform_contour(c, &transform)),
+        );
+        chain.push(base.name.clone());
+        flatten_into(layer, base, &transform, chain, out)?;
+        chain.pop();
+    }
+    Ok(())
+}
+
+/// Returns the outline of `glyph` with all components, including nested ones,
+/// replaced by transformed copies of their base glyph's contours.
+///
+/// The glyph's own contours come first, followed by the contours of each
+/// component in order.
+pub fn decompose_glyph(layer: &Layer, glyph: &Glyph) -> Result<Vec<Contour>, DecomposeError> {
+    let mut contours = glyph.contours.clone();
+    let mut chain = vec![glyph.name.clone()];
+    flatten_into(
+        layer,
+        glyph,
+        &AffineTransform::default(),
+        &mut chain,
+        &mut contours,
+    )?;
+    Ok(contours)
+}
+
+/// Decomposes all glyphs of `layer` selected by `filter`, in parallel.
+///
+/// Glyphs without components or not matched by the filter are not part of the
+/// result.
+pub fn decompose_layer(
+    layer: &Layer,
+    filter: DecomposeFilter,
+) -> Result<BTreeMap<GlyphName, Vec<Contour>>, DecomposeError> {
+    let glyphs: Vec<_> = layer.iter().filter(|g| filter.matches(g)).collect();
+    glyphs
+        .par_iter()
+        .map(|g| decompose_glyph(layer, g).map(|contours| (g.name.clone(), contours)))
+        .collect()
+}
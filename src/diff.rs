@@ -0,0 +1,652 @@
+//! Structured differences between two fonts.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Write};
+use std::path::Path;
+
+use norad::{Font, FontInfo, Glyph, Layer, Plist};
+
+use crate::Error;
+
+/// Whether something was added, removed or changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Added => "added",
+            Status::Removed => "removed",
+            Status::Modified => "modified",
+        }
+    }
+
+    fn sign(&self) -> char {
+        match self {
+            Status::Added => '+',
+            Status::Removed => '-',
+            Status::Modified => '~',
+        }
+    }
+}
+
+/// A changed entry of a dictionary-like structure such as a lib or the
+/// fontinfo. `None` means the key is not set on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub key: String,
+    pub old: Option<plist::Value>,
+    pub new: Option<plist::Value>,
+}
+
+/// A change to one aspect of a glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlyphChange {
+    Width {
+        old: f32,
+        new: f32,
+    },
+    Height {
+        old: f32,
+        new: f32,
+    },
+    Unicodes {
+        old: Vec<char>,
+        new: Vec<char>,
+    },
+    /// The outline changed; the numbers of contours before and after.
+    Contours {
+        old: usize,
+        new: usize,
+    },
+    /// Components were added, removed, reordered or transformed; the base
+    /// glyphs before and after.
+    Components {
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    /// Anchors were changed, matched by name.
+    Anchors {
+        added: Vec<String>,
+        removed: Vec<String>,
+        moved: Vec<String>,
+    },
+    /// The guidelines changed; the numbers of guidelines before and after.
+    Guidelines {
+        old: usize,
+        new: usize,
+    },
+    Image,
+    Note {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Lib(ValueChange),
+}
+
+impl GlyphChange {
+    /// A short, stable name for the kind of change.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GlyphChange::Width { .. } => "width",
+            GlyphChange::Height { .. } => "height",
+            GlyphChange::Unicodes { .. } => "unicodes",
+            GlyphChange::Contours { .. } => "contours",
+            GlyphChange::Components { .. } => "components",
+            GlyphChange::Anchors { .. } => "anchors",
+            GlyphChange::Guidelines { .. } => "guidelines",
+            GlyphChange::Image => "image",
+            GlyphChange::Note { .. } => "note",
+            GlyphChange::Lib(_) => "lib",
+        }
+    }
+}
+
+/// An added, removed or modified glyph. Only modified glyphs have changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphDiff {
+    pub name: String,
+    pub status: Status,
+    pub changes: Vec<GlyphChange>,
+}
+
+/// The differences in a layer. Glyphs are sorted by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerDiff {
+    pub name: String,
+    pub status: Status,
+    pub color: Option<ValueChange>,
+    pub lib: Vec<ValueChange>,
+    pub glyphs: Vec<GlyphDiff>,
+}
+
+/// A kerning pair that was added (`old` is `None`), removed (`new` is
+/// `None`) or changed its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerningChange {
+    pub first: String,
+    pub second: String,
+    pub old: Option<f32>,
+    pub new: Option<f32>,
+}
+
+/// A group whose members changed. A modified group without added or removed
+/// members was reordered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupChange {
+    pub name: String,
+    pub status: Status,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The feature code before and after.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeaturesChange {
+    pub old: String,
+    pub new: String,
+}
+
+impl FeaturesChange {
+    /// The numbers of lines added and removed, ignoring their order.
+    pub fn line_counts(&self) -> (usize, usize) {
+        let mut lines: BTreeMap<&str, isize> = BTreeMap::new();
+        for line in self.new.lines() {
+            *lines.entry(line).or_default() += 1;
+        }
+        for line in self.old.lines() {
+            *lines.entry(line).or_default() -= 1;
+        }
+        let added = lines.values().filter(|n| **n > 0).sum::<isize>();
+        let removed = -lines.values().filter(|n| **n < 0).sum::<isize>();
+        (added as usize, removed as usize)
+    }
+}
+
+/// The differences between two fonts.
+///
+/// Its `Display` implementation gives a human-readable summary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontDiff {
+    /// The layers that differ, in the order of the new font followed by the
+    /// removed layers.
+    pub layers: Vec<LayerDiff>,
+    /// Changed fontinfo attributes, using the names of `fontinfo.plist`.
+    pub info: Vec<ValueChange>,
+    pub lib: Vec<ValueChange>,
+    /// Changed kerning pairs, sorted by pair.
+    pub kerning: Vec<KerningChange>,
+    /// Changed groups, sorted by name.
+    pub groups: Vec<GroupChange>,
+    pub features: Option<FeaturesChange>,
+}
+
+impl FontDiff {
+    /// Whether the fonts are the same.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+            && self.info.is_empty()
+            && self.lib.is_empty()
+            && self.kerning.is_empty()
+            && self.groups.is_empty()
+            && self.features.is_none()
+    }
+}
+
+fn diff_dicts(old: &Plist, new: &Plist) -> Vec<ValueChange> {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|key| old.get(key) != new.get(key))
+        .map(|key| ValueChange {
+            key: key.clone(),
+            old: old.get(key).cloned(),
+            new: new.get(key).cloned(),
+        })
+        .collect()
+}
+
+fn info_dict(info: Option<&FontInfo>) -> Plist {
+    info.and_then(|info| plist::to_value(info).ok())
+        .and_then(plist::Value::into_dictionary)
+        .unwrap_or_default()
+}
+
+fn diff_anchors(old: &Glyph, new: &Glyph) -> Option<GlyphChange> {
+    if old.anchors == new.anchors {
+        return None;
+    }
+    let by_name = |glyph: &Glyph| -> BTreeMap<String, (f32, f32)> {
+        glyph
+            .anchors
+            .iter()
+            .map(|a| (a.name.clone().unwrap_or_default(), (a.x, a.y)))
+            .collect()
+    };
+    let (old, new) = (by_name(old), by_name(new));
+    let added = new.keys().filter(|n| !old.contains_key(*n));
+    let removed = old.keys().filter(|n| !new.contains_key(*n));
+    let moved = new
+        .iter()
+        .filter(|(n, pos)| old.get(*n).is_some_and(|old| old != *pos))
+        .map(|(n, _)| n);
+    Some(GlyphChange::Anchors {
+        added: added.cloned().collect(),
+        removed: removed.cloned().collect(),
+        moved: moved.cloned().collect(),
+    })
+}
+
+/// Returns the changes between two versions of a glyph.
+pub fn diff_glyphs(old: &Glyph, new: &Glyph) -> Vec<GlyphChange> {
+    let mut changes = Vec::new();
+    if old.width != new.width {
+        changes.push(GlyphChange::Width {
+            old: old.width,
+            new: new.width,
+        });
+    }
+    if old.height != new.height {
+        changes.push(GlyphChange::Height {
+            old: old.height,
+            new: new.height,
+        });
+    }
+    if old.codepoints != new.codepoints {
+        changes.push(GlyphChange::Unicodes {
+            old: old.codepoints.clone(),
+            new: new.codepoints.clone(),
+        });
+    }
+    if old.contours != new.contours {
+        changes.push(GlyphChange::Contours {
+            old: old.contours.len(),
+            new: new.contours.len(),
+        });
+    }
+    if old.components != new.components {
+        let bases = |g: &Glyph| g.components.iter().map(|c| c.base.to_string()).collect();
+        changes.push(GlyphChange::Components {
+            old: bases(old),
+            new: bases(new),
+        });
+    }
+    changes.extend(diff_anchors(old, new));
+    if old.guidelines != new.guidelines {
+        changes.push(GlyphChange::Guidelines {
+            old: old.guidelines.len(),
+            new: new.guidelines.len(),
+        });
+    }
+    if old.image != new.image {
+        changes.push(GlyphChange::Image);
+    }
+    if old.note != new.note {
+        changes.push(GlyphChange::Note {
+            old: old.note.clone(),
+            new: new.note.clone(),
+        });
+    }
+    changes.extend(
+        diff_dicts(&old.lib, &new.lib)
+            .into_iter()
+            .map(GlyphChange::Lib),
+    );
+    changes
+}
+
+fn glyph_names(layer: Option<&Layer>) -> BTreeSet<&str> {
+    layer
+        .into_iter()
+        .flat_map(|l| l.iter().map(|g| g.name.as_ref()))
+        .collect()
+}
+
+/// Returns the differences between two versions of a layer, either of which
+/// may be missing.
+fn diff_layers(name: &str, old: Option<&Layer>, new: Option<&Layer>) -> Option<LayerDiff> {
+    let status = match (old, new) {
+        (None, _) => Status::Added,
+        (_, None) => Status::Removed,
+        _ => Status::Modified,
+    };
+    let names: BTreeSet<&str> = glyph_names(old).union(&glyph_names(new)).copied().collect();
+    let mut glyphs = Vec::new();
+    for name in names {
+        let old = old.and_then(|l| l.get_glyph(name));
+        let new = new.and_then(|l| l.get_glyph(name));
+        let (status, changes) = match (old, new) {
+            (Some(old), Some(new)) => (Status::Modified, diff_glyphs(old, new)),
+            (None, _) => (Status::Added, Vec::new()),
+            (_, None) => (Status::Removed, Vec::new()),
+        };
+        if status == Status::Modified && changes.is_empty() {
+            continue;
+        }
+        glyphs.push(GlyphDiff {
+            name: name.to_string(),
+            status,
+            changes,
+        });
+    }
+
+    let empty = Plist::new();
+    let lib = diff_dicts(
+        old.map_or(&empty, |l| &l.lib),
+        new.map_or(&empty, |l| &l.lib),
+    );
+    let color = |layer: Option<&Layer>| {
+        layer
+            .and_then(|l| l.color.as_ref())
+            .map(|c| plist::Value::String(c.to_rgba_string()))
+    };
+    let (old_color, new_color) = (color(old), color(new));
+    let color = if old_color != new_color {
+        Some(ValueChange {
+            key: "color".to_string(),
+            old: old_color,
+            new: new_color,
+        })
+    } else {
+        None
+    };
+
+    if status == Status::Modified && glyphs.is_empty() && lib.is_empty() && color.is_none() {
+        return None;
+    }
+    Some(LayerDiff {
+        name: name.to_string(),
+        status,
+        color,
+        lib,
+        glyphs,
+    })
+}
+
+fn diff_kerning(old: &Font, new: &Font) -> Vec<KerningChange> {
+    let pairs = |font: &Font| -> BTreeMap<(String, String), f32> {
+        let mut pairs = BTreeMap::new();
+        for (first, seconds) in font.kerning.iter().flatten() {
+            for (second, value) in seconds {
+                pairs.insert((first.clone(), second.clone()), *value);
+            }
+        }
+        pairs
+    };
+    let (old, new) = (pairs(old), pairs(new));
+    let keys: BTreeSet<&(String, String)> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|pair| old.get(pair) != new.get(pair))
+        .map(|pair| KerningChange {
+            first: pair.0.clone(),
+            second: pair.1.clone(),
+            old: old.get(pair).copied(),
+            new: new.get(pair).copied(),
+        })
+        .collect()
+}
+
+fn diff_groups(old: &Font, new: &Font) -> Vec<GroupChange> {
+    let empty = norad::Groups::new();
+    let old = old.groups.as_ref().unwrap_or(&empty);
+    let new = new.groups.as_ref().unwrap_or(&empty);
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut changes = Vec::new();
+    for name in names {
+        let (old, new) = (old.get(name), new.get(name));
+        if old == new {
+            continue;
+        }
+        let status = match (old, new) {
+            (None, _) => Status::Added,
+            (_, None) => Status::Removed,
+            _ => Status::Modified,
+        };
+        let (old, new) = (
+            old.cloned().unwrap_or_default(),
+            new.cloned().unwrap_or_default(),
+        );
+        changes.push(GroupChange {
+            name: name.clone(),
+            status,
+            added: new
+                .iter()
+                .filter(|g| !old.contains(g))
+                .map(|g| g.to_string())
+                .collect(),
+            removed: old
+                .iter()
+                .filter(|g| !new.contains(g))
+                .map(|g| g.to_string())
+                .collect(),
+        });
+    }
+    changes
+}
+
+/// Returns the differences between `old` and `new`.
+pub fn diff_fonts(old: &Font, new: &Font) -> FontDiff {
+    let mut names: Vec<&str> = new.layers.names().map(|n| n.as_ref()).collect();
+    names.extend(
+        old.layers
+            .names()
+            .map(|n| n.as_ref())
+            .filter(|n| new.layers.get(n).is_none()),
+    );
+    let layers = names
+        .into_iter()
+        .filter_map(|name| diff_layers(name, old.layers.get(name), new.layers.get(name)))
+        .collect();
+
+    let old_features = old.features.as_deref().unwrap_or("");
+    let new_features = new.features.as_deref().unwrap_or("");
+    let features = if old_features != new_features {
+        Some(FeaturesChange {
+            old: old_features.to_string(),
+            new: new_features.to_string(),
+        })
+    } else {
+        None
+    };
+
+    FontDiff {
+        layers,
+        info: diff_dicts(
+            &info_dict(old.font_info.as_ref()),
+            &info_dict(new.font_info.as_ref()),
+        ),
+        lib: diff_dicts(&old.lib, &new.lib),
+        kerning: diff_kerning(old, new),
+        groups: diff_groups(old, new),
+        features,
+    }
+}
+
+/// Loads the UFOs at `old` and `new` and returns their differences.
+pub fn diff_ufos(old: impl AsRef<Path>, new: impl AsRef<Path>) -> Result<FontDiff, Error> {
+    let (old, new) = (old.as_ref(), new.as_ref());
+    let (old, new) = rayon::join(|| crate::load(old), || crate::load(new));
+    Ok(diff_fonts(&old?, &new?))
+}
+
+/// Formats a value for the summary; `None` is shown as "unset".
+fn show(value: Option<&plist::Value>) -> String {
+    let value = match value {
+        Some(value) => value,
+        None => return "unset".to_string(),
+    };
+    match value {
+        plist::Value::String(s) => format!("{:?}", s),
+        plist::Value::Integer(i) => i.to_string(),
+        plist::Value::Real(r) => r.to_string(),
+        plist::Value::Boolean(b) => b.to_string(),
+        plist::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(|v| show(Some(v))).collect();
+            format!("[{}]", items.join(", "))
+        }
+        plist::Value::Dictionary(d) => format!("{{{} keys}}", d.len()),
+        plist::Value::Data(data) => format!("<{} bytes>", data.len()),
+        other => format!("{:?}", other),
+    }
+}
+
+fn show_kerning(value: Option<f32>) -> String {
+    value.map_or("unset".to_string(), |v| v.to_string())
+}
+
+fn show_unicodes(codepoints: &[char]) -> String {
+    if codepoints.is_empty() {
+        return "none".to_string();
+    }
+    let hex: Vec<String> = codepoints
+        .iter()
+        .map(|c| format!("{:04X}", *c as u32))
+        .collect();
+    hex.join(" ")
+}
+
+impl Display for ValueChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.key,
+            show(self.old.as_ref()),
+            show(self.new.as_ref())
+        )
+    }
+}
+
+impl Display for GlyphChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphChange::Width { old, new } => write!(f, "width {} -> {}", old, new),
+            GlyphChange::Height { old, new } => write!(f, "height {} -> {}", old, new),
+            GlyphChange::Unicodes { old, new } => write!(
+                f,
+                "unicodes {} -> {}",
+                show_unicodes(old),
+                show_unicodes(new)
+            ),
+            GlyphChange::Contours { old, new } => {
+                write!(f, "contours changed ({} -> {})", old, new)
+            }
+            GlyphChange::Components { old, new } if old == new => {
+                write!(f, "components changed ({})", new.join(", "))
+            }
+            GlyphChange::Components { old, new } => {
+                write!(f, "components [{}] -> [{}]", old.join(", "), new.join(", "))
+            }
+            GlyphChange::Anchors {
+                added,
+                removed,
+                moved,
+            } => {
+                let mut parts = Vec::new();
+                for (verb, names) in [("added", added), ("removed", removed), ("moved", moved)] {
+                    if !names.is_empty() {
+                        parts.push(format!("{} {}", verb, names.join(", ")));
+                    }
+                }
+                if parts.is_empty() {
+                    parts.push("changed".to_string());
+                }
+                write!(f, "anchors {}", parts.join("; "))
+            }
+            GlyphChange::Guidelines { old, new } => write!(f, "guidelines {} -> {}", old, new),
+            GlyphChange::Image => write!(f, "image changed"),
+            GlyphChange::Note { .. } => write!(f, "note changed"),
+            GlyphChange::Lib(change) => write!(f, "lib {}", change),
+        }
+    }
+}
+
+impl Display for GlyphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} glyph '{}'", self.status.sign(), self.name)?;
+        if !self.changes.is_empty() {
+            let changes: Vec<String> = self.changes.iter().map(|c| c.to_string()).collect();
+            write!(f, ": {}", changes.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for KerningChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}) {} -> {}",
+            self.first,
+            self.second,
+            show_kerning(self.old),
+            show_kerning(self.new)
+        )
+    }
+}
+
+impl Display for GroupChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} group '{}'", self.status.sign(), self.name)?;
+        if self.status != Status::Modified {
+            return Ok(());
+        }
+        if self.added.is_empty() && self.removed.is_empty() {
+            return write!(f, ": reordered");
+        }
+        let mut parts = Vec::new();
+        if !self.added.is_empty() {
+            parts.push(format!("added {}", self.added.join(", ")));
+        }
+        if !self.removed.is_empty() {
+            parts.push(format!("removed {}", self.removed.join(", ")));
+        }
+        write!(f, ": {}", parts.join("; "))
+    }
+}
+
+impl Display for FontDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        for layer in &self.layers {
+            match layer.status {
+                Status::Modified => writeln!(out, "layer '{}':", layer.name)?,
+                status => writeln!(out, "{} layer '{}':", status.sign(), layer.name)?,
+            }
+            for change in layer.color.iter().chain(&layer.lib) {
+                writeln!(out, "  {}", change)?;
+            }
+            for glyph in &layer.glyphs {
+                writeln!(out, "  {}", glyph)?;
+            }
+        }
+        for (title, changes) in [("fontinfo", &self.info), ("lib", &self.lib)] {
+            if !changes.is_empty() {
+                writeln!(out, "{}:", title)?;
+            }
+            for change in changes {
+                writeln!(out, "  {}", change)?;
+            }
+        }
+        if !self.kerning.is_empty() {
+            writeln!(out, "kerning:")?;
+        }
+        for change in &self.kerning {
+            writeln!(out, "  {}", change)?;
+        }
+        if !self.groups.is_empty() {
+            writeln!(out, "groups:")?;
+        }
+        for change in &self.groups {
+            writeln!(out, "  {}", change)?;
+        }
+        if let Some(features) = &self.features {
+            let (added, removed) = features.line_counts();
+            writeln!(out, "features: {} lines added, {} removed", added, removed)?;
+        }
+        f.write_str(out.trim_end())
+    }
+}
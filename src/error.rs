@@ -0,0 +1,87 @@
+use crate::decompose::DecomposeError;
+use crate::rename::RenameError;
+
+/// Errors returned by iondrive.
+#[derive(Debug)]
+pub enum Error {
+    /// Loading or converting UFO data with norad failed.
+    Norad(norad::Error),
+    /// A property list could not be read.
+    Plist(plist::Error),
+    /// Reading or writing files failed.
+    Io(std::io::Error),
+    /// Components could not be resolved.
+    Decompose(DecomposeError),
+    /// Glyphs could not be renamed.
+    Rename(RenameError),
+    /// The requested layer does not exist.
+    MissingLayer(String),
+    /// The requested glyph does not exist.
+    MissingGlyph(String),
+    /// A UFO archive could not be read or written.
+    Zip(zip::result::ZipError),
+    /// A UFO read from memory uses format version 1, which norad can only
+    /// upgrade when loading from a directory.
+    UnsupportedFormat(u8),
+    /// Reading from a git repository failed.
+    Git(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Norad(error) => error.fmt(f),
+            Error::Plist(error) => error.fmt(f),
+            Error::Io(error) => error.fmt(f),
+            Error::Decompose(error) => error.fmt(f),
+            Error::Rename(error) => error.fmt(f),
+            Error::MissingLayer(name) => write!(f, "no layer named '{}'", name),
+            Error::MissingGlyph(name) => write!(f, "no glyph named '{}'", name),
+            Error::Zip(error) => error.fmt(f),
+            Error::UnsupportedFormat(version) => write!(
+                f,
+                "UFO format version {} can only be loaded from a directory",
+                version
+            ),
+            Error::Git(message) => write!(f, "git: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<norad::Error> for Error {
+    fn from(error: norad::Error) -> Self {
+        Error::Norad(error)
+    }
+}
+
+impl From<plist::Error> for Error {
+    fn from(error: plist::Error) -> Self {
+        Error::Plist(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<DecomposeError> for Error {
+    fn from(error: DecomposeError) -> Self {
+        Error::Decompose(error)
+    }
+}
+
+impl From<RenameError> for Error {
+    fn from(error: RenameError) -> Self {
+        Error::Rename(error)
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(error: zip::result::ZipError) -> Self {
+        Error::Zip(error)
+    }
+}
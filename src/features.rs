@@ -0,0 +1,982 @@
+//! Resolving and checking a UFO's `features.fea`.
+//!
+//! `include()` statements are resolved like [ufo2ft] does: relative to the
+//! directory containing the UFO, for nested includes too. The check is
+//! structural rather than a full grammar, so it doesn't validate the
+//! feature code: it finds unterminated statements, strings and brackets,
+//! unbalanced blocks, blocks closed with the wrong name, substitutions
+//! without `by`, `from` or `lookup`, and statements and blocks that don't
+//! start with a keyword of the feature file syntax. Anonymous blocks are
+//! skipped. Glyph names are checked in substitution, positioning and
+//! `markClass` statements, glyph class definitions and everywhere inside
+//! `[...]`, and every `@class` must be defined before it is used. The same
+//! places are where [`rename_glyphs`] renames glyphs.
+//!
+//! [ufo2ft]: https://github.com/googlefonts/ufo2ft
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use norad::Layer;
+
+use crate::Error;
+
+/// The name issues refer to for the UFO's own feature file.
+pub const FEATURES_FILE: &str = "features.fea";
+
+/// A problem found in the features of a font.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureIssue {
+    /// An included file could not be read; its contents are left out.
+    Include {
+        file: String,
+        line: usize,
+        path: PathBuf,
+        message: String,
+    },
+    /// The structure of the feature code is broken, e.g. a missing `;` or
+    /// an unclosed block. Only the first such problem is reported, as later
+    /// ones tend to follow from it.
+    Structure {
+        file: String,
+        line: usize,
+        message: String,
+    },
+    /// A glyph name that is not in the font.
+    MissingGlyph {
+        file: String,
+        line: usize,
+        glyph: String,
+    },
+    /// A glyph class that is used before being defined, or not at all.
+    UndefinedClass {
+        file: String,
+        line: usize,
+        class: String,
+    },
+    /// A statement or block starting with a word that isn't a keyword, like
+    /// a misspelled `languagesystem`.
+    UnknownKeyword {
+        file: String,
+        line: usize,
+        keyword: String,
+    },
+}
+
+impl FeatureIssue {
+    /// A short, stable name for the kind of issue.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FeatureIssue::Include { .. } => "include",
+            FeatureIssue::Structure { .. } => "structure",
+            FeatureIssue::MissingGlyph { .. } => "missing_glyph",
+            FeatureIssue::UndefinedClass { .. } => "undefined_class",
+            FeatureIssue::UnknownKeyword { .. } => "unknown_keyword",
+        }
+    }
+
+    /// The file and line the issue is at.
+    pub fn location(&self) -> (&str, usize) {
+        match self {
+            FeatureIssue::Include { file, line, .. }
+            | FeatureIssue::Structure { file, line, .. }
+            | FeatureIssue::MissingGlyph { file, line, .. }
+            | FeatureIssue::UndefinedClass { file, line, .. }
+            | FeatureIssue::UnknownKeyword { file, line, .. } => (file, *line),
+        }
+    }
+}
+
+impl Display for FeatureIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (file, line) = self.location();
+        write!(f, "{}:{}: ", file, line)?;
+        match self {
+            FeatureIssue::Include { path, message, .. } => {
+                write!(f, "can't include '{}': {}", path.display(), message)
+            }
+            FeatureIssue::Structure { message, .. } => write!(f, "{}", message),
+            FeatureIssue::MissingGlyph { glyph, .. } => {
+                write!(f, "glyph '{}' is not in the font", glyph)
+            }
+            FeatureIssue::UndefinedClass { class, .. } => {
+                write!(f, "glyph class '@{}' is not defined", class)
+            }
+            FeatureIssue::UnknownKeyword { keyword, .. } => {
+                write!(f, "unknown keyword '{}'", keyword)
+            }
+        }
+    }
+}
+
+/// The features of a font with their includes resolved, and the problems
+/// found in them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckedFeatures {
+    /// The feature code with every `include()` replaced by the contents of
+    /// the included file.
+    pub text: String,
+    pub issues: Vec<FeatureIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Class(String),
+    Number,
+    Cid,
+    Str,
+    Symbol(char),
+}
+
+/// A token and where it is.
+struct Located {
+    token: Token,
+    file: usize,
+    line: usize,
+    /// The byte range of the token in its file, without the `@` or `\` of
+    /// class and glyph names.
+    span: Range<usize>,
+}
+
+const SYMBOLS: &str = "{}[]()<>;,='-";
+
+/// Keywords of substitution and positioning statements that aren't glyphs.
+const KEYWORDS: &[&str] = &[
+    "anchor",
+    "base",
+    "by",
+    "contourpoint",
+    "cursive",
+    "device",
+    "enum",
+    "enumerate",
+    "from",
+    "ignore",
+    "ligComponent",
+    "ligature",
+    "lookup",
+    "mark",
+    "markClass",
+    "NULL",
+    "pos",
+    "position",
+    "reversesub",
+    "rsub",
+    "sub",
+    "substitute",
+];
+
+/// Statements whose names, outside `<...>`, are glyphs.
+const GLYPH_STATEMENTS: &[&str] = &[
+    "enum",
+    "enumerate",
+    "ignore",
+    "markClass",
+    "pos",
+    "position",
+    "reversesub",
+    "rsub",
+    "sub",
+    "substitute",
+];
+
+/// Blocks closed with their name, like `feature liga { ... } liga;`.
+const NAMED_BLOCKS: &[&str] = &["conditionset", "feature", "lookup", "table", "variation"];
+
+/// Keywords that start a block.
+const BLOCKS: &[&str] = &[
+    "AxisValue",
+    "DesignAxis",
+    "conditionset",
+    "cvParameters",
+    "ElidedFallbackName",
+    "FeatUILabelNameID",
+    "FeatUITooltipTextNameID",
+    "feature",
+    "featureNames",
+    "lookup",
+    "ParamUILabelNameID",
+    "SampleTextNameID",
+    "table",
+    "variation",
+];
+
+/// Keywords that start a statement, apart from those in `GLYPH_STATEMENTS`,
+/// including those of the `table` blocks.
+const STATEMENTS: &[&str] = &[
+    "anchorDef",
+    "Ascender",
+    "Attach",
+    "CapHeight",
+    "CaretOffset",
+    "Character",
+    "CodePageRange",
+    "Descender",
+    "ElidedFallbackName",
+    "ElidedFallbackNameID",
+    "FamilyClass",
+    "FeatUILabelNameID",
+    "FeatUITooltipTextNameID",
+    "feature",
+    "flag",
+    "FontRevision",
+    "FSType",
+    "GlyphClassDef",
+    "HorizAxis.BaseScriptList",
+    "HorizAxis.BaseTagList",
+    "HorizAxis.MinMax",
+    "language",
+    "languagesystem",
+    "LigatureCaretByIndex",
+    "LigatureCaretByPos",
+    "LineGap",
+    "location",
+    "lookup",
+    "lookupflag",
+    "LowerOpSize",
+    "name",
+    "nameid",
+    "Panose",
+    "parameters",
+    "ParamUILabelNameID",
+    "SampleTextNameID",
+    "script",
+    "sizemenuname",
+    "subtable",
+    "TypoAscender",
+    "TypoDescender",
+    "TypoLineGap",
+    "UnicodeRange",
+    "UpperOpSize",
+    "valueRecordDef",
+    "Vendor",
+    "VertAdvanceY",
+    "VertAxis.BaseScriptList",
+    "VertAxis.BaseTagList",
+    "VertAxis.MinMax",
+    "VertOriginY",
+    "VertTypoAscender",
+    "VertTypoDescender",
+    "VertTypoLineGap",
+    "WeightClass",
+    "WidthClass",
+    "winAscent",
+    "winDescent",
+    "XHeight",
+];
+
+/// Substitution statements, which need `by`, `from` or `lookup`.
+const SUBSTITUTIONS: &[&str] = &["reversesub", "rsub", "sub", "substitute"];
+
+fn is_name_char(c: char) -> bool {
+    !c.is_whitespace() && !"{}[]()<>;,='\"#@\\".contains(c)
+}
+
+/// Returns the length of `rest`, what follows the keyword of an anonymous
+/// block, up to the `;` of the `} TAG;` line closing it.
+fn anonymous_block_len(rest: &str) -> Option<usize> {
+    let tag: String = rest
+        .trim_start()
+        .chars()
+        .take_while(|c| is_name_char(*c))
+        .collect();
+    if tag.is_empty() {
+        return None;
+    }
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let after = line
+            .trim_start()
+            .strip_prefix('}')
+            .and_then(|l| l.trim_start().strip_prefix(tag.as_str()))
+            .map(str::trim_start);
+        if let Some(after) = after.filter(|a| a.starts_with(';')) {
+            return Some(offset + line.len() - after.len() + 1);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+struct Resolver<'a> {
+    /// Where includes are resolved, or `None` to skip them.
+    include_dir: Option<&'a Path>,
+    files: Vec<String>,
+    tokens: Vec<Located>,
+    issues: Vec<FeatureIssue>,
+    /// The canonical paths of the files being included, to detect cycles.
+    including: Vec<PathBuf>,
+}
+
+impl Resolver<'_> {
+    fn structure(&mut self, file: usize, line: usize, message: String) {
+        self.issues.push(FeatureIssue::Structure {
+            file: self.files[file].clone(),
+            line,
+            message,
+        });
+    }
+
+    /// Tokenizes `text`, the contents of the file `name`, resolving its
+    /// includes, and returns it with the included files inlined.
+    fn resolve(&mut self, name: String, text: &str) -> String {
+        let file = self.files.len();
+        self.files.push(name);
+        let mut out = String::new();
+        let mut copied = 0;
+        let mut line = 1;
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            // Strings can span lines; tokens are where they start.
+            let first_line = line;
+            let token = match c {
+                '\n' => {
+                    line += 1;
+                    continue;
+                }
+                c if c.is_whitespace() => continue,
+                '#' => {
+                    while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                    continue;
+                }
+                '"' => {
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, '\n')) => line += 1,
+                            Some(_) => (),
+                            None => {
+                                self.structure(file, first_line, "unterminated string".to_string());
+                                break;
+                            }
+                        }
+                    }
+                    Token::Str
+                }
+                '@' | '\\' => {
+                    let mut name = String::new();
+                    while let Some((_, c)) = chars.next_if(|(_, c)| is_name_char(*c)) {
+                        name.push(c);
+                    }
+                    if c == '@' {
+                        Token::Class(name)
+                    } else if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) {
+                        Token::Cid
+                    } else {
+                        Token::Name(name)
+                    }
+                }
+                c if c.is_ascii_digit()
+                    || (c == '-' && chars.peek().is_some_and(|(_, c)| c.is_ascii_digit())) =>
+                {
+                    while chars.next_if(|(_, c)| is_name_char(*c)).is_some() {}
+                    Token::Number
+                }
+                c if SYMBOLS.contains(c) => Token::Symbol(c),
+                c => {
+                    let mut name = c.to_string();
+                    while let Some((_, c)) = chars.next_if(|(_, c)| is_name_char(*c)) {
+                        name.push(c);
+                    }
+                    if name == "anon" || name == "anonymous" {
+                        // The contents of anonymous blocks aren't feature code.
+                        let from = chars.peek().map_or(text.len(), |(i, _)| *i);
+                        let end = match anonymous_block_len(&text[from..]) {
+                            Some(len) => from + len,
+                            None => {
+                                self.structure(
+                                    file,
+                                    line,
+                                    format!("{} block is never closed", name),
+                                );
+                                text.len()
+                            }
+                        };
+                        line += text[from..end].matches('\n').count();
+                        while chars.next_if(|(i, _)| *i < end).is_some() {}
+                        continue;
+                    } else if name != "include" {
+                        Token::Name(name)
+                    } else {
+                        while chars.next_if(|(_, c)| *c == ' ' || *c == '\t').is_some() {}
+                        if chars.next_if(|(_, c)| *c == '(').is_none() {
+                            self.structure(file, line, "expected '(' after include".to_string());
+                            continue;
+                        }
+                        let mut path = String::new();
+                        let mut end = None;
+                        for (i, c) in chars.by_ref() {
+                            if c == ')' {
+                                end = Some(i + 1);
+                                break;
+                            }
+                            path.push(c);
+                        }
+                        let mut end = match end {
+                            Some(end) => end,
+                            None => {
+                                self.structure(file, line, "unterminated include".to_string());
+                                text.len()
+                            }
+                        };
+                        // The semicolon after an include is optional.
+                        let rest = &text[end..];
+                        if rest.trim_start_matches([' ', '\t']).starts_with(';') {
+                            end += rest.find(';').unwrap_or(0) + 1;
+                            while chars.next_if(|(i, _)| *i < end).is_some() {}
+                        }
+                        out.push_str(&text[copied..start]);
+                        out.push_str(&self.include(file, line, path.trim()));
+                        copied = end;
+                        continue;
+                    }
+                }
+            };
+            let end = chars.peek().map_or(text.len(), |(i, _)| *i);
+            let start = if c == '@' || c == '\\' {
+                start + 1
+            } else {
+                start
+            };
+            self.tokens.push(Located {
+                token,
+                file,
+                line: first_line,
+                span: start..end,
+            });
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    fn include(&mut self, file: usize, line: usize, path: &str) -> String {
+        let path = match self.include_dir {
+            Some(include_dir) => include_dir.join(path),
+            None => return String::new(),
+        };
+        let result = fs::canonicalize(&path).and_then(|canonical| {
+            if self.including.contains(&canonical) {
+                return Err(std::io::Error::other("the file includes itself"));
+            }
+            Ok((fs::read_to_string(&canonical)?, canonical))
+        });
+        match result {
+            Ok((text, canonical)) => {
+                self.including.push(canonical);
+                let text = self.resolve(path.display().to_string(), &text);
+                self.including.pop();
+                text
+            }
+            Err(error) => {
+                self.issues.push(FeatureIssue::Include {
+                    file: self.files[file].clone(),
+                    line,
+                    path,
+                    message: error.to_string(),
+                });
+                String::new()
+            }
+        }
+    }
+}
+
+/// Returns the name a token is, or an empty string.
+fn name_of(token: Option<&&Located>) -> String {
+    match token.map(|t| &t.token) {
+        Some(Token::Name(name)) => name.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Checks the statements of a token stream.
+struct Checker<'a> {
+    layer: &'a Layer,
+    files: &'a [String],
+    classes: HashSet<String>,
+    issues: Vec<FeatureIssue>,
+    structure_error: bool,
+    /// Where glyph names are referenced, by file.
+    references: Vec<(usize, Range<usize>)>,
+}
+
+impl Checker<'_> {
+    fn structure(&mut self, at: &Located, message: String) {
+        if !self.structure_error {
+            self.structure_error = true;
+            self.issues.push(FeatureIssue::Structure {
+                file: self.files[at.file].clone(),
+                line: at.line,
+                message,
+            });
+        }
+    }
+
+    /// Returns true if `name` is a glyph, or, in a class, a range of glyphs
+    /// like `a-z`.
+    fn is_glyph(&self, name: &str, in_class: bool) -> bool {
+        self.layer.contains_glyph(name)
+            || (in_class
+                && name.match_indices('-').any(|(i, _)| {
+                    self.layer.contains_glyph(&name[..i])
+                        && self.layer.contains_glyph(&name[i + 1..])
+                }))
+    }
+
+    fn check_class(&mut self, at: &Located, class: &str) {
+        if !self.classes.contains(class) {
+            self.issues.push(FeatureIssue::UndefinedClass {
+                file: self.files[at.file].clone(),
+                line: at.line,
+                class: class.to_string(),
+            });
+        }
+    }
+
+    /// Checks the glyph and class references of one statement, and defines
+    /// the classes it defines.
+    fn statement(&mut self, tokens: &[&Located]) {
+        let first = match tokens.first() {
+            Some(first) => &first.token,
+            None => return,
+        };
+        let mut defines = None;
+        let mut names_are_glyphs = false;
+        let mut report_missing = true;
+        let mut rest = tokens;
+        match (first, tokens.get(1).map(|t| &t.token)) {
+            (Token::Class(class), Some(Token::Symbol('='))) => {
+                defines = Some(class.clone());
+                names_are_glyphs = true;
+                rest = &tokens[2..];
+            }
+            (Token::Name(keyword), _) if GLYPH_STATEMENTS.contains(&keyword.as_str()) => {
+                names_are_glyphs = true;
+                if keyword == "markClass" {
+                    if let Some((last, init)) = tokens.split_last() {
+                        if let Token::Class(class) = &last.token {
+                            defines = Some(class.clone());
+                            rest = init;
+                        }
+                    }
+                }
+                let is_target = |t: &&Located| match &t.token {
+                    Token::Name(name) => ["by", "from", "lookup"].contains(&name.as_str()),
+                    _ => false,
+                };
+                if SUBSTITUTIONS.contains(&keyword.as_str()) && !tokens.iter().any(is_target) {
+                    // The replaced glyphs can't be told apart from a
+                    // misspelled `by`.
+                    report_missing = false;
+                    self.structure(
+                        tokens[0],
+                        "expected 'by', 'from' or 'lookup' in substitution".to_string(),
+                    );
+                }
+            }
+            _ => (),
+        }
+
+        let (mut brackets, mut angles) = (0, 0);
+        let mut after_lookup = false;
+        for at in rest {
+            match &at.token {
+                Token::Symbol('[') if brackets > 0 => self.structure(at, "nested '['".to_string()),
+                Token::Symbol('[') => brackets += 1,
+                Token::Symbol(']') if brackets == 0 => {
+                    self.structure(at, "unmatched ']'".to_string())
+                }
+                Token::Symbol(']') => brackets -= 1,
+                Token::Symbol('<') => angles += 1,
+                Token::Symbol('>') if angles == 0 => {
+                    self.structure(at, "unmatched '>'".to_string())
+                }
+                Token::Symbol('>') => angles -= 1,
+                Token::Class(class) if angles == 0 => self.check_class(at, class),
+                Token::Name(name)
+                    if angles == 0
+                        && !after_lookup
+                        && (brackets > 0
+                            || (names_are_glyphs && !KEYWORDS.contains(&name.as_str()))) =>
+                {
+                    self.references.push((at.file, at.span.clone()));
+                    if report_missing && !self.is_glyph(name, brackets > 0) {
+                        self.issues.push(FeatureIssue::MissingGlyph {
+                            file: self.files[at.file].clone(),
+                            line: at.line,
+                            glyph: name.clone(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+            after_lookup = at.token == Token::Name("lookup".to_string());
+        }
+        if let Some(at) = tokens.last() {
+            if brackets > 0 {
+                self.structure(at, "unterminated '['".to_string());
+            } else if angles > 0 {
+                self.structure(at, "unterminated '<'".to_string());
+            }
+        }
+        if let Some(class) = defines {
+            self.classes.insert(class);
+        }
+    }
+
+    /// Reports the keyword of a statement or block if it isn't one of
+    /// `keywords`.
+    fn check_keyword(&mut self, tokens: &[&Located], keywords: &[&str]) {
+        if let Some(at) = tokens.first() {
+            if let Token::Name(keyword) = &at.token {
+                if !keywords.contains(&keyword.as_str()) {
+                    self.issues.push(FeatureIssue::UnknownKeyword {
+                        file: self.files[at.file].clone(),
+                        line: at.line,
+                        keyword: keyword.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn check(&mut self, tokens: &[Located]) {
+        // The open blocks, with their keyword, name and where they start.
+        let mut blocks: Vec<(String, String, &Located)> = Vec::new();
+        let mut statement: Vec<&Located> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let at = &tokens[i];
+            i += 1;
+            match at.token {
+                Token::Symbol(';') => {
+                    // Condition sets contain axis tags rather than keywords.
+                    if blocks.last().is_none_or(|b| b.0 != "conditionset")
+                        && !GLYPH_STATEMENTS.contains(&name_of(statement.first()).as_str())
+                    {
+                        self.check_keyword(&statement, STATEMENTS);
+                    }
+                    self.statement(&statement);
+                    statement.clear();
+                }
+                Token::Symbol('{') => {
+                    if statement.is_empty() {
+                        self.structure(at, "block without a keyword".to_string());
+                    }
+                    self.check_keyword(&statement, BLOCKS);
+                    blocks.push((name_of(statement.first()), name_of(statement.get(1)), at));
+                    statement.clear();
+                }
+                Token::Symbol('}') => {
+                    if !statement.is_empty() {
+                        self.structure(at, "missing ';' before '}'".to_string());
+                        statement.clear();
+                    }
+                    let (keyword, name, _) = match blocks.pop() {
+                        Some(block) => block,
+                        None => {
+                            self.structure(at, "unmatched '}'".to_string());
+                            continue;
+                        }
+                    };
+                    if NAMED_BLOCKS.contains(&keyword.as_str()) {
+                        let end = match tokens.get(i).map(|t| &t.token) {
+                            Some(Token::Name(end)) => {
+                                i += 1;
+                                end
+                            }
+                            _ => "",
+                        };
+                        if *end != name {
+                            self.structure(
+                                at,
+                                format!(
+                                    "{} '{}' must be closed with '}} {};'",
+                                    keyword, name, name
+                                ),
+                            );
+                        }
+                    } else if !BLOCKS.contains(&keyword.as_str()) {
+                        // Unknown blocks are already reported, and may be
+                        // closed with a name.
+                        if let Some(Token::Name(_)) = tokens.get(i).map(|t| &t.token) {
+                            i += 1;
+                        }
+                    }
+                    match tokens.get(i) {
+                        Some(t) if t.token == Token::Symbol(';') => i += 1,
+                        _ => self.structure(at, "missing ';' after '}'".to_string()),
+                    }
+                }
+                _ => statement.push(at),
+            }
+        }
+        if let Some(at) = statement.first() {
+            self.structure(at, "missing ';' at the end".to_string());
+        }
+        if let Some((keyword, name, at)) = blocks.pop() {
+            self.structure(at, format!("{} {} is never closed", keyword, name));
+        }
+    }
+}
+
+/// Resolves the includes of `features`, relative to `include_dir`, and
+/// checks the result against the glyphs of `layer`, which should be the
+/// font's default layer.
+///
+/// Problems with includes come first, then the others in the order they
+/// occur.
+pub fn check_features(features: &str, layer: &Layer, include_dir: &Path) -> CheckedFeatures {
+    let mut resolver = Resolver {
+        include_dir: Some(include_dir),
+        files: Vec::new(),
+        tokens: Vec::new(),
+        issues: Vec::new(),
+        including: Vec::new(),
+    };
+    let text = resolver.resolve(FEATURES_FILE.to_string(), features);
+
+    let mut checker = Checker {
+        layer,
+        files: &resolver.files,
+        classes: HashSet::new(),
+        issues: Vec::new(),
+        // Structure errors found while tokenizing count as the first one.
+        structure_error: resolver
+            .issues
+            .iter()
+            .any(|i| matches!(i, FeatureIssue::Structure { .. })),
+        references: Vec::new(),
+    };
+    checker.check(&resolver.tokens);
+    let mut issues = resolver.issues;
+    issues.extend(checker.issues);
+    CheckedFeatures { text, issues }
+}
+
+/// Returns the directory containing the UFO at `path`, where its feature
+/// includes are resolved.
+pub(crate) fn include_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("."))
+}
+
+/// Loads the UFO at `path` and checks its features with [`check_features`],
+/// resolving includes relative to the directory containing the UFO.
+pub fn check_ufo_features(path: impl AsRef<Path>) -> Result<CheckedFeatures, Error> {
+    let path = path.as_ref();
+    let font = crate::LoadOptions::none()
+        .layers(true)
+        .features(true)
+        .load(path)?;
+    Ok(check_features(
+        font.features.as_deref().unwrap_or_default(),
+        font.default_layer(),
+        include_dir(path),
+    ))
+}
+
+/// Returns the script and language tags of the `languagesystem` statements
+/// in `features`, in order. Included files are not read.
+pub(crate) fn language_systems(features: &str) -> Vec<(String, String)> {
+    let mut resolver = Resolver {
+        include_dir: None,
+        files: Vec::new(),
+        tokens: Vec::new(),
+        issues: Vec::new(),
+        including: Vec::new(),
+    };
+    resolver.resolve(FEATURES_FILE.to_string(), features);
+    resolver
+        .tokens
+        .windows(4)
+        .filter_map(|w| match [&w[0].token, &w[1].token, &w[2].token, &w[3].token] {
+            [Token::Name(keyword), Token::Name(script), Token::Name(language), Token::Symbol(';')]
+                if keyword == "languagesystem" =>
+            {
+                Some((script.clone(), language.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the new name of the glyph reference `name` in a feature file, if
+/// `rename` renames it or, for a range like `a-z`, one of its ends.
+fn renamed_reference(
+    name: &str,
+    layer: &Layer,
+    rename: &BTreeMap<String, String>,
+) -> Option<String> {
+    if let Some(new) = rename.get(name) {
+        return Some(new.clone());
+    }
+    if layer.contains_glyph(name) {
+        return None;
+    }
+    let (first, last) = name
+        .match_indices('-')
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .find(|(first, last)| layer.contains_glyph(first) && layer.contains_glyph(last))?;
+    let renamed = |glyph: &str| rename.get(glyph).map_or(glyph, |g| g.as_str()).to_string();
+    (rename.contains_key(first) || rename.contains_key(last))
+        .then(|| format!("{}-{}", renamed(first), renamed(last)))
+}
+
+/// Renames glyphs in `features`, the contents of a UFO's feature file,
+/// wherever [`check_features`] looks for glyph names, and returns the new
+/// text. `layer` is the font's default layer before the renaming, which
+/// tells glyph ranges like `a-z` from glyph names. Included files are
+/// neither read nor changed.
+pub fn rename_glyphs(features: &str, layer: &Layer, rename: &BTreeMap<String, String>) -> String {
+    let mut resolver = Resolver {
+        include_dir: None,
+        files: Vec::new(),
+        tokens: Vec::new(),
+        issues: Vec::new(),
+        including: Vec::new(),
+    };
+    resolver.resolve(FEATURES_FILE.to_string(), features);
+    let mut checker = Checker {
+        layer,
+        files: &resolver.files,
+        classes: HashSet::new(),
+        issues: Vec::new(),
+        structure_error: true,
+        references: Vec::new(),
+    };
+    checker.check(&resolver.tokens);
+
+    let mut text = String::new();
+    let mut copied = 0;
+    for (_, span) in checker
+        .references
+        .into_iter()
+        .filter(|(file, _)| *file == 0)
+    {
+        if let Some(new) = renamed_reference(&features[span.clone()], layer, rename) {
+            text.push_str(&features[copied..span.start]);
+            text.push_str(&new);
+            copied = span.end;
+        }
+    }
+    text.push_str(&features[copied..]);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use norad::Glyph;
+
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<(Token, usize, &str)> {
+        let mut resolver = Resolver {
+            include_dir: None,
+            files: Vec::new(),
+            tokens: Vec::new(),
+            issues: Vec::new(),
+            including: Vec::new(),
+        };
+        resolver.resolve(FEATURES_FILE.to_string(), text);
+        assert_eq!(resolver.issues, []);
+        resolver
+            .tokens
+            .into_iter()
+            .map(|t| (t.token, t.line, &text[t.span]))
+            .collect()
+    }
+
+    fn layer(glyphs: &[&str]) -> Layer {
+        let mut layer = Layer::default();
+        for name in glyphs {
+            layer.insert_glyph(Glyph::new_named(*name));
+        }
+        layer
+    }
+
+    fn issues(features: &str) -> Vec<FeatureIssue> {
+        let layer = layer(&["A", "B", "f", "i", "f_i"]);
+        check_features(features, &layer, Path::new(".")).issues
+    }
+
+    #[test]
+    fn tokenizes() {
+        let name = |n: &str| Token::Name(n.to_string());
+        let text = "@UC = [\\A B-C \\123]; # [comment]\npos A -10 \"a\nb\" <0 0>;";
+        assert_eq!(
+            tokens(text),
+            [
+                (Token::Class("UC".to_string()), 1, "UC"),
+                (Token::Symbol('='), 1, "="),
+                (Token::Symbol('['), 1, "["),
+                (name("A"), 1, "A"),
+                (name("B-C"), 1, "B-C"),
+                (Token::Cid, 1, "123"),
+                (Token::Symbol(']'), 1, "]"),
+                (Token::Symbol(';'), 1, ";"),
+                (name("pos"), 2, "pos"),
+                (name("A"), 2, "A"),
+                (Token::Number, 2, "-10"),
+                (Token::Str, 2, "\"a\nb\""),
+                (Token::Symbol('<'), 3, "<"),
+                (Token::Number, 3, "0"),
+                (Token::Number, 3, "0"),
+                (Token::Symbol('>'), 3, ">"),
+                (Token::Symbol(';'), 3, ";"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_anonymous_blocks() {
+        let text = "anon sbit {\n  } other;\n \"\n} sbit ;\npos A B 1;";
+        let lines: Vec<_> = tokens(text).into_iter().map(|(_, line, _)| line).collect();
+        assert_eq!(lines, [5, 5, 5, 5, 5]);
+    }
+
+    #[test]
+    fn reports_structure_once() {
+        let found = issues("feature liga {\n  sub A by B\n} calt;\nlookup x {");
+        assert_eq!(
+            found,
+            [FeatureIssue::Structure {
+                file: FEATURES_FILE.to_string(),
+                line: 3,
+                message: "missing ';' before '}'".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_keywords_and_glyphs() {
+        let found =
+            issues("languagesytem DFLT dflt;\nsub f i bye f_i;\nsub A by C;\nfoo {\n} foo;");
+        let kinds: Vec<_> = found.iter().map(|i| (i.kind(), i.location().1)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("unknown_keyword", 1),
+                ("structure", 2),
+                ("missing_glyph", 3),
+                ("unknown_keyword", 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_glyphs_and_ranges() {
+        let layer = layer(&["a", "b", "c", "a.sc"]);
+        let rename: BTreeMap<_, _> = [("a", "b"), ("b", "a"), ("a.sc", "A.sc")]
+            .iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect();
+        let features = "@lc = [a-c \\b];\nsub a by a.sc; # a\nlanguagesystem latn dflt;";
+        assert_eq!(
+            rename_glyphs(features, &layer, &rename),
+            "@lc = [b-c \\a];\nsub b by A.sc; # a\nlanguagesystem latn dflt;"
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Loading a UFO from files held in memory.
+//!
+//! This follows norad's loader, but reads every file from a map of relative
+//! paths to contents instead of from disk, so nothing touches the
+//! filesystem. UFO 2 sources are upgraded like norad does; UFO 1 is not
+//! supported.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+use norad::error::GroupsValidationError;
+use norad::{
+    Color, DataRequest, Font, FontInfo, FormatVersion, Groups, Kerning, Layer, LayerSet, MetaInfo,
+    Plist,
+};
+use plist::Value;
+use rayon::prelude::*;
+
+use crate::kerning::{KERN1_PREFIX, KERN2_PREFIX};
+use crate::Error;
+
+const PUBLIC_OBJECT_LIBS_KEY: &str = "public.objectLibs";
+
+/// The contents of a UFO, keyed by paths relative to the UFO and separated
+/// by `/`.
+pub(crate) type Files = BTreeMap<String, Vec<u8>>;
+
+/// UFO 2 fontinfo keys that became integers in UFO 3.
+const V2_INTEGER_KEYS: &[&str] = &[
+    "openTypeHheaAscender",
+    "openTypeHheaCaretOffset",
+    "openTypeHheaDescender",
+    "openTypeHheaLineGap",
+    "openTypeOS2StrikeoutPosition",
+    "openTypeOS2StrikeoutSize",
+    "openTypeOS2SubscriptXOffset",
+    "openTypeOS2SubscriptXSize",
+    "openTypeOS2SubscriptYOffset",
+    "openTypeOS2SubscriptYSize",
+    "openTypeOS2SuperscriptXOffset",
+    "openTypeOS2SuperscriptXSize",
+    "openTypeOS2SuperscriptYOffset",
+    "openTypeOS2SuperscriptYSize",
+    "openTypeOS2TypoAscender",
+    "openTypeOS2TypoDescender",
+    "openTypeOS2TypoLineGap",
+    "openTypeVheaCaretOffset",
+    "openTypeVheaVertTypoAscender",
+    "openTypeVheaVertTypoDescender",
+    "openTypeVheaVertTypoLineGap",
+];
+
+/// UFO 2 fontinfo keys that became non-negative integers in UFO 3.
+const V2_NON_NEGATIVE_KEYS: &[&str] = &[
+    "openTypeHeadLowestRecPPEM",
+    "openTypeOS2WinAscent",
+    "openTypeOS2WinDescent",
+    "versionMinor",
+];
+
+fn join(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+fn required<'a>(files: &'a Files, path: &str) -> Result<&'a [u8], Error> {
+    files
+        .get(path)
+        .map(Vec::as_slice)
+        .ok_or_else(|| norad::Error::MissingFile(path.to_string()).into())
+}
+
+fn dictionary(data: &[u8], path: &str) -> Result<Plist, Error> {
+    Value::from_reader(std::io::Cursor::new(data))?
+        .into_dictionary()
+        .ok_or_else(|| norad::Error::ExpectedPlistDictionary(path.to_string()).into())
+}
+
+/// Mirrors norad's check of `groups.plist`, which it does not export.
+fn validate_groups(groups: &Groups) -> Result<(), GroupsValidationError> {
+    let (mut kern1, mut kern2) = (HashSet::new(), HashSet::new());
+    for (name, members) in groups {
+        let seen = if name.starts_with(KERN1_PREFIX) {
+            &mut kern1
+        } else if name.starts_with(KERN2_PREFIX) {
+            &mut kern2
+        } else if name.is_empty() {
+            return Err(GroupsValidationError::InvalidName);
+        } else {
+            continue;
+        };
+        // A kerning group needs a name after the prefix.
+        if name.len() == KERN1_PREFIX.len() {
+            return Err(GroupsValidationError::InvalidName);
+        }
+        for member in members {
+            if !seen.insert(member) {
+                return Err(GroupsValidationError::OverlappingKerningGroups {
+                    glyph_name: member.to_string(),
+                    group_name: name.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds `public.kern1.`/`public.kern2.` copies of the UFO 2 kerning groups,
+/// those named `@MMK_L_`/`@MMK_R_` or used in kerning pairs, and renames
+/// them in the kerning, like norad and ufoLib do.
+fn upconvert_kerning(
+    groups: &Groups,
+    kerning: &Kerning,
+    glyphs: &BTreeSet<&str>,
+) -> (Groups, Kerning) {
+    let is_group = |name: &str, prefix: &str| {
+        groups.contains_key(name) && !glyphs.contains(name) && !name.starts_with(prefix)
+    };
+    let mut firsts: BTreeSet<&String> = groups
+        .keys()
+        .filter(|name| name.starts_with("@MMK_L_"))
+        .collect();
+    let mut seconds: BTreeSet<&String> = groups
+        .keys()
+        .filter(|name| name.starts_with("@MMK_R_"))
+        .collect();
+    for (first, pairs) in kerning {
+        if is_group(first, KERN1_PREFIX) {
+            firsts.insert(first);
+        }
+        seconds.extend(pairs.keys().filter(|second| is_group(second, KERN2_PREFIX)));
+    }
+
+    let mut new_groups = groups.clone();
+    let mut renamed = |old: &String, prefix: &str, mmk: &str| {
+        let name = format!("{}{}", prefix, old.replace(mmk, ""));
+        let mut new = name.clone();
+        let mut counter = 1;
+        while new_groups.contains_key(&new) {
+            new = format!("{}{}", name, counter);
+            counter += 1;
+        }
+        new_groups.insert(new.clone(), groups[old].clone());
+        (old.clone(), new)
+    };
+    let firsts: BTreeMap<String, String> = firsts
+        .into_iter()
+        .map(|old| renamed(old, KERN1_PREFIX, "@MMK_L_"))
+        .collect();
+    let seconds: BTreeMap<String, String> = seconds
+        .into_iter()
+        .map(|old| renamed(old, KERN2_PREFIX, "@MMK_R_"))
+        .collect();
+
+    let new_kerning = kerning
+        .iter()
+        .map(|(first, pairs)| {
+            let pairs = pairs
+                .iter()
+                .map(|(second, value)| (seconds.get(second).unwrap_or(second).clone(), *value))
+                .collect();
+            (firsts.get(first).unwrap_or(first).clone(), pairs)
+        })
+        .collect();
+    (new_groups, new_kerning)
+}
+
+/// Converts the values of a UFO 2 `fontinfo.plist` to their UFO 3 types:
+/// numbers that must be integers are rounded, and those that must not be
+/// negative lose their sign.
+fn upconvert_fontinfo(info: &mut Plist) {
+    let number = |value: &Value| {
+        value
+            .as_real()
+            .or_else(|| value.as_signed_integer().map(|i| i as f64))
+    };
+    for (key, value) in info.iter_mut() {
+        let non_negative = V2_NON_NEGATIVE_KEYS.contains(&key.as_str());
+        if let Some(n) = number(value) {
+            if non_negative {
+                *value = Value::Integer((n.round().abs() as u64).into());
+            } else if V2_INTEGER_KEYS.contains(&key.as_str()) {
+                *value = Value::Integer((n.round() as i64).into());
+            } else if key == "unitsPerEm" {
+                *value = Value::Real(n.abs());
+            }
+        } else if key == "openTypeOS2Panose" {
+            for item in value.as_array_mut().into_iter().flatten() {
+                if let Some(n) = item.as_signed_integer() {
+                    *item = Value::Integer(n.unsigned_abs().into());
+                }
+            }
+        }
+    }
+}
+
+fn load_layer(files: &Files, name: &str, dir: &str) -> Result<Layer, Error> {
+    let contents_path = join(dir, "contents.plist");
+    let contents: BTreeMap<String, String> = plist::from_bytes(required(files, &contents_path)?)?;
+    let glyphs = contents
+        .par_iter()
+        .map(|(name, file)| {
+            let path = join(dir, file);
+            let mut glyph = crate::glif::parse(required(files, &path)?, Path::new(&path))?;
+            glyph.name = name.as_str().into();
+            Ok(glyph)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut layer = Layer::new(name.into(), Some(dir.into()));
+    for glyph in glyphs {
+        layer.insert_glyph(glyph);
+    }
+    let layerinfo_path = join(dir, "layerinfo.plist");
+    if let Some(data) = files.get(&layerinfo_path) {
+        let mut info = dictionary(data, &layerinfo_path)?;
+        if let Some(color) = info.remove("color") {
+            let color = color
+                .into_string()
+                .ok_or(norad::Error::ExpectedPlistString)?;
+            layer.color = Some(color.parse::<Color>().map_err(norad::Error::InvalidColor)?);
+        }
+        if let Some(lib) = info.remove("lib") {
+            layer.lib = lib.into_dictionary().ok_or_else(|| {
+                norad::Error::ExpectedPlistDictionary(format!("{} (lib)", layerinfo_path))
+            })?;
+        }
+    }
+    Ok(layer)
+}
+
+fn load_layers(files: &Files, format: FormatVersion) -> Result<LayerSet, Error> {
+    // Before UFO 3, there is only the default layer.
+    let layer_contents: Vec<(String, String)> = match files.get("layercontents.plist") {
+        Some(data) => plist::from_bytes(data)?,
+        None if format != FormatVersion::V3 => {
+            vec![("public.default".to_string(), "glyphs".to_string())]
+        }
+        None => return Err(norad::Error::MissingFile("layercontents.plist".to_string()).into()),
+    };
+    let mut layers = layer_contents
+        .iter()
+        .map(|(name, dir)| load_layer(files, name, dir))
+        .collect::<Result<Vec<_>, _>>()?;
+    let default = layer_contents
+        .iter()
+        .position(|(_, dir)| dir == "glyphs")
+        .ok_or(norad::Error::MissingDefaultLayer)?;
+    layers.rotate_left(default);
+    Ok(LayerSet::new(layers))
+}
+
+/// Moves the guideline libs in the font lib's `public.objectLibs` to the
+/// fontinfo guidelines.
+fn load_guideline_libs(info: &mut FontInfo, lib: &mut Plist) -> Result<(), Error> {
+    let mut libs = match lib.remove(PUBLIC_OBJECT_LIBS_KEY) {
+        Some(libs) => libs.into_dictionary().ok_or_else(|| {
+            norad::Error::ExpectedPlistDictionary(PUBLIC_OBJECT_LIBS_KEY.to_string())
+        })?,
+        None => return Ok(()),
+    };
+    for guideline in info.guidelines.iter_mut().flatten() {
+        let id = match guideline.identifier() {
+            Some(id) => id.as_str().to_string(),
+            None => continue,
+        };
+        if let Some(guideline_lib) = libs.remove(&id) {
+            let guideline_lib = guideline_lib.into_dictionary().ok_or_else(|| {
+                norad::Error::ExpectedPlistDictionary(format!("{}, {}", PUBLIC_OBJECT_LIBS_KEY, id))
+            })?;
+            guideline.replace_lib(guideline_lib);
+        }
+    }
+    Ok(())
+}
+
+fn load_fontinfo(data: &[u8], format: FormatVersion, lib: &mut Plist) -> Result<FontInfo, Error> {
+    if format == FormatVersion::V3 {
+        let mut info: FontInfo = plist::from_bytes(data)?;
+        info.validate()?;
+        load_guideline_libs(&mut info, lib)?;
+        return Ok(info);
+    }
+    let mut values = dictionary(data, "fontinfo.plist")?;
+    upconvert_fontinfo(&mut values);
+    let info: FontInfo = plist::from_value(&Value::Dictionary(values))?;
+    info.validate()
+        .map_err(|_| norad::Error::FontInfoUpconversion)?;
+    Ok(info)
+}
+
+/// Loads a UFO from `files`, reading the parts selected by `request`.
+pub(crate) fn load(files: &Files, request: DataRequest) -> Result<Font, Error> {
+    let mut meta: MetaInfo = plist::from_bytes(required(files, "metainfo.plist")?)?;
+    let format = meta.format_version;
+    if format == FormatVersion::V1 {
+        return Err(Error::UnsupportedFormat(1));
+    }
+    let mut lib = match files.get("lib.plist") {
+        Some(data) if request.lib => dictionary(data, "lib.plist")?,
+        _ => Plist::new(),
+    };
+    let font_info = match files.get("fontinfo.plist") {
+        Some(data) => Some(load_fontinfo(data, format, &mut lib)?),
+        None => None,
+    };
+    let mut groups = match files.get("groups.plist") {
+        Some(data) if request.groups => {
+            let groups: Groups = plist::from_bytes(data)?;
+            validate_groups(&groups).map_err(norad::Error::InvalidGroups)?;
+            Some(groups)
+        }
+        _ => None,
+    };
+    let mut kerning = match files.get("kerning.plist") {
+        Some(data) if request.kerning => Some(plist::from_bytes::<Kerning>(data)?),
+        _ => None,
+    };
+    let features = match files.get("features.fea") {
+        Some(data) if request.features => Some(
+            String::from_utf8(data.clone())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        ),
+        _ => None,
+    };
+    let layers = if request.layers {
+        load_layers(files, format)?
+    } else {
+        LayerSet::default()
+    };
+
+    if let (FormatVersion::V2, Some(old_groups)) = (format, &groups) {
+        let glyphs = layers
+            .iter()
+            .flat_map(|layer| layer.iter().map(|glyph| &*glyph.name))
+            .collect();
+        let (new_groups, new_kerning) =
+            upconvert_kerning(old_groups, &kerning.unwrap_or_default(), &glyphs);
+        validate_groups(&new_groups).map_err(norad::Error::GroupsUpconversionFailure)?;
+        groups = Some(new_groups);
+        kerning = Some(new_kerning);
+    }
+    meta.format_version = FormatVersion::V3;
+
+    let mut font = Font::new();
+    font.meta = meta;
+    font.font_info = font_info;
+    font.layers = layers;
+    font.lib = lib;
+    font.groups = groups;
+    font.kerning = kerning;
+    font.features = features;
+    font.data_request = request;
+    Ok(font)
+}
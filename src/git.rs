@@ -0,0 +1,119 @@
+//! Reading the files of a UFO from a git commit.
+//!
+//! The files are read straight from the object database with the `git`
+//! command, without checking anything out.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::files::Files;
+use crate::Error;
+
+fn git(repo: &Path) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo);
+    command
+}
+
+/// Runs `command` and returns its output, or its error message if it fails.
+fn output(command: &mut Command) -> Result<Vec<u8>, Error> {
+    let output = command.output()?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Git(message.trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// Returns the path and object id of every blob below `dir` in `revision`,
+/// with paths relative to `dir`.
+fn list_blobs(repo: &Path, revision: &str, dir: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut command = git(repo);
+    command.args(["ls-tree", "-r", "-z", "--full-tree", revision]);
+    if !dir.is_empty() {
+        command.arg("--").arg(dir);
+    }
+    let listing = output(&mut command)?;
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir)
+    };
+
+    let mut blobs = Vec::new();
+    for entry in listing.split(|b| *b == 0).filter(|e| !e.is_empty()) {
+        // Each entry is "<mode> <type> <object>\t<path>".
+        let entry = String::from_utf8_lossy(entry);
+        let (info, path) = entry
+            .split_once('\t')
+            .ok_or_else(|| Error::Git(format!("unexpected ls-tree output '{}'", entry)))?;
+        let mut info = info.split(' ');
+        if let (Some(_), Some("blob"), Some(id)) = (info.next(), info.next(), info.next()) {
+            if let Some(path) = path.strip_prefix(&prefix) {
+                blobs.push((path.to_string(), id.to_string()));
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+/// Reads the contents of the blobs `ids` with a single `git cat-file`.
+fn read_blobs(repo: &Path, ids: Vec<String>) -> Result<Vec<Vec<u8>>, Error> {
+    let mut child = git(repo)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let count = ids.len();
+    let writer = std::thread::spawn(move || -> std::io::Result<()> {
+        for id in ids {
+            writeln!(stdin, "{}", id)?;
+        }
+        Ok(())
+    });
+
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut blobs = Vec::with_capacity(count);
+    let mut header = String::new();
+    for _ in 0..count {
+        // Each object is "<object> <type> <size>\n<contents>\n".
+        header.clear();
+        stdout.read_line(&mut header)?;
+        let size = header
+            .trim_end()
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse::<usize>().ok())
+            .ok_or_else(|| Error::Git(format!("cannot read object: {}", header.trim_end())))?;
+        let mut data = vec![0; size + 1];
+        stdout.read_exact(&mut data)?;
+        data.pop();
+        blobs.push(data);
+    }
+    writer.join().expect("writer thread panicked")?;
+    child.wait()?;
+    Ok(blobs)
+}
+
+/// Reads the files of the UFO at `ufo_path` in `revision` of the repository
+/// at `repo`, keyed by their path relative to the UFO.
+pub(crate) fn read(repo: &Path, revision: &str, ufo_path: &str) -> Result<Files, Error> {
+    if revision.starts_with('-') {
+        return Err(Error::Git(format!("invalid revision '{}'", revision)));
+    }
+    let dir = ufo_path.replace('\\', "/");
+    let dir = dir.trim_matches('/').trim_start_matches("./");
+    let dir = if dir == "." { "" } else { dir };
+    let (paths, ids): (Vec<_>, Vec<_>) = list_blobs(repo, revision, dir)?.into_iter().unzip();
+    if paths.is_empty() {
+        return Err(Error::Git(format!(
+            "'{}' has no files in revision '{}'",
+            ufo_path, revision
+        )));
+    }
+    let blobs = read_blobs(repo, ids)?;
+    Ok(paths.into_iter().zip(blobs).collect())
+}
@@ -0,0 +1,565 @@
+//! Parsing of `.glif` data from memory.
+//!
+//! norad can only read glyphs from files, so this mirrors its parser for
+//! glyphs that come from elsewhere, e.g. a zip archive. The result is the same
+//! `Glyph` norad would load from a file with the same contents.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use norad::error::{ErrorKind, GlifError};
+use norad::{
+    AffineTransform, Anchor, Color, Component, Contour, ContourPoint, GlifVersion, Glyph,
+    Guideline, Identifier, Image, Line, Plist, PointType,
+};
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+const PUBLIC_OBJECT_LIBS_KEY: &str = "public.objectLibs";
+
+struct Parser<'a> {
+    reader: Reader<&'a [u8]>,
+    xml: &'a [u8],
+    path: &'a Path,
+    format: GlifVersion,
+    identifiers: HashSet<Identifier>,
+    glyph: Glyph,
+}
+
+/// Parses the contents of a `.glif` file. `path` is only used in errors.
+pub(crate) fn parse(xml: &[u8], path: &Path) -> Result<Glyph, norad::Error> {
+    let mut reader = Reader::from_reader(xml);
+    reader.trim_text(true);
+    let mut parser = Parser {
+        reader,
+        xml,
+        path,
+        format: GlifVersion::V2,
+        identifiers: HashSet::new(),
+        glyph: Glyph::new_named(""),
+    };
+    parser.parse_glyph()?;
+    parser.load_object_libs()?;
+    Ok(parser.glyph)
+}
+
+fn number(value: &str) -> Result<f32, ErrorKind> {
+    value.parse().map_err(|_| ErrorKind::BadNumber)
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ErrorKind) -> norad::Error {
+        norad::Error::Glif(GlifError {
+            path: Some(PathBuf::from(self.path)),
+            position: self.reader.buffer_position(),
+            kind,
+        })
+    }
+
+    fn value(&self, attr: &Attribute) -> Result<String, norad::Error> {
+        Ok(attr.unescape_and_decode_value(&self.reader)?)
+    }
+
+    fn add_identifier(&mut self, identifier: Option<&Identifier>) -> Result<(), norad::Error> {
+        if let Some(identifier) = identifier {
+            if self.format == GlifVersion::V1 {
+                return Err(self.error(ErrorKind::UnexpectedAttribute));
+            }
+            if !self.identifiers.insert(identifier.clone()) {
+                return Err(self.error(ErrorKind::DuplicateIdentifier));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_glyph(&mut self) -> Result<(), norad::Error> {
+        let mut buf = Vec::new();
+        loop {
+            match self.reader.read_event(&mut buf)? {
+                Event::Comment(_) | Event::Decl(_) => (),
+                Event::Start(ref start) if start.name() == b"glyph" => {
+                    let mut name = String::new();
+                    let mut format = None;
+                    for attr in start.attributes() {
+                        let attr = attr?;
+                        let value = self.value(&attr)?;
+                        match attr.key {
+                            b"name" => name = value,
+                            b"format" => {
+                                format = Some(match value.as_str() {
+                                    "1" => GlifVersion::V1,
+                                    "2" => GlifVersion::V2,
+                                    _ => return Err(self.error(ErrorKind::UnsupportedGlifVersion)),
+                                })
+                            }
+                            b"formatMinor" => (),
+                            _ => return Err(self.error(ErrorKind::UnexpectedAttribute)),
+                        }
+                    }
+                    match format {
+                        Some(format) if !name.is_empty() => {
+                            self.format = format;
+                            self.glyph.name = name.into();
+                            break;
+                        }
+                        _ => return Err(self.error(ErrorKind::WrongFirstElement)),
+                    }
+                }
+                _ => return Err(self.error(ErrorKind::WrongFirstElement)),
+            }
+        }
+
+        let (mut seen_advance, mut seen_image, mut seen_lib) = (false, false, false);
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::Start(start) => match start.name() {
+                    b"outline" => self.parse_outline()?,
+                    b"lib" if !seen_lib => {
+                        seen_lib = true;
+                        self.parse_lib()?;
+                    }
+                    b"note" if self.glyph.note.is_none() => self.parse_note()?,
+                    b"lib" | b"note" => return Err(self.error(ErrorKind::UnexpectedDuplicate)),
+                    _ => return Err(self.error(ErrorKind::UnexpectedTag)),
+                },
+                Event::Empty(start) => match start.name() {
+                    b"outline" => (),
+                    b"advance" if !seen_advance => {
+                        seen_advance = true;
+                        self.parse_advance(&start)?;
+                    }
+                    b"unicode" => self.parse_unicode(&start)?,
+                    b"anchor" if self.format == GlifVersion::V2 => self.parse_anchor(&start)?,
+                    b"guideline" if self.format == GlifVersion::V2 => {
+                        self.parse_guideline(&start)?
+                    }
+                    b"image" if self.format == GlifVersion::V2 && !seen_image => {
+                        seen_image = true;
+                        self.parse_image(&start)?;
+                    }
+                    b"advance" | b"image" => return Err(self.error(ErrorKind::UnexpectedDuplicate)),
+                    _ => return Err(self.error(ErrorKind::UnexpectedTag)),
+                },
+                Event::End(ref end) if end.name() == b"glyph" => break,
+                _ => return Err(self.error(ErrorKind::MissingCloseTag)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_outline(&mut self) -> Result<(), norad::Error> {
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::Start(ref start) if start.name() == b"contour" => {
+                    let start = start.to_owned();
+                    self.parse_contour(&start)?;
+                }
+                // Empty contours are meaningless and skipped, like norad does.
+                Event::Empty(ref start) if start.name() == b"contour" => (),
+                Event::Empty(ref start) if start.name() == b"component" => {
+                    let start = start.to_owned();
+                    self.parse_component(&start)?;
+                }
+                Event::Start(_) | Event::Empty(_) => {
+                    return Err(self.error(ErrorKind::UnexpectedTag))
+                }
+                Event::End(ref end) if end.name() == b"outline" => break,
+                Event::Eof => return Err(self.error(ErrorKind::UnexpectedEof)),
+                _ => return Err(self.error(ErrorKind::UnexpectedElement)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_contour(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        let mut identifier = None;
+        for attr in start.attributes() {
+            let attr = attr?;
+            match attr.key {
+                b"identifier" => {
+                    let value = self.value(&attr)?;
+                    identifier = Some(value.parse().map_err(|kind| self.error(kind))?);
+                }
+                _ => return Err(self.error(ErrorKind::UnexpectedAttribute)),
+            }
+        }
+        self.add_identifier(identifier.as_ref())?;
+
+        let mut points = Vec::new();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::Empty(ref start) if start.name() == b"point" => {
+                    let start = start.to_owned();
+                    points.push(self.parse_point(&start)?);
+                }
+                Event::End(ref end) if end.name() == b"contour" => break,
+                Event::Eof => return Err(self.error(ErrorKind::UnexpectedEof)),
+                _ => return Err(self.error(ErrorKind::UnexpectedElement)),
+            }
+        }
+        self.check_points(&points)?;
+
+        // Format 1 stores anchors as contours with a single named move point.
+        if self.format == GlifVersion::V1
+            && points.len() == 1
+            && points[0].typ == PointType::Move
+            && points[0].name.is_some()
+        {
+            let point = points.remove(0);
+            self.glyph
+                .anchors
+                .push(Anchor::new(point.x, point.y, point.name, None, None, None));
+        } else if !points.is_empty() {
+            self.glyph
+                .contours
+                .push(Contour::new(points, identifier, None));
+        }
+        Ok(())
+    }
+
+    /// Checks the point sequence of a contour the way norad's outline builder
+    /// does.
+    fn check_points(&self, points: &[ContourPoint]) -> Result<(), norad::Error> {
+        let mut offcurves = 0;
+        for (i, point) in points.iter().enumerate() {
+            match point.typ {
+                PointType::Move if i > 0 => return Err(self.error(ErrorKind::UnexpectedMove)),
+                PointType::Move => (),
+                PointType::Line if offcurves > 0 => {
+                    return Err(self.error(ErrorKind::UnexpectedPointAfterOffCurve))
+                }
+                PointType::Line => (),
+                PointType::OffCurve if point.smooth => {
+                    return Err(self.error(ErrorKind::UnexpectedSmooth))
+                }
+                PointType::OffCurve => offcurves += 1,
+                PointType::QCurve => offcurves = 0,
+                PointType::Curve if offcurves > 2 => {
+                    return Err(self.error(ErrorKind::TooManyOffCurves))
+                }
+                PointType::Curve => offcurves = 0,
+            }
+        }
+        if offcurves == 0 {
+            return Ok(());
+        }
+        if points[0].typ == PointType::Move {
+            return Err(self.error(ErrorKind::TrailingOffCurves));
+        }
+        // Closed contours wrap around to the first on-curve point.
+        for point in points {
+            match point.typ {
+                PointType::OffCurve => offcurves += 1,
+                PointType::Curve if offcurves > 2 => {
+                    return Err(self.error(ErrorKind::TooManyOffCurves))
+                }
+                PointType::Line => return Err(self.error(ErrorKind::UnexpectedPointAfterOffCurve)),
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_point(&mut self, start: &BytesStart) -> Result<ContourPoint, norad::Error> {
+        let (mut x, mut y, mut name, mut identifier) = (None, None, None, None);
+        let mut typ = PointType::OffCurve;
+        let mut smooth = false;
+        for attr in start.attributes() {
+            let attr = attr?;
+            let value = self.value(&attr)?;
+            match attr.key {
+                b"x" => x = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"y" => y = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"name" => name = Some(value),
+                b"type" => typ = value.parse().map_err(|kind| self.error(kind))?,
+                b"smooth" => smooth = value == "yes",
+                b"identifier" => identifier = Some(value.parse().map_err(|kind| self.error(kind))?),
+                _ => return Err(self.error(ErrorKind::UnexpectedPointField)),
+            }
+        }
+        let (x, y) = match (x, y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(self.error(ErrorKind::BadPoint)),
+        };
+        self.add_identifier(identifier.as_ref())?;
+        Ok(ContourPoint::new(x, y, typ, smooth, name, identifier, None))
+    }
+
+    fn parse_transform(
+        &self,
+        attr: &Attribute,
+        transform: &mut AffineTransform,
+    ) -> Result<bool, norad::Error> {
+        let field = match attr.key {
+            b"xScale" => &mut transform.x_scale,
+            b"xyScale" => &mut transform.xy_scale,
+            b"yxScale" => &mut transform.yx_scale,
+            b"yScale" => &mut transform.y_scale,
+            b"xOffset" => &mut transform.x_offset,
+            b"yOffset" => &mut transform.y_offset,
+            _ => return Ok(false),
+        };
+        *field = number(&self.value(attr)?).map_err(|kind| self.error(kind))?;
+        Ok(true)
+    }
+
+    fn parse_component(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        let (mut base, mut identifier) = (None, None);
+        let mut transform = AffineTransform::default();
+        for attr in start.attributes() {
+            let attr = attr?;
+            if self.parse_transform(&attr, &mut transform)? {
+                continue;
+            }
+            let value = self.value(&attr)?;
+            match attr.key {
+                b"base" => base = Some(value),
+                b"identifier" => identifier = Some(value.parse().map_err(|kind| self.error(kind))?),
+                _ => return Err(self.error(ErrorKind::UnexpectedComponentField)),
+            }
+        }
+        let base = base.ok_or_else(|| self.error(ErrorKind::BadComponent))?;
+        self.add_identifier(identifier.as_ref())?;
+        self.glyph
+            .components
+            .push(Component::new(base.into(), transform, identifier, None));
+        Ok(())
+    }
+
+    fn parse_lib(&mut self) -> Result<(), norad::Error> {
+        let start = self.reader.buffer_position();
+        let mut end = start;
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::End(ref e) if e.name() == b"lib" => break,
+                Event::Eof => return Err(self.error(ErrorKind::UnexpectedEof)),
+                _ => end = self.reader.buffer_position(),
+            }
+        }
+        self.glyph.lib = plist::Value::from_reader_xml(&self.xml[start..end])
+            .ok()
+            .and_then(plist::Value::into_dictionary)
+            .ok_or_else(|| self.error(ErrorKind::BadLib))?;
+        Ok(())
+    }
+
+    fn parse_note(&mut self) -> Result<(), norad::Error> {
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match self.reader.read_event(&mut buf)? {
+                Event::Text(text) => {
+                    self.glyph.note = Some(text.unescape_and_decode(&self.reader)?);
+                }
+                Event::End(ref e) if e.name() == b"note" => break,
+                Event::Eof => return Err(self.error(ErrorKind::UnexpectedEof)),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_advance(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        for attr in start.attributes() {
+            let attr = attr?;
+            let value = number(&self.value(&attr)?).map_err(|kind| self.error(kind))?;
+            match attr.key {
+                b"width" => self.glyph.width = value,
+                b"height" => self.glyph.height = value,
+                _ => return Err(self.error(ErrorKind::UnexpectedAttribute)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_unicode(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        for attr in start.attributes() {
+            let attr = attr?;
+            match attr.key {
+                b"hex" => {
+                    let chr = u32::from_str_radix(&self.value(&attr)?, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error(ErrorKind::BadHexValue))?;
+                    self.glyph.codepoints.push(chr);
+                }
+                _ => return Err(self.error(ErrorKind::UnexpectedAttribute)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_color(&self, value: &str) -> Result<Color, norad::Error> {
+        value.parse().map_err(|_| self.error(ErrorKind::BadColor))
+    }
+
+    fn parse_anchor(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        let (mut x, mut y, mut name, mut color, mut identifier) = (None, None, None, None, None);
+        for attr in start.attributes() {
+            let attr = attr?;
+            let value = self.value(&attr)?;
+            match attr.key {
+                b"x" => x = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"y" => y = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"name" => name = Some(value),
+                b"color" => color = Some(self.parse_color(&value)?),
+                b"identifier" => identifier = Some(value.parse().map_err(|kind| self.error(kind))?),
+                _ => return Err(self.error(ErrorKind::UnexpectedAnchorField)),
+            }
+        }
+        let (x, y) = match (x, y) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(self.error(ErrorKind::BadAnchor)),
+        };
+        self.add_identifier(identifier.as_ref())?;
+        self.glyph
+            .anchors
+            .push(Anchor::new(x, y, name, color, identifier, None));
+        Ok(())
+    }
+
+    fn parse_guideline(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        let (mut x, mut y, mut angle) = (None, None, None);
+        let (mut name, mut color, mut identifier) = (None, None, None);
+        for attr in start.attributes() {
+            let attr = attr?;
+            let value = self.value(&attr)?;
+            match attr.key {
+                b"x" => x = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"y" => y = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"angle" => angle = Some(number(&value).map_err(|kind| self.error(kind))?),
+                b"name" => name = Some(value),
+                b"color" => color = Some(self.parse_color(&value)?),
+                b"identifier" => identifier = Some(value.parse().map_err(|kind| self.error(kind))?),
+                _ => return Err(self.error(ErrorKind::UnexpectedGuidelineField)),
+            }
+        }
+        let line = match (x, y, angle) {
+            (Some(x), None, None) => Line::Vertical(x),
+            (None, Some(y), None) => Line::Horizontal(y),
+            (Some(x), Some(y), Some(degrees)) if (0.0..=360.0).contains(&degrees) => {
+                Line::Angle { x, y, degrees }
+            }
+            _ => return Err(self.error(ErrorKind::BadGuideline)),
+        };
+        self.add_identifier(identifier.as_ref())?;
+        self.glyph
+            .guidelines
+            .push(Guideline::new(line, name, color, identifier, None));
+        Ok(())
+    }
+
+    fn parse_image(&mut self, start: &BytesStart) -> Result<(), norad::Error> {
+        let (mut file_name, mut color) = (None, None);
+        let mut transform = AffineTransform::default();
+        for attr in start.attributes() {
+            let attr = attr?;
+            if self.parse_transform(&attr, &mut transform)? {
+                continue;
+            }
+            let value = self.value(&attr)?;
+            match attr.key {
+                b"fileName" => file_name = Some(PathBuf::from(value)),
+                b"color" => color = Some(self.parse_color(&value)?),
+                _ => return Err(self.error(ErrorKind::UnexpectedImageField)),
+            }
+        }
+        let file_name = file_name.ok_or_else(|| self.error(ErrorKind::BadImage))?;
+        self.glyph.image = Some(Image {
+            file_name,
+            color,
+            transform,
+        });
+        Ok(())
+    }
+
+    /// Moves the libs in the glyph lib's `public.objectLibs` to the objects
+    /// they belong to.
+    fn load_object_libs(&mut self) -> Result<(), norad::Error> {
+        let mut libs: Plist = match self.glyph.lib.remove(PUBLIC_OBJECT_LIBS_KEY) {
+            Some(libs) => libs
+                .into_dictionary()
+                .ok_or_else(|| self.error(ErrorKind::BadLib))?,
+            None => return Ok(()),
+        };
+        let mut take = |identifier: Option<&Identifier>| -> Result<Option<Plist>, ErrorKind> {
+            match identifier.and_then(|id| libs.remove(id.as_str())) {
+                Some(lib) => lib.into_dictionary().map(Some).ok_or(ErrorKind::BadLib),
+                None => Ok(None),
+            }
+        };
+        let glyph = &mut self.glyph;
+        let result = (|| {
+            for anchor in &mut glyph.anchors {
+                if let Some(lib) = take(anchor.identifier())? {
+                    anchor.replace_lib(lib);
+                }
+            }
+            for guideline in &mut glyph.guidelines {
+                if let Some(lib) = take(guideline.identifier())? {
+                    guideline.replace_lib(lib);
+                }
+            }
+            for contour in &mut glyph.contours {
+                if let Some(lib) = take(contour.identifier())? {
+                    contour.replace_lib(lib);
+                }
+                for point in &mut contour.points {
+                    if let Some(lib) = take(point.identifier())? {
+                        point.replace_lib(lib);
+                    }
+                }
+            }
+            for component in &mut glyph.components {
+                if let Some(lib) = take(component.identifier())? {
+                    component.replace_lib(lib);
+                }
+            }
+            Ok(())
+        })();
+        result.map_err(|kind| self.error(kind))
+    }
+}
+
+/// Returns the values of the `<unicode>` elements of a `.glif` file as they
+/// are written, including values that are not valid codepoints, without
+/// building the rest of the glyph. `path` is only used in errors.
+pub(crate) fn codepoints(xml: &[u8], path: &Path) -> Result<Vec<u32>, norad::Error> {
+    let mut reader = Reader::from_reader(xml);
+    let error = |reader: &Reader<&[u8]>, kind| {
+        norad::Error::Glif(GlifError {
+            path: Some(PathBuf::from(path)),
+            position: reader.buffer_position(),
+            kind,
+        })
+    };
+    let mut codepoints = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(ref start) | Event::Empty(ref start) if start.name() == b"unicode" => {
+                for attr in start.attributes() {
+                    let attr = attr?;
+                    if attr.key == b"hex" {
+                        let value = attr.unescape_and_decode_value(&reader)?;
+                        let value = u32::from_str_radix(&value, 16)
+                            .map_err(|_| error(&reader, ErrorKind::BadHexValue))?;
+                        codepoints.push(value);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(codepoints)
+}
@@ -0,0 +1,328 @@
+//! Content hashes of glyphs and fonts.
+//!
+//! The hashes are computed from the loaded data rather than from the files, so
+//! they stay the same when a UFO is only reformatted, e.g. by another editor
+//! or by reordering the keys of a lib. They are SHA-256 digests and stable
+//! across runs, platforms and versions of this crate.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use norad::{Color, Font, Glyph, Layer, Plist, PointType};
+use rayon::prelude::*;
+use sha2::{Digest as _, Sha256};
+
+/// A SHA-256 digest.
+pub type Digest = [u8; 32];
+
+/// Returns `digest` as lowercase hexadecimal.
+pub fn to_hex(digest: &Digest) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Feeds values into a hash in an unambiguous encoding: every value is
+/// preceded by a tag or its length.
+struct Canonical(Sha256);
+
+impl Canonical {
+    fn new() -> Self {
+        Canonical(Sha256::new())
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.0.update([tag]);
+    }
+
+    fn len(&mut self, len: usize) {
+        self.0.update((len as u64).to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) {
+        self.len(s.len());
+        self.0.update(s.as_bytes());
+    }
+
+    fn opt_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.tag(1);
+                self.str(s);
+            }
+            None => self.tag(0),
+        }
+    }
+
+    /// Hashes a number so that equal values, like 0.0 and -0.0, hash alike.
+    fn number(&mut self, n: f64) {
+        // Normalize -0.0, which compares equal to 0.0.
+        let n = if n == 0.0 { 0.0 } else { n };
+        self.0.update(n.to_bits().to_le_bytes());
+    }
+
+    fn color(&mut self, color: Option<&Color>) {
+        match color {
+            Some(c) => {
+                self.tag(1);
+                for n in [c.red, c.green, c.blue, c.alpha] {
+                    self.number(n as f64);
+                }
+            }
+            None => self.tag(0),
+        }
+    }
+
+    fn value(&mut self, value: &plist::Value) {
+        match value {
+            plist::Value::Array(items) => {
+                self.tag(b'a');
+                self.len(items.len());
+                items.iter().for_each(|v| self.value(v));
+            }
+            plist::Value::Dictionary(dict) => {
+                self.tag(b'd');
+                self.dict(dict);
+            }
+            plist::Value::Boolean(b) => {
+                self.tag(b'b');
+                self.tag(*b as u8);
+            }
+            plist::Value::Data(data) => {
+                self.tag(b'x');
+                self.len(data.len());
+                self.0.update(data);
+            }
+            plist::Value::Date(date) => {
+                self.tag(b't');
+                self.str(&date.to_xml_format());
+            }
+            plist::Value::Real(n) => {
+                self.tag(b'r');
+                self.number(*n);
+            }
+            plist::Value::Integer(n) => {
+                self.tag(b'i');
+                self.str(&n.to_string());
+            }
+            plist::Value::String(s) => {
+                self.tag(b's');
+                self.str(s);
+            }
+            plist::Value::Uid(uid) => {
+                self.tag(b'u');
+                self.0.update(uid.get().to_le_bytes());
+            }
+            _ => self.tag(b'?'),
+        }
+    }
+
+    /// Hashes a dictionary in key order, ignoring the order in the file.
+    fn dict(&mut self, dict: &Plist) {
+        let mut entries: Vec<_> = dict.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        self.len(entries.len());
+        for (key, value) in entries {
+            self.str(key);
+            self.value(value);
+        }
+    }
+
+    fn finish(self) -> Digest {
+        self.0.finalize().into()
+    }
+}
+
+fn point_type(typ: &PointType) -> u8 {
+    match typ {
+        PointType::Move => b'm',
+        PointType::Line => b'l',
+        PointType::OffCurve => b'o',
+        PointType::Curve => b'c',
+        PointType::QCurve => b'q',
+    }
+}
+
+/// Returns the hash of `glyph`'s own data: its advance, unicodes, outline,
+/// components, anchors and lib. The glyph's name, note, guidelines and image
+/// are left out, as they don't end up in a compiled font.
+pub fn glyph_hash(glyph: &Glyph) -> Digest {
+    let mut h = Canonical::new();
+    h.number(glyph.width as f64);
+    h.number(glyph.height as f64);
+    h.len(glyph.codepoints.len());
+    for c in &glyph.codepoints {
+        h.0.update((*c as u32).to_le_bytes());
+    }
+
+    h.len(glyph.contours.len());
+    for contour in &glyph.contours {
+        h.opt_str(contour.identifier().map(|i| i.as_str()));
+        h.len(contour.points.len());
+        for point in &contour.points {
+            h.number(point.x as f64);
+            h.number(point.y as f64);
+            h.tag(point_type(&point.typ));
+            h.tag(point.smooth as u8);
+            h.opt_str(point.name.as_deref());
+            h.opt_str(point.identifier().map(|i| i.as_str()));
+        }
+    }
+
+    h.len(glyph.components.len());
+    for component in &glyph.components {
+        h.str(&component.base);
+        let t = &component.transform;
+        for n in [
+            t.x_scale, t.xy_scale, t.yx_scale, t.y_scale, t.x_offset, t.y_offset,
+        ] {
+            h.number(n as f64);
+        }
+        h.opt_str(component.identifier().map(|i| i.as_str()));
+    }
+
+    h.len(glyph.anchors.len());
+    for anchor in &glyph.anchors {
+        h.number(anchor.x as f64);
+        h.number(anchor.y as f64);
+        h.opt_str(anchor.name.as_deref());
+        h.color(anchor.color.as_ref());
+        h.opt_str(anchor.identifier().map(|i| i.as_str()));
+    }
+
+    h.dict(&glyph.lib);
+    h.finish()
+}
+
+/// Combines the hash of a glyph with those of its component base glyphs,
+/// recursively. Missing base glyphs and component cycles contribute only
+/// their name.
+fn deep_hash<'a>(
+    layer: &'a Layer,
+    name: &'a str,
+    own: &HashMap<&str, Digest>,
+    deep: &mut HashMap<&'a str, Digest>,
+    visiting: &mut HashSet<&'a str>,
+) -> Option<Digest> {
+    if let Some(digest) = deep.get(name) {
+        return Some(*digest);
+    }
+    let glyph = layer.get_glyph(name)?;
+    if glyph.components.is_empty() {
+        return own.get(name).copied();
+    }
+    visiting.insert(name);
+    let mut h = Canonical::new();
+    h.0.update(own[name]);
+    for component in &glyph.components {
+        let base: &str = &component.base;
+        let base_hash = if visiting.contains(base) {
+            None
+        } else {
+            deep_hash(layer, base, own, deep, visiting)
+        };
+        h.str(base);
+        match base_hash {
+            Some(digest) => {
+                h.tag(1);
+                h.0.update(digest);
+            }
+            None => h.tag(0),
+        }
+    }
+    visiting.remove(name);
+    let digest = h.finish();
+    deep.insert(name, digest);
+    Some(digest)
+}
+
+/// Returns the hashes of all glyphs in `layer`, computed in parallel. With
+/// `with_components`, the hash of a composite glyph also covers its component
+/// base glyphs, recursively, so it changes when any of them does.
+pub fn layer_hashes(layer: &Layer, with_components: bool) -> BTreeMap<String, Digest> {
+    let glyphs: Vec<_> = layer.iter().collect();
+    let own: HashMap<&str, Digest> = glyphs
+        .par_iter()
+        .map(|g| (g.name.as_ref(), glyph_hash(g)))
+        .collect();
+    if !with_components {
+        return own.into_iter().map(|(n, d)| (n.to_string(), d)).collect();
+    }
+
+    let mut names: Vec<&str> = own.keys().copied().collect();
+    names.sort_unstable();
+    let mut deep = HashMap::new();
+    let mut visiting = HashSet::new();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            deep_hash(layer, name, &own, &mut deep, &mut visiting).map(|d| (name.to_string(), d))
+        })
+        .collect()
+}
+
+/// The content hashes of a font.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontHashes {
+    /// The hash of every glyph, by layer and glyph name.
+    pub layers: BTreeMap<String, BTreeMap<String, Digest>>,
+    /// The hash of the whole font: the glyph hashes, the layers' order, color
+    /// and lib, the fontinfo, kerning, groups, features and the font lib.
+    pub font: Digest,
+}
+
+/// Returns the content hashes of every glyph in `font` and of the whole
+/// font. See [`layer_hashes`] for `with_components`.
+pub fn font_hashes(font: &Font, with_components: bool) -> FontHashes {
+    let layers: Vec<&Layer> = font.layers.iter().collect();
+    let hashes: Vec<_> = layers
+        .par_iter()
+        .map(|layer| layer_hashes(layer, with_components))
+        .collect();
+
+    let mut h = Canonical::new();
+    h.len(layers.len());
+    for (layer, glyphs) in layers.iter().zip(&hashes) {
+        h.str(layer.name());
+        h.color(layer.color.as_ref());
+        h.dict(&layer.lib);
+        h.len(glyphs.len());
+        for (name, digest) in glyphs {
+            h.str(name);
+            h.0.update(digest);
+        }
+    }
+
+    let info = font
+        .font_info
+        .as_ref()
+        .and_then(|info| plist::to_value(info).ok())
+        .and_then(plist::Value::into_dictionary)
+        .unwrap_or_default();
+    h.dict(&info);
+    let kerning = font.kerning.as_ref();
+    h.len(kerning.map_or(0, |k| k.len()));
+    for (first, seconds) in kerning.into_iter().flatten() {
+        h.str(first);
+        h.len(seconds.len());
+        for (second, value) in seconds {
+            h.str(second);
+            h.number(*value as f64);
+        }
+    }
+    let groups = font.groups.as_ref();
+    h.len(groups.map_or(0, |g| g.len()));
+    for (name, members) in groups.into_iter().flatten() {
+        h.str(name);
+        h.len(members.len());
+        members.iter().for_each(|m| h.str(m));
+    }
+    h.opt_str(font.features.as_deref());
+    h.dict(&font.lib);
+
+    FontHashes {
+        layers: layers
+            .iter()
+            .map(|l| l.name().to_string())
+            .zip(hashes)
+            .collect(),
+        font: h.finish(),
+    }
+}
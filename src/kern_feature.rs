@@ -0,0 +1,423 @@
+//! Generating `kern` feature code from a font's kerning and kerning groups.
+//!
+//! Kerning groups become glyph classes and the pairs are written so that the
+//! precedence of the [UFO specification][kerning] holds in the compiled font:
+//! glyph-glyph pairs come first, then glyph-group and group-glyph exceptions
+//! as `enum` pairs, then group-group pairs. Exceptions leave out the glyph
+//! pairs an earlier, more specific pair already covers.
+//!
+//! [kerning]: https://unifiedfontobject.org/versions/ufo3/kerning.plist/
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+use norad::{Font, Groups, Kerning, Layer};
+
+use crate::kerning::{KerningLookup, KERN1_PREFIX, KERN2_PREFIX};
+use crate::mark_feature::ot_round;
+use crate::validate::KerningIssue;
+
+/// The generated `kern` feature.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KernFeature {
+    /// The feature file code: the class definitions and the `kern` feature.
+    pub fea: String,
+    /// The pairs and group members left out because they refer to missing
+    /// glyphs or groups.
+    pub warnings: Vec<KerningIssue>,
+}
+
+/// The writing direction of a kerning pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// The OpenType tags of the right-to-left scripts that [`rtl_script`]
+/// recognizes.
+const RTL_SCRIPTS: &[&str] = &[
+    "adlm", "arab", "hebr", "mand", "nko", "samr", "syrc", "thaa",
+];
+
+/// Returns true if `codepoint` is in a block of a right-to-left script.
+fn is_rtl(codepoint: u32) -> bool {
+    matches!(
+        codepoint,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFE | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+/// Returns the OpenType tag of the right-to-left script of `codepoint`, for
+/// the common ones.
+fn rtl_script(codepoint: u32) -> Option<&'static str> {
+    match codepoint {
+        0x0590..=0x05FF | 0xFB1D..=0xFB4F => Some("hebr"),
+        0x0600..=0x06FF
+        | 0x0750..=0x077F
+        | 0x0870..=0x08FF
+        | 0xFB50..=0xFDFF
+        | 0xFE70..=0xFEFE
+        | 0x1EE00..=0x1EEFF => Some("arab"),
+        0x0700..=0x074F | 0x0860..=0x086F => Some("syrc"),
+        0x0780..=0x07BF => Some("thaa"),
+        0x07C0..=0x07FF => Some("nko"),
+        0x0800..=0x083F => Some("samr"),
+        0x0840..=0x085F => Some("mand"),
+        0x1E900..=0x1E95F => Some("adlm"),
+        _ => None,
+    }
+}
+
+/// Returns the glyphs of `layer` that belong to a right-to-left script: the
+/// ones with such a codepoint, and unencoded ones like `alef-ar.fina` whose
+/// name up to the first period is that of such a glyph.
+fn rtl_glyphs(layer: &Layer) -> HashSet<&str> {
+    let encoded: HashSet<&str> = layer
+        .iter()
+        .filter(|g| g.codepoints.iter().any(|c| is_rtl(*c as u32)))
+        .map(|g| g.name.as_ref())
+        .collect();
+    let unencoded = layer.iter().filter(|g| {
+        g.codepoints.is_empty()
+            && g.name
+                .split('.')
+                .next()
+                .is_some_and(|base| encoded.contains(base))
+    });
+    let unencoded: Vec<&str> = unencoded.map(|g| g.name.as_ref()).collect();
+    encoded.into_iter().chain(unencoded).collect()
+}
+
+/// Returns a glyph class name for the kerning group `group`, made unique
+/// among `used`.
+fn class_name(group: &str, used: &mut HashSet<String>) -> String {
+    let name = match group.strip_prefix(KERN1_PREFIX) {
+        Some(rest) => format!("kern1.{}", rest),
+        None => format!("kern2.{}", group.trim_start_matches(KERN2_PREFIX)),
+    };
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let mut unique = name.clone();
+    let mut i = 1;
+    while !used.insert(unique.clone()) {
+        unique = format!("{}_{}", name, i);
+        i += 1;
+    }
+    format!("@{}", unique)
+}
+
+/// A pair to write: both sides as they appear in the feature code, and the
+/// kerning pair it comes from.
+struct Statement<'a> {
+    enumerate: bool,
+    first: String,
+    second: String,
+    value: f32,
+    pair: (&'a str, &'a str),
+}
+
+impl Statement<'_> {
+    fn to_fea(&self, direction: Direction) -> String {
+        let value = ot_round(self.value as f64);
+        let value = match direction {
+            Direction::Ltr => value.to_string(),
+            Direction::Rtl => format!("<{} 0 {} 0>", value, value),
+        };
+        let keyword = if self.enumerate { "enum pos" } else { "pos" };
+        format!("{} {} {} {};", keyword, self.first, self.second, value)
+    }
+}
+
+/// Returns one side of an exception: the class of the whole group, or an
+/// inline class of the members that aren't covered yet.
+fn exception_side(class: &str, members: &[&str], remaining: &[&str]) -> String {
+    if remaining.len() == members.len() {
+        class.to_string()
+    } else {
+        format!("[{}]", remaining.join(" "))
+    }
+}
+
+/// Writes a lookup with the `statements`, indented by `indent`.
+fn write_lookup(
+    fea: &mut String,
+    indent: &str,
+    name: &str,
+    direction: Direction,
+    statements: &[Statement],
+) {
+    writeln!(fea, "{}lookup {} {{", indent, name).unwrap();
+    writeln!(fea, "{}    lookupflag IgnoreMarks;", indent).unwrap();
+    for statement in statements {
+        writeln!(fea, "{}    {}", indent, statement.to_fea(direction)).unwrap();
+    }
+    writeln!(fea, "{}}} {};", indent, name).unwrap();
+}
+
+/// Generates the `kern` feature for the kerning of `font`, checked against
+/// the glyphs of its default layer.
+///
+/// With `split_direction`, pairs whose groups, or the groups of their
+/// glyphs, have a glyph of a right-to-left script, as found from the
+/// codepoints, go into a separate lookup with value records that adjust both
+/// placement and advance, so an exception always shares the lookup of the
+/// group pair it overrides. The lookups are defined outside the feature and
+/// registered under `script` statements: the right-to-left lookup for the
+/// right-to-left scripts of its glyphs and of the features' `languagesystem`
+/// statements, the other one for `DFLT` and the other declared scripts, with
+/// their declared languages. If the scripts of the right-to-left glyphs are
+/// unknown, both lookups are registered for `DFLT`. Otherwise all pairs share
+/// one lookup.
+pub fn kern_feature(font: &Font, split_direction: bool) -> KernFeature {
+    let (no_kerning, no_groups) = (Kerning::new(), Groups::new());
+    let kerning = font.kerning.as_ref().unwrap_or(&no_kerning);
+    let groups = font.groups.as_ref().unwrap_or(&no_groups);
+    let layer = font.default_layer();
+    let lookup = KerningLookup::new(kerning, groups);
+    let mut warnings = Vec::new();
+
+    // A glyph in several groups of one side is kept in the group the lookup
+    // uses, so the classes of a side don't overlap.
+    let mut members: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (group, glyphs) in groups {
+        let group_of = if group.starts_with(KERN1_PREFIX) {
+            KerningLookup::first_group
+        } else if group.starts_with(KERN2_PREFIX) {
+            KerningLookup::second_group
+        } else {
+            continue;
+        };
+        let mut kept: Vec<&str> = Vec::new();
+        for glyph in glyphs {
+            if !layer.contains_glyph(glyph) {
+                warnings.push(KerningIssue::MissingGroupMember {
+                    group: group.clone(),
+                    glyph: glyph.to_string(),
+                });
+            } else if group_of(&lookup, glyph) == Some(group.as_str()) && !kept.contains(&&**glyph)
+            {
+                kept.push(glyph);
+            }
+        }
+        members.insert(group, kept);
+    }
+    let mut used = HashSet::new();
+    let classes: BTreeMap<&str, String> = members
+        .keys()
+        .map(|group| (*group, class_name(group, &mut used)))
+        .collect();
+
+    // Glyph-glyph, glyph-group, group-glyph and group-group pairs.
+    let mut pairs: [Vec<(&str, &str, f32)>; 4] = Default::default();
+    for (first, seconds) in kerning {
+        for (second, value) in seconds {
+            let mut missing = Vec::new();
+            for (name, prefix) in [(first, KERN1_PREFIX), (second, KERN2_PREFIX)] {
+                if name.starts_with(prefix) && !members.contains_key(name.as_str()) {
+                    missing.push(KerningIssue::MissingGroup {
+                        first: first.clone(),
+                        second: second.clone(),
+                        group: name.clone(),
+                    });
+                } else if !name.starts_with(prefix) && !layer.contains_glyph(name) {
+                    missing.push(KerningIssue::MissingGlyph {
+                        first: first.clone(),
+                        second: second.clone(),
+                        glyph: name.clone(),
+                    });
+                }
+            }
+            if !missing.is_empty() {
+                warnings.extend(missing);
+                continue;
+            }
+            let kind = 2 * first.starts_with(KERN1_PREFIX) as usize
+                + second.starts_with(KERN2_PREFIX) as usize;
+            pairs[kind].push((first.as_str(), second.as_str(), *value));
+        }
+    }
+
+    let mut covered: HashSet<(&str, &str)> = HashSet::new();
+    let mut statements = Vec::new();
+    let mut used_classes = BTreeSet::new();
+    for &(first, second, value) in &pairs[0] {
+        covered.insert((first, second));
+        statements.push(Statement {
+            enumerate: false,
+            first: first.to_string(),
+            second: second.to_string(),
+            value,
+            pair: (first, second),
+        });
+    }
+    for &(first, group, value) in &pairs[1] {
+        let seconds = &members[group];
+        let remaining: Vec<&str> = seconds
+            .iter()
+            .copied()
+            .filter(|s| !covered.contains(&(first, *s)))
+            .collect();
+        if remaining.is_empty() {
+            continue;
+        }
+        if remaining.len() == seconds.len() {
+            used_classes.insert(group);
+        }
+        let second = exception_side(&classes[group], seconds, &remaining);
+        covered.extend(remaining.iter().map(|s| (first, *s)));
+        statements.push(Statement {
+            enumerate: true,
+            first: first.to_string(),
+            second,
+            value,
+            pair: (first, group),
+        });
+    }
+    for &(group, second, value) in &pairs[2] {
+        let firsts = &members[group];
+        let remaining: Vec<&str> = firsts
+            .iter()
+            .copied()
+            .filter(|f| !covered.contains(&(*f, second)))
+            .collect();
+        if remaining.is_empty() {
+            continue;
+        }
+        if remaining.len() == firsts.len() {
+            used_classes.insert(group);
+        }
+        let first = exception_side(&classes[group], firsts, &remaining);
+        statements.push(Statement {
+            enumerate: true,
+            first,
+            second: second.to_string(),
+            value,
+            pair: (group, second),
+        });
+    }
+    for &(first, second, value) in &pairs[3] {
+        if members[first].is_empty() || members[second].is_empty() {
+            continue;
+        }
+        used_classes.extend([first, second]);
+        statements.push(Statement {
+            enumerate: false,
+            first: classes[first].clone(),
+            second: classes[second].clone(),
+            value,
+            pair: (first, second),
+        });
+    }
+
+    let mut fea = String::new();
+    for group in &used_classes {
+        writeln!(fea, "{} = [{}];", classes[group], members[group].join(" ")).unwrap();
+    }
+
+    if !split_direction {
+        if !statements.is_empty() {
+            fea.push_str(if fea.is_empty() { "" } else { "\n" });
+            fea.push_str("feature kern {\n");
+            write_lookup(&mut fea, "    ", "kern_pairs", Direction::Ltr, &statements);
+            fea.push_str("} kern;\n");
+        }
+        return KernFeature { fea, warnings };
+    }
+
+    // A pair goes where the group pair it belongs to goes, so that exceptions
+    // share the lookup of the pairs they override.
+    let rtl = rtl_glyphs(layer);
+    let side_is_rtl = |name: &str, first: bool| {
+        let group = if members.contains_key(name) {
+            Some(name)
+        } else if first {
+            lookup.first_group(name)
+        } else {
+            lookup.second_group(name)
+        };
+        match group.and_then(|group| members.get(group)) {
+            Some(glyphs) => glyphs.iter().any(|g| rtl.contains(g)),
+            None => rtl.contains(name),
+        }
+    };
+    let (rtl_statements, ltr_statements): (Vec<_>, Vec<_>) =
+        statements.into_iter().partition(|statement| {
+            let (first, second) = statement.pair;
+            side_is_rtl(first, true) || side_is_rtl(second, false)
+        });
+
+    // The lookups are registered per script: the right-to-left one for the
+    // scripts of its glyphs, the other one for the rest.
+    let mut systems: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    systems.entry("DFLT".to_string()).or_default();
+    for (script, language) in
+        crate::features::language_systems(font.features.as_deref().unwrap_or_default())
+    {
+        let languages = systems.entry(script).or_default();
+        if language != "dflt" {
+            languages.insert(language);
+        }
+    }
+    let rtl_scripts: BTreeSet<&str> = rtl_statements
+        .iter()
+        .flat_map(|statement| [statement.pair.0, statement.pair.1])
+        .flat_map(|name| members.get(name).cloned().unwrap_or_else(|| vec![name]))
+        .filter_map(|name| {
+            // Unencoded glyphs like `alef-ar.fina` take the script of their
+            // base glyph, as in `rtl_glyphs`.
+            let glyph = layer.get_glyph(name)?;
+            match glyph.codepoints.is_empty() {
+                true => layer.get_glyph(name.split('.').next()?),
+                false => Some(glyph),
+            }
+        })
+        .flat_map(|glyph| glyph.codepoints.iter())
+        .filter_map(|c| rtl_script(*c as u32))
+        .collect();
+    for script in &rtl_scripts {
+        systems.entry(script.to_string()).or_default();
+    }
+    let is_rtl_script = |script: &str| {
+        RTL_SCRIPTS.contains(&script) || (rtl_scripts.is_empty() && script == "DFLT")
+    };
+
+    let mut registered = String::new();
+    for (script, languages) in &systems {
+        let mut names = Vec::new();
+        if !is_rtl_script(script) || script == "DFLT" {
+            names.extend((!ltr_statements.is_empty()).then_some("kern_ltr"));
+        }
+        if is_rtl_script(script) {
+            names.extend((!rtl_statements.is_empty()).then_some("kern_rtl"));
+        }
+        if names.is_empty() {
+            continue;
+        }
+        writeln!(registered, "    script {};", script).unwrap();
+        for name in names {
+            writeln!(registered, "    lookup {};", name).unwrap();
+        }
+        for language in languages {
+            writeln!(registered, "    language {};", language).unwrap();
+        }
+    }
+    for (name, direction, statements) in [
+        ("kern_ltr", Direction::Ltr, &ltr_statements),
+        ("kern_rtl", Direction::Rtl, &rtl_statements),
+    ] {
+        if !statements.is_empty() {
+            fea.push_str(if fea.is_empty() { "" } else { "\n" });
+            write_lookup(&mut fea, "", name, direction, statements);
+        }
+    }
+    if !registered.is_empty() {
+        write!(fea, "\nfeature kern {{\n{}}} kern;\n", registered).unwrap();
+    }
+    KernFeature { fea, warnings }
+}
@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use norad::{Font, Groups, Kerning};
+use rayon::prelude::*;
+
+/// Prefix of groups on the first (left in LTR) side of a kerning pair.
+pub const KERN1_PREFIX: &str = "public.kern1.";
+/// Prefix of groups on the second (right in LTR) side of a kerning pair.
+pub const KERN2_PREFIX: &str = "public.kern2.";
+
+/// Resolves kerning values between glyphs, taking kerning groups into account.
+///
+/// Lookups follow the precedence of the [UFO specification][kerning]: a
+/// glyph-glyph pair wins over a glyph-group pair, which wins over a group-glyph
+/// pair, which wins over a group-group pair.
+///
+/// [kerning]: https://unifiedfontobject.org/versions/ufo3/kerning.plist/
+#[derive(Debug, Clone, Default)]
+pub struct KerningLookup {
+    kerning: Kerning,
+    groups: Groups,
+    first_group: HashMap<String, String>,
+    second_group: HashMap<String, String>,
+}
+
+impl KerningLookup {
+    /// Builds a lookup from a font's kerning and groups.
+    ///
+    /// Groups that aren't kerning groups are ignored. Should a glyph be in more
+    /// than one kerning group of the same side, the group that sorts first is
+    /// used.
+    pub fn new(kerning: &Kerning, groups: &Groups) -> Self {
+        let mut first_group = HashMap::new();
+        let mut second_group = HashMap::new();
+        for (name, members) in groups.iter().rev() {
+            let side = if name.starts_with(KERN1_PREFIX) {
+                &mut first_group
+            } else if name.starts_with(KERN2_PREFIX) {
+                &mut second_group
+            } else {
+                continue;
+            };
+            for member in members {
+                side.insert(member.to_string(), name.clone());
+            }
+        }
+        KerningLookup {
+            kerning: kerning.clone(),
+            groups: groups.clone(),
+            first_group,
+            second_group,
+        }
+    }
+
+    /// Builds a lookup from the kerning and groups of `font`.
+    pub fn from_font(font: &Font) -> Self {
+        Self::new(
+            font.kerning.as_ref().unwrap_or(&Kerning::new()),
+            font.groups.as_ref().unwrap_or(&Groups::new()),
+        )
+    }
+
+    /// Returns the first-side kerning group of `glyph`, if any.
+    pub fn first_group(&self, glyph: &str) -> Option<&str> {
+        self.first_group.get(glyph).map(|g| g.as_str())
+    }
+
+    /// Returns the second-side kerning group of `glyph`, if any.
+    pub fn second_group(&self, glyph: &str) -> Option<&str> {
+        self.second_group.get(glyph).map(|g| g.as_str())
+    }
+
+    fn pair(&self, first: &str, second: &str) -> Option<f32> {
+        self.kerning.get(first).and_then(|v| v.get(second)).copied()
+    }
+
+    /// Returns the kerning value between `first` and `second`, or `None` if the
+    /// pair isn't kerned.
+    ///
+    /// Both sides may be glyph names or kerning group names.
+    pub fn get(&self, first: &str, second: &str) -> Option<f32> {
+        let first_group = self.first_group(first);
+        let second_group = self.second_group(second);
+        self.pair(first, second)
+            .or_else(|| second_group.and_then(|g| self.pair(first, g)))
+            .or_else(|| first_group.and_then(|g| self.pair(g, second)))
+            .or_else(|| first_group.and_then(|f| second_group.and_then(|s| self.pair(f, s))))
+    }
+
+    fn expand<'a>(&'a self, name: &'a str, prefix: &str) -> Vec<&'a str> {
+        if name.starts_with(prefix) {
+            self.groups
+                .get(name)
+                .map(|members| members.iter().map(|m| m.as_ref()).collect())
+                .unwrap_or_default()
+        } else {
+            vec![name]
+        }
+    }
+
+    /// Resolves all group kerning into glyph-glyph pairs.
+    ///
+    /// Every pair of glyphs covered by some kerning entry is listed with the
+    /// value `get` would return for it.
+    pub fn flatten(&self) -> BTreeMap<(String, String), f32> {
+        let mut candidates = BTreeSet::new();
+        for (first, seconds) in &self.kerning {
+            let firsts = self.expand(first, KERN1_PREFIX);
+            for second in seconds.keys() {
+                let seconds = self.expand(second, KERN2_PREFIX);
+                for f in &firsts {
+                    for s in &seconds {
+                        candidates.insert((*f, *s));
+                    }
+                }
+            }
+        }
+        candidates
+            .into_par_iter()
+            .filter_map(|(f, s)| {
+                self.get(f, s)
+                    .map(|value| ((f.to_string(), s.to_string()), value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup() -> KerningLookup {
+        let mut groups = Groups::new();
+        groups.insert("public.kern1.O".into(), vec!["O".into(), "Q".into()]);
+        groups.insert("public.kern2.V".into(), vec!["V".into(), "W".into()]);
+        let mut kerning = Kerning::new();
+        let pairs = [
+            ("O", "V", -10.0),
+            ("O", "public.kern2.V", -20.0),
+            ("public.kern1.O", "W", -30.0),
+            ("public.kern1.O", "public.kern2.V", -40.0),
+        ];
+        for (first, second, value) in pairs {
+            kerning
+                .entry(first.to_string())
+                .or_default()
+                .insert(second.to_string(), value);
+        }
+        KerningLookup::new(&kerning, &groups)
+    }
+
+    #[test]
+    fn precedence() {
+        let lookup = lookup();
+        assert_eq!(lookup.get("O", "V"), Some(-10.0));
+        assert_eq!(lookup.get("O", "W"), Some(-20.0));
+        assert_eq!(lookup.get("Q", "W"), Some(-30.0));
+        assert_eq!(lookup.get("Q", "V"), Some(-40.0));
+        assert_eq!(lookup.get("public.kern1.O", "public.kern2.V"), Some(-40.0));
+        assert_eq!(lookup.get("V", "O"), None);
+    }
+
+    #[test]
+    fn flatten_resolves_every_pair() {
+        let flat = lookup().flatten();
+        let pair = |f: &str, s: &str| flat.get(&(f.to_string(), s.to_string())).copied();
+        assert_eq!(flat.len(), 4);
+        assert_eq!(pair("O", "V"), Some(-10.0));
+        assert_eq!(pair("O", "W"), Some(-20.0));
+        assert_eq!(pair("Q", "W"), Some(-30.0));
+        assert_eq!(pair("Q", "V"), Some(-40.0));
+    }
+
+    #[test]
+    fn first_sorting_group_wins() {
+        let mut groups = Groups::new();
+        groups.insert("public.kern1.A".into(), vec!["A".into()]);
+        groups.insert("public.kern1.B".into(), vec!["A".into()]);
+        groups.insert("other".into(), vec!["A".into()]);
+        let lookup = KerningLookup::new(&Kerning::new(), &groups);
+        assert_eq!(lookup.first_group("A"), Some("public.kern1.A"));
+        assert_eq!(lookup.second_group("A"), None);
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use norad::{DataRequest, Font, Layer};
+
+use crate::Error;
+
+/// Options controlling which parts of a UFO are loaded.
+///
+/// By default, everything is loaded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadOptions {
+    request: DataRequest,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            request: DataRequest::all(),
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Returns options that load everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns options that load nothing but the UFO's metadata and fontinfo.
+    pub fn none() -> Self {
+        LoadOptions {
+            request: DataRequest::none(),
+        }
+    }
+
+    /// Whether to load glyph layers.
+    pub fn layers(&mut self, b: bool) -> &mut Self {
+        self.request.layers(b);
+        self
+    }
+
+    /// Whether to load the font lib and glyph libs.
+    pub fn lib(&mut self, b: bool) -> &mut Self {
+        self.request.lib(b);
+        self
+    }
+
+    /// Whether to load `groups.plist`.
+    pub fn groups(&mut self, b: bool) -> &mut Self {
+        self.request.groups(b);
+        self
+    }
+
+    /// Whether to load `kerning.plist`.
+    pub fn kerning(&mut self, b: bool) -> &mut Self {
+        self.request.kerning(b);
+        self
+    }
+
+    /// Whether to load `features.fea`.
+    pub fn features(&mut self, b: bool) -> &mut Self {
+        self.request.features(b);
+        self
+    }
+
+    /// Loads the UFO at `path`, which is either a directory or a `.ufoz`
+    /// archive.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<Font, Error> {
+        let path = path.as_ref();
+        if path.is_file() && crate::ufoz::is_ufoz(path) {
+            return crate::ufoz::load(path, self.request);
+        }
+        Ok(Font::load_requested_data(path, self.request)?)
+    }
+
+    /// Loads a UFO from the contents of its files, keyed by their paths
+    /// relative to the UFO with `/` as separator, e.g. `glyphs/a.glif`.
+    ///
+    /// The files are parsed in memory; nothing is read from or written to
+    /// disk. The result is the same as loading them from a UFO directory,
+    /// UFO 2 sources included, except that the glyphs get file names from
+    /// their names when saved, as new glyphs do. UFO 1 is not supported.
+    pub fn load_from_files(&self, files: &BTreeMap<String, Vec<u8>>) -> Result<Font, Error> {
+        crate::files::load(files, self.request)
+    }
+
+    /// Loads the UFO at `ufo_path`, relative to the root of the git
+    /// repository at `repo`, as it is in `revision` (a commit, branch, tag or
+    /// any other revision git understands).
+    ///
+    /// The files are read from the repository's object database using the
+    /// `git` command and parsed in memory as by
+    /// [`LoadOptions::load_from_files`]; nothing is checked out or written to
+    /// disk.
+    pub fn load_from_git(
+        &self,
+        repo: impl AsRef<Path>,
+        revision: &str,
+        ufo_path: &str,
+    ) -> Result<Font, Error> {
+        let files = crate::git::read(repo.as_ref(), revision, ufo_path)?;
+        crate::files::load(&files, self.request)
+    }
+}
+
+/// Loads the complete UFO at `path`.
+pub fn load(path: impl AsRef<Path>) -> Result<Font, Error> {
+    LoadOptions::default().load(path)
+}
+
+/// Loads a complete UFO from the contents of its files; see
+/// [`LoadOptions::load_from_files`].
+pub fn load_from_files(files: &BTreeMap<String, Vec<u8>>) -> Result<Font, Error> {
+    LoadOptions::default().load_from_files(files)
+}
+
+/// Loads a complete UFO from a git revision; see
+/// [`LoadOptions::load_from_git`].
+pub fn load_from_git(
+    repo: impl AsRef<Path>,
+    revision: &str,
+    ufo_path: &str,
+) -> Result<Font, Error> {
+    LoadOptions::default().load_from_git(repo, revision, ufo_path)
+}
+
+/// Returns the layer named `name`, or the default layer if `name` is `None`.
+pub fn layer_or_default<'a>(font: &'a Font, name: Option<&str>) -> Result<&'a Layer, Error> {
+    match name {
+        Some(name) => font
+            .layers
+            .get(name)
+            .ok_or_else(|| Error::MissingLayer(name.to_string())),
+        None => Ok(font.default_layer()),
+    }
+}
+
+/// Returns the layer named `name` for editing, or the default layer if `name`
+/// is `None`.
+pub fn layer_or_default_mut<'a>(
+    font: &'a mut Font,
+    name: Option<&str>,
+) -> Result<&'a mut Layer, Error> {
+    match name {
+        Some(name) => font
+            .layers
+            .get_mut(name)
+            .ok_or_else(|| Error::MissingLayer(name.to_string())),
+        None => Ok(font.default_layer_mut()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::files::Files;
+
+    /// Asserts that two fonts are equal apart from the file names of their
+    /// glyphs, which norad only keeps when it reads them from disk.
+    pub(crate) fn assert_same_font(found: &Font, expected: &Font) {
+        assert_eq!(found.layers.len(), expected.layers.len());
+        for (layer, other) in found.layers.iter().zip(expected.layers.iter()) {
+            assert_eq!(layer.name(), other.name());
+            assert_eq!(layer.path(), other.path());
+            assert_eq!((&layer.color, &layer.lib), (&other.color, &other.lib));
+            assert_eq!(
+                layer.iter().collect::<Vec<_>>(),
+                other.iter().collect::<Vec<_>>()
+            );
+        }
+        let mut found = found.clone();
+        found.layers = expected.layers.clone();
+        assert_eq!(&found, expected);
+    }
+
+    fn read_files(ufo: &Path) -> Files {
+        crate::save::files(ufo)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                let name = path.to_string_lossy().replace('\\', "/");
+                (name, fs::read(ufo.join(&path)).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn files_load_like_dir() {
+        for entry in fs::read_dir("tests/data").unwrap() {
+            let ufo = entry.unwrap().path();
+            if ufo.extension().is_some_and(|ext| ext == "ufo") {
+                assert_same_font(
+                    &load_from_files(&read_files(&ufo)).unwrap(),
+                    &load(&ufo).unwrap(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ufo2_files_load_like_dir() {
+        let plist = |body: &str| {
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <plist version=\"1.0\">\n{}\n</plist>\n",
+                body
+            )
+        };
+        let files: Files = vec![
+            (
+                "metainfo.plist",
+                plist(
+                    "<dict><key>creator</key><string>test</string>\
+                     <key>formatVersion</key><integer>2</integer></dict>",
+                ),
+            ),
+            (
+                "fontinfo.plist",
+                plist(
+                    "<dict><key>openTypeHheaAscender</key><real>750.4</real>\
+                     <key>openTypeOS2WinDescent</key><integer>-250</integer>\
+                     <key>unitsPerEm</key><integer>1000</integer></dict>",
+                ),
+            ),
+            (
+                "groups.plist",
+                plist(
+                    "<dict><key>@MMK_L_A</key><array><string>A</string></array>\
+                     <key>B</key><array><string>A</string></array></dict>",
+                ),
+            ),
+            (
+                "kerning.plist",
+                plist("<dict><key>@MMK_L_A</key><dict><key>B</key><integer>-10</integer></dict></dict>"),
+            ),
+            (
+                "glyphs/contents.plist",
+                plist("<dict><key>A</key><string>A_.glif</string></dict>"),
+            ),
+            (
+                "glyphs/A_.glif",
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <glyph name=\"A\" format=\"1\">\n<advance width=\"500\"/>\n\
+                 <unicode hex=\"0041\"/>\n<outline><contour>\
+                 <point x=\"250\" y=\"700\" type=\"move\" name=\"top\"/>\
+                 </contour></outline>\n</glyph>\n"
+                    .to_string(),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, contents)| (name.to_string(), contents.into_bytes()))
+        .collect();
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in &files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        let font = load_from_files(&files).unwrap();
+
+        assert_same_font(&font, &load(dir.path()).unwrap());
+        assert!(font.groups.as_ref().unwrap().contains_key("public.kern1.A"));
+        assert_eq!(
+            font.default_layer().get_glyph("A").unwrap().anchors.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn git_loads_like_dir() {
+        let ufo = Path::new("tests/data/MutatorSansBoldCondensed.ufo");
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        for (name, contents) in read_files(ufo) {
+            let path = repo.join("font.ufo").join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "font"]);
+        fs::remove_dir_all(repo.join("font.ufo")).unwrap();
+
+        let font = load_from_git(repo, "HEAD", "font.ufo").unwrap();
+
+        assert_same_font(&font, &load(ufo).unwrap());
+    }
+
+    #[test]
+    fn files_without_metainfo_are_rejected() {
+        let mut files = Files::new();
+        files.insert("../metainfo.plist".to_string(), Vec::new());
+        assert!(load_from_files(&files).is_err());
+    }
+}
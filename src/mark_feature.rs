@@ -0,0 +1,281 @@
+//! Generating `mark` and `mkmk` feature code from glyph anchors.
+//!
+//! The anchors follow the usual conventions: a mark glyph has an anchor
+//! `_top` where it attaches to the anchor `top` of a base glyph or of another
+//! mark, and a ligature has anchors `top_1`, `top_2`, ... for each of its
+//! components.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use norad::{Font, Glyph, Layer};
+
+use crate::public_lib::{Category, FontLib};
+
+/// An anchor position.
+pub type Position = (f64, f64);
+
+/// The mark attachments of a layer, by anchor name without the underscore.
+///
+/// Only the anchors of names with a mark class are included, as nothing can
+/// attach to the others.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkFeatures {
+    /// The mark glyphs of every mark class, with their `_name` anchor.
+    pub mark_classes: BTreeMap<String, BTreeMap<String, Position>>,
+    /// The base glyphs marks attach to, with their `name` anchor.
+    pub bases: BTreeMap<String, BTreeMap<String, Position>>,
+    /// The ligature glyphs marks attach to, with the `name_N` anchor of each
+    /// component, if it has one.
+    pub ligatures: BTreeMap<String, BTreeMap<String, Vec<Option<Position>>>>,
+    /// The mark glyphs other marks attach to, with their `name` anchor.
+    pub marks: BTreeMap<String, BTreeMap<String, Position>>,
+}
+
+/// The kind of an anchor, from its name.
+enum AnchorName<'a> {
+    /// `_name`, where a mark attaches.
+    Mark(&'a str),
+    /// `name_N`, where marks attach to the Nth component of a ligature.
+    Ligature(&'a str, usize),
+    /// `name`, where marks attach to a base glyph or mark.
+    Base(&'a str),
+}
+
+fn parse_anchor_name(name: &str) -> Option<AnchorName<'_>> {
+    if let Some(name) = name.strip_prefix('_') {
+        return (!name.is_empty()).then_some(AnchorName::Mark(name));
+    }
+    if let Some((name, index)) = name.rsplit_once('_') {
+        if !name.is_empty() && !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) {
+            return match index.parse() {
+                Ok(index) if index > 0 => Some(AnchorName::Ligature(name, index)),
+                _ => None,
+            };
+        }
+    }
+    (!name.is_empty()).then_some(AnchorName::Base(name))
+}
+
+/// Returns the anchors of `glyph` with their parsed names. Of several
+/// anchors with the same name, the first one is used.
+fn anchors(glyph: &Glyph) -> Vec<(AnchorName<'_>, Position)> {
+    let mut seen = Vec::new();
+    let mut found = Vec::new();
+    for anchor in &glyph.anchors {
+        let name = match anchor.name.as_deref() {
+            Some(name) if !seen.contains(&name) => name,
+            _ => continue,
+        };
+        seen.push(name);
+        if let Some(parsed) = parse_anchor_name(name) {
+            found.push((parsed, (anchor.x as f64, anchor.y as f64)));
+        }
+    }
+    found
+}
+
+/// Returns the category of `glyph`: the one in `categories` if it is listed
+/// there, or else mark if it has a mark anchor, ligature if it has ligature
+/// anchors and base otherwise.
+fn category(glyph: &Glyph, categories: Option<&BTreeMap<String, Category>>) -> Category {
+    if let Some(category) = categories.and_then(|c| c.get(&*glyph.name)) {
+        return *category;
+    }
+    let anchors = anchors(glyph);
+    if anchors
+        .iter()
+        .any(|(a, _)| matches!(a, AnchorName::Mark(_)))
+    {
+        Category::Mark
+    } else if anchors
+        .iter()
+        .any(|(a, _)| matches!(a, AnchorName::Ligature(..)))
+    {
+        Category::Ligature
+    } else {
+        Category::Base
+    }
+}
+
+impl MarkFeatures {
+    /// Collects the mark attachments of the glyphs in `layer`. Glyphs are
+    /// sorted into marks, bases and ligatures by `categories`, usually the
+    /// font's `public.openTypeCategories`, or by their anchors if they are
+    /// not listed. Glyphs of other categories are left out.
+    pub fn new(layer: &Layer, categories: Option<&BTreeMap<String, Category>>) -> Self {
+        let mut features = MarkFeatures::default();
+        for glyph in layer.iter() {
+            let name = glyph.name.to_string();
+            let category = category(glyph, categories);
+            let mut components: BTreeMap<&str, Vec<Option<Position>>> = BTreeMap::new();
+            for (anchor, position) in anchors(glyph) {
+                let (map, anchor) = match (category, anchor) {
+                    (Category::Mark, AnchorName::Mark(a)) => (&mut features.mark_classes, a),
+                    (Category::Mark, AnchorName::Base(a)) => (&mut features.marks, a),
+                    (Category::Base, AnchorName::Base(a)) => (&mut features.bases, a),
+                    (Category::Ligature, AnchorName::Ligature(a, index)) => {
+                        let slots = components.entry(a).or_default();
+                        if slots.len() < index {
+                            slots.resize(index, None);
+                        }
+                        slots[index - 1] = Some(position);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                map.entry(anchor.to_string())
+                    .or_default()
+                    .insert(name.clone(), position);
+            }
+            let count = glyph_components(glyph);
+            for (anchor, mut slots) in components {
+                if slots.len() < count {
+                    slots.resize(count, None);
+                }
+                features
+                    .ligatures
+                    .entry(anchor.to_string())
+                    .or_default()
+                    .insert(name.clone(), slots);
+            }
+        }
+
+        let classes = &features.mark_classes;
+        for map in [&mut features.bases, &mut features.marks] {
+            map.retain(|anchor, _| classes.contains_key(anchor));
+        }
+        features
+            .ligatures
+            .retain(|anchor, _| classes.contains_key(anchor));
+        features
+    }
+
+    /// Returns the feature file code: the mark classes, a `mark` feature with
+    /// mark-to-base and mark-to-ligature lookups, and a `mkmk` feature with
+    /// mark-to-mark lookups, one lookup per anchor name. Anchor positions are
+    /// rounded to integers.
+    pub fn to_fea(&self) -> String {
+        let mut fea = String::new();
+        for (anchor, marks) in &self.mark_classes {
+            for (glyph, position) in marks {
+                writeln!(
+                    fea,
+                    "markClass {} {} @MC_{};",
+                    glyph,
+                    fea_anchor(Some(*position)),
+                    anchor
+                )
+                .unwrap();
+            }
+        }
+
+        let mut mark = String::new();
+        for (anchor, bases) in &self.bases {
+            let lookup = format!("mark2base_{}", anchor);
+            writeln!(mark, "    lookup {} {{", lookup).unwrap();
+            for (glyph, position) in bases {
+                writeln!(
+                    mark,
+                    "        pos base {} {} mark @MC_{};",
+                    glyph,
+                    fea_anchor(Some(*position)),
+                    anchor
+                )
+                .unwrap();
+            }
+            writeln!(mark, "    }} {};", lookup).unwrap();
+        }
+        for (anchor, ligatures) in &self.ligatures {
+            let lookup = format!("mark2liga_{}", anchor);
+            writeln!(mark, "    lookup {} {{", lookup).unwrap();
+            for (glyph, components) in ligatures {
+                let components: Vec<String> = components
+                    .iter()
+                    .map(|position| match position {
+                        Some(_) => format!("{} mark @MC_{}", fea_anchor(*position), anchor),
+                        None => fea_anchor(None),
+                    })
+                    .collect();
+                writeln!(
+                    mark,
+                    "        pos ligature {} {};",
+                    glyph,
+                    components.join("\n            ligComponent ")
+                )
+                .unwrap();
+            }
+            writeln!(mark, "    }} {};", lookup).unwrap();
+        }
+
+        let mut mkmk = String::new();
+        for (anchor, marks) in &self.marks {
+            let lookup = format!("mark2mark_{}", anchor);
+            let mut filter: Vec<&str> = self.mark_classes[anchor]
+                .keys()
+                .chain(marks.keys())
+                .map(|g| g.as_str())
+                .collect();
+            filter.sort_unstable();
+            filter.dedup();
+            writeln!(mkmk, "    lookup {} {{", lookup).unwrap();
+            writeln!(mkmk, "        @MFS_{} = [{}];", lookup, filter.join(" ")).unwrap();
+            writeln!(
+                mkmk,
+                "        lookupflag UseMarkFilteringSet @MFS_{};",
+                lookup
+            )
+            .unwrap();
+            for (glyph, position) in marks {
+                writeln!(
+                    mkmk,
+                    "        pos mark {} {} mark @MC_{};",
+                    glyph,
+                    fea_anchor(Some(*position)),
+                    anchor
+                )
+                .unwrap();
+            }
+            writeln!(mkmk, "    }} {};", lookup).unwrap();
+        }
+
+        for (tag, lookups) in [("mark", mark), ("mkmk", mkmk)] {
+            if !lookups.is_empty() {
+                write!(fea, "\nfeature {} {{\n{}}} {};\n", tag, lookups, tag).unwrap();
+            }
+        }
+        fea
+    }
+}
+
+/// Returns the number of ligature components of `glyph` implied by all its
+/// ligature anchors.
+fn glyph_components(glyph: &Glyph) -> usize {
+    anchors(glyph)
+        .into_iter()
+        .filter_map(|(anchor, _)| match anchor {
+            AnchorName::Ligature(_, index) => Some(index),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Rounds like fontTools' `otRound`, half up.
+pub(crate) fn ot_round(value: f64) -> i64 {
+    (value + 0.5).floor() as i64
+}
+
+fn fea_anchor(position: Option<Position>) -> String {
+    match position {
+        Some((x, y)) => format!("<anchor {} {}>", ot_round(x), ot_round(y)),
+        None => "<anchor NULL>".to_string(),
+    }
+}
+
+/// Collects the mark attachments of the default layer of `font`, using the
+/// font's `public.openTypeCategories` if it has any.
+pub fn mark_features(font: &Font) -> MarkFeatures {
+    let categories = FontLib::new(&font.lib).0.opentype_categories;
+    MarkFeatures::new(font.default_layer(), categories.as_ref())
+}
@@ -0,0 +1,662 @@
+//! Three-way merging of UFO sources.
+//!
+//! Changes made on only one side are taken over; a conflict is reported when
+//! both sides changed the same glyph, kerning pair, fontinfo attribute or lib
+//! key differently, and the merge then keeps "our" version. Glyph order and
+//! group members are merged as lists, so that glyphs added or removed on
+//! either side end up in the result.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::sync::Arc;
+
+use norad::{Font, Glyph, Layer, LayerSet, Plist};
+
+use crate::Error;
+
+const GLYPH_ORDER_KEY: &str = "public.glyphOrder";
+
+/// Something both sides changed differently.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    Glyph {
+        layer: String,
+        glyph: String,
+    },
+    /// A layer that was removed on one side and changed on the other.
+    Layer(String),
+    /// A key of the font lib, or of a layer's lib if `layer` is given.
+    Lib {
+        layer: Option<String>,
+        key: String,
+    },
+    Info(String),
+    Kerning {
+        first: String,
+        second: String,
+    },
+    /// A group that was removed on one side and changed on the other.
+    Group(String),
+    Features,
+    /// A file the merge driver merges as a whole, such as a `.glif` file.
+    File(String),
+}
+
+impl Conflict {
+    /// A short, stable name for the kind of conflict.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Conflict::Glyph { .. } => "glyph",
+            Conflict::Layer(_) => "layer",
+            Conflict::Lib { .. } => "lib",
+            Conflict::Info(_) => "info",
+            Conflict::Kerning { .. } => "kerning",
+            Conflict::Group(_) => "group",
+            Conflict::Features => "features",
+            Conflict::File(_) => "file",
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Glyph { layer, glyph } => {
+                write!(f, "glyph '{}' in layer '{}'", glyph, layer)
+            }
+            Conflict::Layer(name) => write!(f, "layer '{}'", name),
+            Conflict::Lib {
+                layer: Some(layer),
+                key,
+            } => write!(f, "lib key '{}' of layer '{}'", key, layer),
+            Conflict::Lib { layer: None, key } => write!(f, "lib key '{}'", key),
+            Conflict::Info(key) => write!(f, "fontinfo attribute '{}'", key),
+            Conflict::Kerning { first, second } => {
+                write!(f, "kerning pair ({}, {})", first, second)
+            }
+            Conflict::Group(name) => write!(f, "group '{}'", name),
+            Conflict::Features => write!(f, "features"),
+            Conflict::File(path) => write!(f, "file '{}'", path),
+        }
+    }
+}
+
+/// The result of a three-way merge.
+#[derive(Debug, Clone)]
+pub struct Merge {
+    pub font: Font,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merges one value. Returns `None` if both sides changed it differently.
+fn merge_value<'a, V: PartialEq>(
+    base: Option<&'a V>,
+    ours: Option<&'a V>,
+    theirs: Option<&'a V>,
+) -> Option<Option<&'a V>> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Merges key-value entries. The result has our order, followed by the keys
+/// only they added. The keys of conflicting entries are pushed to
+/// `conflicts`, and keep our value.
+fn merge_entries<K: Ord + Clone, V: PartialEq + Clone>(
+    base: &[(K, V)],
+    ours: &[(K, V)],
+    theirs: &[(K, V)],
+    conflicts: &mut Vec<K>,
+) -> Vec<(K, V)> {
+    let base: BTreeMap<&K, &V> = base.iter().map(|(k, v)| (k, v)).collect();
+    let our_map: BTreeMap<&K, &V> = ours.iter().map(|(k, v)| (k, v)).collect();
+    let their_map: BTreeMap<&K, &V> = theirs.iter().map(|(k, v)| (k, v)).collect();
+    let keys = ours.iter().map(|(k, _)| k).chain(
+        theirs
+            .iter()
+            .map(|(k, _)| k)
+            .filter(|k| !our_map.contains_key(k)),
+    );
+
+    let mut merged = Vec::new();
+    for key in keys {
+        let ours = our_map.get(key).copied();
+        let value = match merge_value(base.get(key).copied(), ours, their_map.get(key).copied()) {
+            Some(value) => value,
+            None => {
+                conflicts.push(key.clone());
+                ours
+            }
+        };
+        if let Some(value) = value {
+            merged.push((key.clone(), value.clone()));
+        }
+    }
+    merged
+}
+
+/// Merges two edited versions of a list whose items are unique, such as a
+/// glyph order or a group. Items they removed are removed from ours, and
+/// items they added are inserted after the item that precedes them in
+/// their list.
+fn merge_lists<T: PartialEq + Clone>(base: &[T], ours: &[T], theirs: &[T]) -> Vec<T> {
+    let mut merged: Vec<T> = ours
+        .iter()
+        .filter(|item| !base.contains(item) || theirs.contains(item))
+        .cloned()
+        .collect();
+    for (i, item) in theirs.iter().enumerate() {
+        if base.contains(item) || ours.contains(item) {
+            continue;
+        }
+        let position = theirs[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| merged.iter().position(|m| m == prev))
+            .map_or(0, |p| p + 1);
+        merged.insert(position, item.clone());
+    }
+    merged
+}
+
+fn dict_entries(dict: &Plist) -> Vec<(String, plist::Value)> {
+    dict.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+fn string_list(value: Option<&plist::Value>) -> Vec<plist::Value> {
+    value
+        .and_then(plist::Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Merges a lib key by key. If both sides changed `public.glyphOrder`, it
+/// is merged as a list. Returns the merged lib and the conflicting keys.
+fn merge_lib(base: &Plist, ours: &Plist, theirs: &Plist) -> (Plist, Vec<String>) {
+    let mut conflicts = Vec::new();
+    let mut merged: Plist = merge_entries(
+        &dict_entries(base),
+        &dict_entries(ours),
+        &dict_entries(theirs),
+        &mut conflicts,
+    )
+    .into_iter()
+    .collect();
+    let order_changed_by_both = conflicts.iter().any(|key| key == GLYPH_ORDER_KEY);
+    if order_changed_by_both && ours.contains_key(GLYPH_ORDER_KEY) {
+        let order = merge_lists(
+            &string_list(base.get(GLYPH_ORDER_KEY)),
+            &string_list(ours.get(GLYPH_ORDER_KEY)),
+            &string_list(theirs.get(GLYPH_ORDER_KEY)),
+        );
+        merged.insert(GLYPH_ORDER_KEY.to_string(), plist::Value::Array(order));
+        conflicts.retain(|key| key != GLYPH_ORDER_KEY);
+    }
+    (merged, conflicts)
+}
+
+/// Kerning as a list of pairs. The values are plist values when merging
+/// `kerning.plist`, to keep how they are written.
+type KerningPairs<V> = Vec<((String, String), V)>;
+
+fn kerning_pairs<'a, V: Clone + 'a>(
+    kerning: impl IntoIterator<Item = (&'a String, &'a BTreeMap<String, V>)>,
+) -> KerningPairs<V> {
+    let mut pairs = Vec::new();
+    for (first, seconds) in kerning {
+        for (second, value) in seconds {
+            pairs.push(((first.clone(), second.clone()), value.clone()));
+        }
+    }
+    pairs
+}
+
+fn merge_kerning<V: PartialEq + Clone>(
+    base: &KerningPairs<V>,
+    ours: &KerningPairs<V>,
+    theirs: &KerningPairs<V>,
+    conflicts: &mut Vec<Conflict>,
+) -> BTreeMap<String, BTreeMap<String, V>> {
+    let mut pair_conflicts = Vec::new();
+    let mut kerning: BTreeMap<String, BTreeMap<String, V>> = BTreeMap::new();
+    for ((first, second), value) in merge_entries(base, ours, theirs, &mut pair_conflicts) {
+        kerning.entry(first).or_default().insert(second, value);
+    }
+    conflicts.extend(
+        pair_conflicts
+            .into_iter()
+            .map(|(first, second)| Conflict::Kerning { first, second }),
+    );
+    kerning
+}
+
+/// Returns the kerning groups that share members with another group of the
+/// same side.
+pub(crate) fn overlapping_groups<T: Ord>(groups: &BTreeMap<String, Vec<T>>) -> BTreeSet<String> {
+    let mut members: BTreeMap<(bool, &T), Vec<&String>> = BTreeMap::new();
+    for (name, glyphs) in groups {
+        let first = name.starts_with(crate::kerning::KERN1_PREFIX);
+        if !first && !name.starts_with(crate::kerning::KERN2_PREFIX) {
+            continue;
+        }
+        for glyph in glyphs {
+            members.entry((first, glyph)).or_default().push(name);
+        }
+    }
+    members
+        .into_values()
+        .filter(|names| names.len() > 1)
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// Merges groups member by member. A group that was removed on one side and
+/// changed on the other is a conflict, as are kerning groups that would end
+/// up sharing glyphs; conflicting groups keep our members.
+fn merge_groups<T: Ord + Clone>(
+    base: &BTreeMap<String, Vec<T>>,
+    ours: &BTreeMap<String, Vec<T>>,
+    theirs: &BTreeMap<String, Vec<T>>,
+    conflicts: &mut Vec<Conflict>,
+) -> BTreeMap<String, Vec<T>> {
+    let names: BTreeSet<&String> = ours.keys().chain(theirs.keys()).collect();
+    let mut merged = BTreeMap::new();
+    let mut conflicting = BTreeSet::new();
+    for name in names {
+        let (b, o, t) = (base.get(name), ours.get(name), theirs.get(name));
+        let members = match (merge_value(b, o, t), o, t) {
+            (Some(members), _, _) => members.cloned(),
+            (None, Some(o), Some(t)) => Some(merge_lists(b.map_or(&[], |b| b), o, t)),
+            (None, o, _) => {
+                conflicting.insert(name.clone());
+                o.cloned()
+            }
+        };
+        if let Some(members) = members {
+            merged.insert(name.clone(), members);
+        }
+    }
+    // Reverting groups to our members can only remove overlaps, as our
+    // groups don't overlap.
+    loop {
+        let overlapping: Vec<String> = overlapping_groups(&merged)
+            .into_iter()
+            .filter(|name| merged.get(name) != ours.get(name))
+            .collect();
+        if overlapping.is_empty() {
+            break;
+        }
+        for name in overlapping {
+            match ours.get(&name) {
+                Some(members) => merged.insert(name.clone(), members.clone()),
+                None => merged.remove(&name),
+            };
+            conflicting.insert(name);
+        }
+    }
+    conflicts.extend(conflicting.into_iter().map(Conflict::Group));
+    merged
+}
+
+fn glyph_entries(layer: Option<&Layer>) -> Vec<(String, Arc<Glyph>)> {
+    let mut glyphs: Vec<_> = layer
+        .into_iter()
+        .flat_map(|l| l.iter().map(|g| (g.name.to_string(), Arc::clone(g))))
+        .collect();
+    glyphs.sort_by(|a, b| a.0.cmp(&b.0));
+    glyphs
+}
+
+fn layers_equal(a: &Layer, b: &Layer) -> bool {
+    a.color == b.color && a.lib == b.lib && glyph_entries(Some(a)) == glyph_entries(Some(b))
+}
+
+/// Merges a layer that exists on both sides, starting from ours so that its
+/// glyph file names are kept.
+fn merge_layer(
+    base: Option<&Layer>,
+    ours: &Layer,
+    theirs: &Layer,
+    conflicts: &mut Vec<Conflict>,
+) -> Layer {
+    let name = ours.name().to_string();
+    let mut layer = ours.clone();
+    let mut glyph_conflicts = Vec::new();
+    let merged = merge_entries(
+        &glyph_entries(base),
+        &glyph_entries(Some(ours)),
+        &glyph_entries(Some(theirs)),
+        &mut glyph_conflicts,
+    );
+    let merged_names: BTreeSet<&str> = merged.iter().map(|(n, _)| n.as_str()).collect();
+    let removed: Vec<String> = ours
+        .iter()
+        .map(|g| g.name.to_string())
+        .filter(|n| !merged_names.contains(n.as_str()))
+        .collect();
+    for glyph in removed {
+        layer.remove_glyph(&glyph);
+    }
+    for (glyph_name, glyph) in merged {
+        if ours.get_glyph(glyph_name.as_str()) != Some(&glyph) {
+            layer.insert_glyph(glyph);
+        }
+    }
+    conflicts.extend(glyph_conflicts.into_iter().map(|glyph| Conflict::Glyph {
+        layer: name.clone(),
+        glyph,
+    }));
+
+    let empty = Plist::new();
+    let (lib, lib_conflicts) = merge_lib(base.map_or(&empty, |l| &l.lib), &ours.lib, &theirs.lib);
+    layer.lib = lib;
+    conflicts.extend(lib_conflicts.into_iter().map(|key| Conflict::Lib {
+        layer: Some(name.clone()),
+        key,
+    }));
+    match merge_value(
+        base.map(|l| &l.color),
+        Some(&ours.color),
+        Some(&theirs.color),
+    ) {
+        Some(color) => layer.color = color.cloned().flatten(),
+        None => conflicts.push(Conflict::Layer(name)),
+    }
+    layer
+}
+
+fn merge_layers(
+    base: &LayerSet,
+    ours: &LayerSet,
+    theirs: &LayerSet,
+    conflicts: &mut Vec<Conflict>,
+) -> LayerSet {
+    let mut names: Vec<&str> = ours.names().map(|n| n.as_ref()).collect();
+    names.extend(
+        theirs
+            .names()
+            .map(|n| n.as_ref())
+            .filter(|n| ours.get(n).is_none()),
+    );
+    let mut layers = Vec::new();
+    for name in names {
+        let (b, o, t) = (base.get(name), ours.get(name), theirs.get(name));
+        match (o, t) {
+            (Some(o), Some(t)) => layers.push(merge_layer(b, o, t, conflicts)),
+            // Removed on one side: drop it if the other side didn't touch it.
+            (Some(layer), None) | (None, Some(layer)) => match b {
+                None => layers.push(layer.clone()),
+                Some(b) if layers_equal(b, layer) => (),
+                Some(_) => {
+                    conflicts.push(Conflict::Layer(name.to_string()));
+                    if let Some(o) = o {
+                        layers.push(o.clone());
+                    }
+                }
+            },
+            (None, None) => (),
+        }
+    }
+    LayerSet::new(layers)
+}
+
+fn info_dict(info: Option<&norad::FontInfo>) -> Result<Plist, Error> {
+    match info {
+        Some(info) => Ok(plist::to_value(info)?.into_dictionary().unwrap_or_default()),
+        None => Ok(Plist::new()),
+    }
+}
+
+/// Merges `ours` and `theirs`, two edited versions of `base`.
+pub fn merge_fonts(base: &Font, ours: &Font, theirs: &Font) -> Result<Merge, Error> {
+    let mut conflicts = Vec::new();
+    let mut font = ours.clone();
+    font.layers = merge_layers(&base.layers, &ours.layers, &theirs.layers, &mut conflicts);
+
+    let (lib, lib_conflicts) = merge_lib(&base.lib, &ours.lib, &theirs.lib);
+    font.lib = lib;
+    conflicts.extend(
+        lib_conflicts
+            .into_iter()
+            .map(|key| Conflict::Lib { layer: None, key }),
+    );
+
+    let mut info_conflicts = Vec::new();
+    let info: Plist = merge_entries(
+        &dict_entries(&info_dict(base.font_info.as_ref())?),
+        &dict_entries(&info_dict(ours.font_info.as_ref())?),
+        &dict_entries(&info_dict(theirs.font_info.as_ref())?),
+        &mut info_conflicts,
+    )
+    .into_iter()
+    .collect();
+    if ours.font_info.is_some() || theirs.font_info.is_some() {
+        let info: norad::FontInfo = plist::from_value(&plist::Value::Dictionary(info))?;
+        info.validate()?;
+        font.font_info = Some(info);
+    }
+    conflicts.extend(info_conflicts.into_iter().map(Conflict::Info));
+
+    let kerning = |font: &Font| kerning_pairs(font.kerning.iter().flatten());
+    let kerning = merge_kerning(
+        &kerning(base),
+        &kerning(ours),
+        &kerning(theirs),
+        &mut conflicts,
+    );
+    if ours.kerning.is_some() || !kerning.is_empty() {
+        font.kerning = Some(kerning);
+    }
+
+    let empty = norad::Groups::new();
+    let groups = |font: &'_ Font| font.groups.as_ref().unwrap_or(&empty).clone();
+    let groups = merge_groups(
+        &groups(base),
+        &groups(ours),
+        &groups(theirs),
+        &mut conflicts,
+    );
+    if ours.groups.is_some() || !groups.is_empty() {
+        font.groups = Some(groups);
+    }
+
+    match merge_value(
+        base.features.as_ref(),
+        ours.features.as_ref(),
+        theirs.features.as_ref(),
+    ) {
+        Some(features) => font.features = features.cloned(),
+        None => conflicts.push(Conflict::Features),
+    }
+
+    Ok(Merge { font, conflicts })
+}
+
+/// Merges the UFOs `ours` and `theirs`, two edited versions of `base`, and
+/// saves the result to `out` with [`crate::save`]. Returns the conflicts.
+///
+/// The `data` and `images` directories are not merged; when `out` is the
+/// UFO at `ours`, ours are kept.
+pub fn merge_ufos(
+    base: impl AsRef<Path>,
+    ours: impl AsRef<Path>,
+    theirs: impl AsRef<Path>,
+    out: impl AsRef<Path>,
+) -> Result<Vec<Conflict>, Error> {
+    let (base, ours, theirs) = (base.as_ref(), ours.as_ref(), theirs.as_ref());
+    let (base, (ours, theirs)) = rayon::join(
+        || crate::load(base),
+        || rayon::join(|| crate::load(ours), || crate::load(theirs)),
+    );
+    let merge = merge_fonts(&base?, &ours?, &theirs?)?;
+    crate::save(&merge.font, out)?;
+    Ok(merge.conflicts)
+}
+
+fn parse<T: serde::de::DeserializeOwned + Default>(data: &[u8]) -> Result<T, Error> {
+    if data.is_empty() {
+        return Ok(T::default());
+    }
+    Ok(plist::from_bytes(data)?)
+}
+
+/// Writes a merged property list, ending with a newline if `ours` does.
+fn write_plist(value: &impl serde::Serialize, ours: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    plist::to_writer_xml(&mut data, value)?;
+    if ours.ends_with(b"\n") {
+        data.push(b'\n');
+    }
+    Ok(data)
+}
+
+/// Merges one file of a UFO, as a git merge driver does. `path` is the path
+/// of the file, used to tell what it contains; an empty version stands for a
+/// file that doesn't exist on that side.
+///
+/// The property lists of a UFO are merged entry by entry, as
+/// [`merge_fonts`] does; other files, such as glyphs, are merged as a whole.
+/// Returns the merged contents, which are ours for conflicting parts, and the
+/// conflicts.
+pub fn merge_file(
+    path: &Path,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<(Vec<u8>, Vec<Conflict>), Error> {
+    let mut conflicts = Vec::new();
+    // The directory of a layer, used to name it in conflicts.
+    let dir = || {
+        path.parent()
+            .and_then(|p| p.file_name())
+            .map_or(String::new(), |n| n.to_string_lossy().into_owned())
+    };
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    let merged = match name.as_deref().unwrap_or("") {
+        "kerning.plist" => {
+            let kerning = |data| -> Result<KerningPairs<plist::Value>, Error> {
+                let kerning: BTreeMap<String, BTreeMap<String, plist::Value>> = parse(data)?;
+                Ok(kerning_pairs(&kerning))
+            };
+            let kerning = merge_kerning(
+                &kerning(base)?,
+                &kerning(ours)?,
+                &kerning(theirs)?,
+                &mut conflicts,
+            );
+            write_plist(&kerning, ours)?
+        }
+        "groups.plist" => {
+            let groups: [BTreeMap<String, Vec<String>>; 3] =
+                [parse(base)?, parse(ours)?, parse(theirs)?];
+            write_plist(
+                &merge_groups(&groups[0], &groups[1], &groups[2], &mut conflicts),
+                ours,
+            )?
+        }
+        "lib.plist" => {
+            let (lib, keys) = merge_lib(&parse(base)?, &parse(ours)?, &parse(theirs)?);
+            conflicts.extend(
+                keys.into_iter()
+                    .map(|key| Conflict::Lib { layer: None, key }),
+            );
+            write_plist(&lib, ours)?
+        }
+        "layerinfo.plist" => {
+            let infos: [Plist; 3] = [parse(base)?, parse(ours)?, parse(theirs)?];
+            let mut info = infos[1].clone();
+            match merge_value(
+                infos[0].get("color"),
+                infos[1].get("color"),
+                infos[2].get("color"),
+            ) {
+                Some(Some(color)) => info.insert("color".to_string(), color.clone()),
+                Some(None) => info.remove("color"),
+                None => {
+                    conflicts.push(Conflict::Layer(dir()));
+                    None
+                }
+            };
+            let lib = |info: &Plist| {
+                info.get("lib")
+                    .and_then(plist::Value::as_dictionary)
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let (lib, keys) = merge_lib(&lib(&infos[0]), &lib(&infos[1]), &lib(&infos[2]));
+            if lib.is_empty() {
+                info.remove("lib");
+            } else {
+                info.insert("lib".to_string(), plist::Value::Dictionary(lib));
+            }
+            conflicts.extend(keys.into_iter().map(|key| Conflict::Lib {
+                layer: Some(dir()),
+                key,
+            }));
+            write_plist(&info, ours)?
+        }
+        "layercontents.plist" => {
+            let layers: [Vec<(String, String)>; 3] = [parse(base)?, parse(ours)?, parse(theirs)?];
+            write_plist(&merge_lists(&layers[0], &layers[1], &layers[2]), ours)?
+        }
+        "fontinfo.plist" | "contents.plist" | "metainfo.plist" => {
+            let dicts: [Plist; 3] = [parse(base)?, parse(ours)?, parse(theirs)?];
+            let mut keys = Vec::new();
+            let merged: Plist = merge_entries(
+                &dict_entries(&dicts[0]),
+                &dict_entries(&dicts[1]),
+                &dict_entries(&dicts[2]),
+                &mut keys,
+            )
+            .into_iter()
+            .collect();
+            conflicts.extend(keys.into_iter().map(|key| match name.as_deref() {
+                Some("fontinfo.plist") => Conflict::Info(key),
+                Some("contents.plist") => Conflict::Glyph {
+                    layer: dir(),
+                    glyph: key,
+                },
+                _ => Conflict::File(path.display().to_string()),
+            }));
+            write_plist(&merged, ours)?
+        }
+        _ => match merge_value(Some(&base), Some(&ours), Some(&theirs)) {
+            Some(merged) => merged.map_or(Vec::new(), |m| m.to_vec()),
+            None => {
+                conflicts.push(match name.as_deref() {
+                    Some("features.fea") => Conflict::Features,
+                    _ => Conflict::File(path.display().to_string()),
+                });
+                ours.to_vec()
+            }
+        },
+    };
+    Ok((merged, conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_groups_are_per_side() {
+        let groups: BTreeMap<String, Vec<&str>> = vec![
+            ("public.kern1.A", vec!["A", "Agrave"]),
+            ("public.kern1.Agrave", vec!["Agrave"]),
+            ("public.kern1.O", vec!["O"]),
+            ("public.kern2.A", vec!["A"]),
+            ("accents", vec!["A", "O"]),
+        ]
+        .into_iter()
+        .map(|(name, glyphs)| (name.to_string(), glyphs))
+        .collect();
+        let overlapping: Vec<_> = overlapping_groups(&groups).into_iter().collect();
+        assert_eq!(overlapping, ["public.kern1.A", "public.kern1.Agrave"]);
+    }
+}
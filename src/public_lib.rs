@@ -0,0 +1,372 @@
+//! Typed access to the well-known `public.*` keys of font and glyph libs.
+//!
+//! The keys are defined by the [UFO specification][lib]. Values that don't
+//! have the specified form are left out of the typed view and reported as
+//! [`LibWarning`]s; the raw lib is not changed.
+//!
+//! [lib]: https://unifiedfontobject.org/versions/ufo3/lib.plist/
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use norad::{Color, Font, Glyph, Layer, Plist};
+use plist::Value;
+use rayon::prelude::*;
+
+use crate::Error;
+
+pub const GLYPH_ORDER_KEY: &str = "public.glyphOrder";
+pub const POSTSCRIPT_NAMES_KEY: &str = "public.postscriptNames";
+pub const SKIP_EXPORT_GLYPHS_KEY: &str = "public.skipExportGlyphs";
+pub const OPENTYPE_CATEGORIES_KEY: &str = "public.openTypeCategories";
+pub const UNICODE_VARIATION_SEQUENCES_KEY: &str = "public.unicodeVariationSequences";
+pub const OBJECT_LIBS_KEY: &str = "public.objectLibs";
+pub const MARK_COLOR_KEY: &str = "public.markColor";
+pub const VERTICAL_ORIGIN_KEY: &str = "public.verticalOrigin";
+
+/// The OpenType category of a glyph, from `public.openTypeCategories`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Unassigned,
+    Base,
+    Ligature,
+    Mark,
+    Component,
+}
+
+impl Category {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Unassigned => "unassigned",
+            Category::Base => "base",
+            Category::Ligature => "ligature",
+            Category::Mark => "mark",
+            Category::Component => "component",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "unassigned" => Category::Unassigned,
+            "base" => Category::Base,
+            "ligature" => Category::Ligature,
+            "mark" => Category::Mark,
+            "component" => Category::Component,
+            _ => return None,
+        })
+    }
+}
+
+/// A `public.*` lib value that doesn't have the specified form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibWarning {
+    /// The layer and glyph whose lib it is, or `None` for the font lib.
+    pub glyph: Option<(String, String)>,
+    pub key: String,
+    pub message: String,
+}
+
+impl Display for LibWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.glyph {
+            Some((layer, glyph)) => write!(
+                f,
+                "lib key '{}' of glyph '{}' in layer '{}': {}",
+                self.key, glyph, layer, self.message
+            ),
+            None => write!(f, "font lib key '{}': {}", self.key, self.message),
+        }
+    }
+}
+
+/// Collects the warnings for one lib.
+struct Checker<'a> {
+    glyph: Option<(&'a str, &'a str)>,
+    warnings: Vec<LibWarning>,
+}
+
+impl<'a> Checker<'a> {
+    fn warn(&mut self, key: &str, message: String) {
+        self.warnings.push(LibWarning {
+            glyph: self
+                .glyph
+                .map(|(layer, glyph)| (layer.to_string(), glyph.to_string())),
+            key: key.to_string(),
+            message,
+        });
+    }
+
+    fn string_list(&mut self, lib: &Plist, key: &str) -> Option<Vec<String>> {
+        let value = lib.get(key)?;
+        let items = match value.as_array() {
+            Some(items) => items,
+            None => {
+                self.warn(key, "not an array".to_string());
+                return None;
+            }
+        };
+        let mut strings = Vec::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            match item.as_string() {
+                Some(s) => strings.push(s.to_string()),
+                None => self.warn(key, format!("item {} is not a string", i)),
+            }
+        }
+        Some(strings)
+    }
+
+    fn dict<'l>(&mut self, lib: &'l Plist, key: &str) -> Option<&'l Plist> {
+        let value = lib.get(key)?;
+        if value.as_dictionary().is_none() {
+            self.warn(key, "not a dictionary".to_string());
+        }
+        value.as_dictionary()
+    }
+
+    fn string_dict(&mut self, lib: &Plist, key: &str) -> Option<BTreeMap<String, String>> {
+        let dict = self.dict(lib, key)?;
+        let mut strings = BTreeMap::new();
+        for (name, value) in dict {
+            match value.as_string() {
+                Some(s) => {
+                    strings.insert(name.clone(), s.to_string());
+                }
+                None => self.warn(key, format!("value of '{}' is not a string", name)),
+            }
+        }
+        Some(strings)
+    }
+
+    fn categories(&mut self, lib: &Plist) -> Option<BTreeMap<String, Category>> {
+        let key = OPENTYPE_CATEGORIES_KEY;
+        let dict = self.dict(lib, key)?;
+        let mut categories = BTreeMap::new();
+        for (name, value) in dict {
+            match value.as_string().and_then(Category::from_str) {
+                Some(category) => {
+                    categories.insert(name.clone(), category);
+                }
+                None => self.warn(
+                    key,
+                    format!(
+                        "category of '{}' is not unassigned, base, ligature, mark or component",
+                        name
+                    ),
+                ),
+            }
+        }
+        Some(categories)
+    }
+
+    fn codepoint(&mut self, key: &str, hex: &str) -> Option<u32> {
+        let codepoint = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|c| char::from_u32(*c).is_some());
+        if codepoint.is_none() {
+            self.warn(key, format!("'{}' is not a hexadecimal codepoint", hex));
+        }
+        codepoint
+    }
+
+    fn variation_sequences(&mut self, lib: &Plist) -> Option<BTreeMap<u32, BTreeMap<u32, String>>> {
+        let key = UNICODE_VARIATION_SEQUENCES_KEY;
+        let dict = self.dict(lib, key)?;
+        let mut sequences = BTreeMap::new();
+        for (selector_hex, bases) in dict {
+            let selector = match self.codepoint(key, selector_hex) {
+                Some(selector) => selector,
+                None => continue,
+            };
+            if !is_variation_selector(selector) {
+                self.warn(
+                    key,
+                    format!("U+{:04X} is not a variation selector", selector),
+                );
+                continue;
+            }
+            let bases = match bases.as_dictionary() {
+                Some(bases) => bases,
+                None => {
+                    self.warn(
+                        key,
+                        format!("value of '{}' is not a dictionary", selector_hex),
+                    );
+                    continue;
+                }
+            };
+            let mut glyphs = BTreeMap::new();
+            for (base_hex, glyph) in bases {
+                let base = self.codepoint(key, base_hex);
+                match (base, glyph.as_string()) {
+                    (Some(base), Some(glyph)) => {
+                        glyphs.insert(base, glyph.to_string());
+                    }
+                    (Some(_), None) => self.warn(
+                        key,
+                        format!("glyph of '{} {}' is not a string", base_hex, selector_hex),
+                    ),
+                    (None, _) => (),
+                }
+            }
+            sequences.insert(selector, glyphs);
+        }
+        Some(sequences)
+    }
+
+    fn object_libs(&mut self, lib: &Plist) -> Option<BTreeMap<String, Plist>> {
+        let key = OBJECT_LIBS_KEY;
+        let dict = self.dict(lib, key)?;
+        let mut libs = BTreeMap::new();
+        for (identifier, value) in dict {
+            match value.as_dictionary() {
+                Some(lib) => {
+                    libs.insert(identifier.clone(), lib.clone());
+                }
+                None => self.warn(
+                    key,
+                    format!("lib of object '{}' is not a dictionary", identifier),
+                ),
+            }
+        }
+        Some(libs)
+    }
+
+    fn color(&mut self, lib: &Plist, key: &str) -> Option<Color> {
+        let value = lib.get(key)?;
+        let color = value.as_string().and_then(|s| s.parse().ok());
+        if color.is_none() {
+            self.warn(
+                key,
+                "not a color string of four numbers between 0 and 1".to_string(),
+            );
+        }
+        color
+    }
+
+    fn number(&mut self, lib: &Plist, key: &str) -> Option<f64> {
+        let number = match lib.get(key)? {
+            Value::Integer(n) => n.as_signed().map(|n| n as f64),
+            Value::Real(n) => Some(*n),
+            _ => None,
+        };
+        if number.is_none() {
+            self.warn(key, "not a number".to_string());
+        }
+        number
+    }
+}
+
+fn is_variation_selector(c: u32) -> bool {
+    matches!(c, 0x180B..=0x180D | 0x180F | 0xFE00..=0xFE0F | 0xE0100..=0xE01EF)
+}
+
+/// The well-known keys of a font lib.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontLib {
+    pub glyph_order: Option<Vec<String>>,
+    pub postscript_names: Option<BTreeMap<String, String>>,
+    pub skip_export_glyphs: Option<Vec<String>>,
+    pub opentype_categories: Option<BTreeMap<String, Category>>,
+    /// Glyphs by variation selector and base codepoint.
+    pub unicode_variation_sequences: Option<BTreeMap<u32, BTreeMap<u32, String>>>,
+    /// The libs of fontinfo guidelines by identifier, if they haven't been
+    /// moved onto the guidelines while loading.
+    pub object_libs: Option<BTreeMap<String, Plist>>,
+}
+
+impl FontLib {
+    /// Reads the well-known keys of a font lib. Malformed values, or the
+    /// malformed entries of arrays and dictionaries, are left out and
+    /// reported.
+    pub fn new(lib: &Plist) -> (Self, Vec<LibWarning>) {
+        let mut checker = Checker {
+            glyph: None,
+            warnings: Vec::new(),
+        };
+        let font_lib = FontLib {
+            glyph_order: checker.string_list(lib, GLYPH_ORDER_KEY),
+            postscript_names: checker.string_dict(lib, POSTSCRIPT_NAMES_KEY),
+            skip_export_glyphs: checker.string_list(lib, SKIP_EXPORT_GLYPHS_KEY),
+            opentype_categories: checker.categories(lib),
+            unicode_variation_sequences: checker.variation_sequences(lib),
+            object_libs: checker.object_libs(lib),
+        };
+        (font_lib, checker.warnings)
+    }
+}
+
+/// The well-known keys of a glyph lib.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlyphLib {
+    pub mark_color: Option<Color>,
+    pub vertical_origin: Option<f64>,
+    /// The libs of the glyph's contours, points, anchors and guidelines by
+    /// identifier, if they haven't been moved onto the objects while loading.
+    pub object_libs: Option<BTreeMap<String, Plist>>,
+}
+
+impl GlyphLib {
+    /// Reads the well-known keys of the lib of the glyph `glyph` in `layer`,
+    /// like [`FontLib::new`].
+    pub fn new(lib: &Plist, layer: &str, glyph: &str) -> (Self, Vec<LibWarning>) {
+        let mut checker = Checker {
+            glyph: Some((layer, glyph)),
+            warnings: Vec::new(),
+        };
+        let glyph_lib = GlyphLib {
+            mark_color: checker.color(lib, MARK_COLOR_KEY),
+            vertical_origin: checker.number(lib, VERTICAL_ORIGIN_KEY),
+            object_libs: checker.object_libs(lib),
+        };
+        (glyph_lib, checker.warnings)
+    }
+}
+
+/// Returns the problems with the well-known keys of the font lib and of all
+/// glyph libs of `font`, checking the glyphs in parallel.
+pub fn check_font(font: &Font) -> Vec<LibWarning> {
+    let mut warnings = FontLib::new(&font.lib).1;
+    let glyphs: Vec<_> = font
+        .layers
+        .iter()
+        .flat_map(|layer| layer.iter().map(move |glyph| (layer.name(), glyph)))
+        .collect();
+    let glyph_warnings: Vec<Vec<LibWarning>> = glyphs
+        .par_iter()
+        .map(|(layer, glyph)| GlyphLib::new(&glyph.lib, layer, &glyph.name).1)
+        .collect();
+    warnings.extend(glyph_warnings.into_iter().flatten());
+    warnings
+}
+
+/// Returns the glyphs of `layer` in the order declared by `glyph_order`,
+/// usually the font's `public.glyphOrder`: first the listed glyphs that are
+/// in the layer, in the listed order and each once, then the unlisted ones
+/// sorted by name.
+pub fn ordered_glyphs<'a>(layer: &'a Layer, glyph_order: &[String]) -> Vec<&'a Arc<Glyph>> {
+    let mut seen = HashSet::new();
+    let mut glyphs: Vec<&Arc<Glyph>> = glyph_order
+        .iter()
+        .filter(|name| seen.insert(name.as_str()))
+        .filter_map(|name| layer.get_glyph(name.as_str()))
+        .collect();
+    let mut unlisted: Vec<&Arc<Glyph>> =
+        layer.iter().filter(|g| !seen.contains(&*g.name)).collect();
+    unlisted.sort_by(|a, b| a.name.cmp(&b.name));
+    glyphs.extend(unlisted);
+    glyphs
+}
+
+/// Returns the names of the glyphs in a layer of `font`, the default layer
+/// unless `layer` is given, in the order resolved from the font's
+/// `public.glyphOrder` by [`ordered_glyphs`]. Without a glyph order, the
+/// glyphs are sorted by name.
+pub fn resolved_glyph_order(font: &Font, layer: Option<&str>) -> Result<Vec<String>, Error> {
+    let layer = crate::layer_or_default(font, layer)?;
+    let glyph_order = FontLib::new(&font.lib).0.glyph_order.unwrap_or_default();
+    Ok(ordered_glyphs(layer, &glyph_order)
+        .into_iter()
+        .map(|g| g.name.to_string())
+        .collect())
+}
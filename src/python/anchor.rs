@@ -1,4 +1,4 @@
-use crate::ToWrappedPyObject;
+use super::{get_wrapped, FromWrappedPyObject, ToWrappedPyObject};
 use pyo3::prelude::*;
 use pyo3::types::IntoPyDict;
 
@@ -27,3 +27,16 @@ impl ToWrappedPyObject for norad::Anchor {
         cls.call((), Some(kwargs)).unwrap().into()
     }
 }
+
+impl FromWrappedPyObject for norad::Anchor {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        Ok(norad::Anchor::new(
+            obj.getattr("x")?.extract()?,
+            obj.getattr("y")?.extract()?,
+            obj.getattr("name")?.extract()?,
+            get_wrapped(obj, "color")?,
+            get_wrapped(obj, "identifier")?,
+            None,
+        ))
+    }
+}
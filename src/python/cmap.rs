@@ -0,0 +1,48 @@
+use super::MyToPyObject;
+use crate::cmap::{Cmap, CmapIssue};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for CmapIssue {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match self {
+            CmapIssue::Duplicate { codepoint, glyphs } => vec![
+                ("codepoint", codepoint.to_object(py)),
+                ("glyphs", glyphs.to_object(py)),
+            ],
+            CmapIssue::Invalid { glyph, codepoint } => vec![
+                ("glyph", glyph.to_object(py)),
+                ("codepoint", codepoint.to_object(py)),
+            ],
+            CmapIssue::NameMismatch {
+                glyph,
+                implied,
+                codepoints,
+            } => vec![
+                ("glyph", glyph.to_object(py)),
+                ("implied", implied.to_object(py)),
+                ("codepoints", codepoints.to_object(py)),
+            ],
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
+
+impl MyToPyObject for Cmap {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("mapping", self.mapping.to_object(py)),
+            ("issues", self.issues.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
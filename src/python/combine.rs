@@ -0,0 +1,46 @@
+use super::MyToPyObject;
+use crate::combine::{Conflict, Item, Resolution};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for Conflict {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+            ("resolution", self.resolution.kind().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let mut fields = match &self.item {
+            Item::Glyph(name) => vec![("glyph", name.to_object(py))],
+            Item::Codepoint {
+                codepoint,
+                ours,
+                theirs,
+            } => vec![
+                ("codepoint", (*codepoint as u32).to_object(py)),
+                ("ours", ours.to_object(py)),
+                ("theirs", theirs.to_object(py)),
+            ],
+            Item::Layer(name) => vec![("layer", name.to_object(py))],
+            Item::Kerning { first, second } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+            ],
+            Item::Group(name) => vec![("group", name.to_object(py))],
+            Item::GroupMembers { group, glyphs } => vec![
+                ("group", group.to_object(py)),
+                ("glyphs", glyphs.to_object(py)),
+            ],
+            Item::Lib(key) => vec![("key", key.to_object(py))],
+            Item::Features => vec![],
+        };
+        if let Resolution::Renamed(name) = &self.resolution {
+            fields.push(("renamed", name.to_object(py)));
+        }
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
@@ -0,0 +1,65 @@
+use super::MyToPyObject;
+use crate::compatibility::{Incompatibility, Problem};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for Incompatibility {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("glyph", self.glyph.to_object(py)),
+            ("master", self.master.to_object(py)),
+            ("kind", self.problem.kind().to_object(py)),
+            ("message", self.problem.to_string().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match &self.problem {
+            Problem::MissingGlyph => vec![],
+            Problem::ContourCount { expected, found }
+            | Problem::ComponentCount { expected, found } => vec![
+                ("expected", expected.to_object(py)),
+                ("found", found.to_object(py)),
+            ],
+            Problem::PointCount {
+                contour,
+                expected,
+                found,
+            } => vec![
+                ("contour", contour.to_object(py)),
+                ("expected", expected.to_object(py)),
+                ("found", found.to_object(py)),
+            ],
+            Problem::PointType {
+                contour,
+                point,
+                expected,
+                found,
+            } => vec![
+                ("contour", contour.to_object(py)),
+                ("point", point.to_object(py)),
+                ("expected", expected.to_string().to_object(py)),
+                ("found", found.to_string().to_object(py)),
+            ],
+            Problem::ComponentBase {
+                component,
+                expected,
+                found,
+            } => vec![
+                ("component", component.to_object(py)),
+                ("expected", expected.to_object(py)),
+                ("found", found.to_object(py)),
+            ],
+            Problem::Anchors { expected, found } => vec![
+                ("expected", expected.to_object(py)),
+                ("found", found.to_object(py)),
+            ],
+            Problem::StartPoint { contour, point } => vec![
+                ("contour", contour.to_object(py)),
+                ("point", point.to_object(py)),
+            ],
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
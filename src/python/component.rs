@@ -1,4 +1,5 @@
-use crate::ToWrappedPyObject;
+use super::{get_wrapped, FromWrappedPyObject, ToWrappedPyObject};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::IntoPyDict;
 
@@ -30,3 +31,27 @@ impl ToWrappedPyObject for norad::Component {
         cls.call((), Some(kwargs)).unwrap().into()
     }
 }
+
+impl FromWrappedPyObject for norad::Component {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let base: &str = obj.getattr("baseGlyph")?.extract()?;
+        let t: Vec<f32> = obj.getattr("transformation")?.extract()?;
+        if t.len() != 6 {
+            return Err(PyValueError::new_err("a transformation needs six values"));
+        }
+        let transform = norad::AffineTransform {
+            x_scale: t[0],
+            xy_scale: t[1],
+            yx_scale: t[2],
+            y_scale: t[3],
+            x_offset: t[4],
+            y_offset: t[5],
+        };
+        Ok(norad::Component::new(
+            base.into(),
+            transform,
+            get_wrapped(obj, "identifier")?,
+            None,
+        ))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ToWrappedPyObject;
+use super::{get_wrapped, FromWrappedPyObject, ToWrappedPyObject};
 use pyo3::prelude::*;
 use pyo3::types::IntoPyDict;
 
@@ -17,3 +17,13 @@ impl ToWrappedPyObject for norad::Contour {
         cls.call((), Some(kwargs)).unwrap().into()
     }
 }
+
+impl FromWrappedPyObject for norad::Contour {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        Ok(norad::Contour::new(
+            get_wrapped(obj, "points")?,
+            get_wrapped(obj, "identifier")?,
+            None,
+        ))
+    }
+}
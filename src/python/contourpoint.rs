@@ -1,4 +1,5 @@
-use crate::{MyToPyObject, ToWrappedPyObject};
+use super::{get_wrapped, FromWrappedPyObject, MyToPyObject, ToWrappedPyObject};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::IntoPyDict;
 
@@ -38,9 +39,34 @@ impl ToWrappedPyObject for norad::ContourPoint {
 /// Converts value to a Python integer if it can be considered one, otherwise a
 /// Python float.
 fn f32_to_int_or_float(v: f32, py: Python) -> PyObject {
-    if (v - v.round()).abs() < std::f32::EPSILON {
+    if (v - v.round()).abs() < f32::EPSILON {
         (v as i32).to_object(py)
     } else {
         v.to_object(py)
     }
 }
+
+impl FromWrappedPyObject for norad::PointType {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        match obj.extract::<Option<&str>>()? {
+            Some(typ) => typ
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("unknown point type '{}'", typ))),
+            None => Ok(norad::PointType::OffCurve),
+        }
+    }
+}
+
+impl FromWrappedPyObject for norad::ContourPoint {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        Ok(norad::ContourPoint::new(
+            obj.getattr("x")?.extract()?,
+            obj.getattr("y")?.extract()?,
+            get_wrapped(obj, "type")?,
+            obj.getattr("smooth")?.extract()?,
+            obj.getattr("name")?.extract()?,
+            get_wrapped(obj, "identifier")?,
+            None,
+        ))
+    }
+}
@@ -0,0 +1,149 @@
+use super::MyToPyObject;
+use crate::diff::{
+    FeaturesChange, FontDiff, GlyphChange, GlyphDiff, GroupChange, KerningChange, LayerDiff,
+    ValueChange,
+};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+fn codepoints(chars: &[char]) -> Vec<u32> {
+    chars.iter().map(|c| *c as u32).collect()
+}
+
+impl MyToPyObject for ValueChange {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("key", self.key.to_object(py)),
+            ("old", self.old.to_object(py)),
+            ("new", self.new.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for GlyphChange {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match self {
+            GlyphChange::Width { old, new } | GlyphChange::Height { old, new } => {
+                vec![("old", old.to_object(py)), ("new", new.to_object(py))]
+            }
+            GlyphChange::Unicodes { old, new } => vec![
+                ("old", codepoints(old).to_object(py)),
+                ("new", codepoints(new).to_object(py)),
+            ],
+            GlyphChange::Contours { old, new } | GlyphChange::Guidelines { old, new } => {
+                vec![("old", old.to_object(py)), ("new", new.to_object(py))]
+            }
+            GlyphChange::Components { old, new } => {
+                vec![("old", old.to_object(py)), ("new", new.to_object(py))]
+            }
+            GlyphChange::Anchors {
+                added,
+                removed,
+                moved,
+            } => vec![
+                ("added", added.to_object(py)),
+                ("removed", removed.to_object(py)),
+                ("moved", moved.to_object(py)),
+            ],
+            GlyphChange::Image => vec![],
+            GlyphChange::Note { old, new } => {
+                vec![("old", old.to_object(py)), ("new", new.to_object(py))]
+            }
+            GlyphChange::Lib(change) => vec![
+                ("key", change.key.to_object(py)),
+                ("old", change.old.to_object(py)),
+                ("new", change.new.to_object(py)),
+            ],
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
+
+impl MyToPyObject for GlyphDiff {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("name", self.name.to_object(py)),
+            ("status", self.status.as_str().to_object(py)),
+            ("changes", self.changes.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for LayerDiff {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("name", self.name.to_object(py)),
+            ("status", self.status.as_str().to_object(py)),
+            ("color", self.color.to_object(py)),
+            ("lib", self.lib.to_object(py)),
+            ("glyphs", self.glyphs.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for KerningChange {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("first", self.first.to_object(py)),
+            ("second", self.second.to_object(py)),
+            ("old", self.old.to_object(py)),
+            ("new", self.new.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for GroupChange {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("name", self.name.to_object(py)),
+            ("status", self.status.as_str().to_object(py)),
+            ("added", self.added.to_object(py)),
+            ("removed", self.removed.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for FeaturesChange {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("old", self.old.to_object(py)),
+            ("new", self.new.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for FontDiff {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("layers", self.layers.to_object(py)),
+            ("info", self.info.to_object(py)),
+            ("lib", self.lib.to_object(py)),
+            ("kerning", self.kerning.to_object(py)),
+            ("groups", self.groups.to_object(py)),
+            ("features", self.features.to_object(py)),
+            ("summary", self.to_string().to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
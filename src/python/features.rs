@@ -0,0 +1,41 @@
+use super::MyToPyObject;
+use crate::features::{CheckedFeatures, FeatureIssue};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for FeatureIssue {
+    fn to_object(&self, py: Python) -> PyObject {
+        let (file, line) = self.location();
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+            ("file", file.to_object(py)),
+            ("line", line.to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match self {
+            FeatureIssue::Include { path, .. } => vec![("path", path.to_object(py))],
+            FeatureIssue::Structure { .. } => vec![],
+            FeatureIssue::MissingGlyph { glyph, .. } => vec![("glyph", glyph.to_object(py))],
+            FeatureIssue::UndefinedClass { class, .. } => vec![("class", class.to_object(py))],
+            FeatureIssue::UnknownKeyword { keyword, .. } => {
+                vec![("keyword", keyword.to_object(py))]
+            }
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
+
+impl MyToPyObject for CheckedFeatures {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("text", self.text.to_object(py)),
+            ("issues", self.issues.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::{wrap_kerning, FromWrappedPyObject, IondriveError, MyToPyObject, ToWrappedPyObject};
+
+/// A point as `(x, y, type, smooth)`, the type being None for off-curve points.
+type PointTuple = (f32, f32, Option<String>, bool);
+
+/// A font loaded and owned by Rust.
+///
+/// Unlike `iondrive.load`, opening a `RustFont` does not create any Python
+/// objects; the accessors convert only the data they return. Call
+/// `to_ufolib2(ufoLib2.objects)` to get the complete ufoLib2 object tree.
+#[pyclass(name = "RustFont")]
+#[pyo3(text_signature = "(/)")]
+pub(super) struct RustFont {
+    pub(super) font: norad::Font,
+    /// Where the font was loaded from; the default target of `save`.
+    pub(super) path: Option<PathBuf>,
+}
+
+impl RustFont {
+    fn glyph_mut(&mut self, name: &str, layer: Option<&str>) -> PyResult<&mut norad::Glyph> {
+        crate::layer_or_default_mut(&mut self.font, layer)?
+            .get_glyph_mut(name)
+            .ok_or_else(|| crate::Error::MissingGlyph(name.to_string()).into())
+    }
+}
+
+/// Sets the fontinfo attribute `key`, named as in ufoLib2, to `value`, or
+/// unsets it if `value` is `None`.
+fn set_info_value(
+    info: &norad::FontInfo,
+    key: &str,
+    value: Option<plist::Value>,
+) -> Result<norad::FontInfo, crate::Error> {
+    let mut fields = plist::to_value(info)?.into_dictionary().unwrap_or_default();
+    match value {
+        Some(value) => fields.insert(key.to_string(), value),
+        None => fields.remove(key),
+    };
+    let info: norad::FontInfo = plist::from_value(&plist::Value::Dictionary(fields))?;
+    info.validate()?;
+    Ok(info)
+}
+
+#[pymethods]
+impl RustFont {
+    #[new]
+    fn new() -> Self {
+        RustFont {
+            font: norad::Font::new(),
+            path: None,
+        }
+    }
+
+    /// Load the UFO at `path`, warning about malformed `public.*` lib values
+    /// like `iondrive.load`.
+    #[staticmethod]
+    #[pyo3(text_signature = "(path, /)")]
+    fn open(path: PathBuf, py: Python) -> PyResult<Self> {
+        let font = crate::load(&path)?;
+        super::public_lib::warn_lib(py, &font)?;
+        Ok(RustFont {
+            font,
+            path: Some(path),
+        })
+    }
+
+    /// Copy a ufoLib2 `Font`, or an object with the same attributes, into a
+    /// new `RustFont`.
+    #[staticmethod]
+    #[pyo3(text_signature = "(font, /)")]
+    fn from_ufolib2(font: &PyAny) -> PyResult<Self> {
+        Ok(RustFont {
+            font: norad::Font::from_wrapped_object(font)?,
+            path: None,
+        })
+    }
+
+    /// Return the names of the glyphs in a layer, the default layer unless
+    /// `layer` is given.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, /, layer=None)")]
+    fn glyph_names(&self, layer: Option<String>) -> PyResult<Vec<String>> {
+        let layer = crate::layer_or_default(&self.font, layer.as_deref())?;
+        Ok(layer.iter().map(|g| g.name.to_string()).collect())
+    }
+
+    /// Return the `ComponentGraph` of a layer, the default layer unless
+    /// `layer` is given.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, /, layer=None)")]
+    fn component_graph(&self, layer: Option<String>) -> PyResult<super::PyComponentGraph> {
+        let layer = crate::layer_or_default(&self.font, layer.as_deref())?;
+        Ok(super::PyComponentGraph {
+            inner: crate::components::ComponentGraph::new(layer),
+        })
+    }
+
+    /// Return the names of all layers, starting with the default layer.
+    #[pyo3(text_signature = "($self, /)")]
+    fn layer_names(&self) -> Vec<String> {
+        self.font.layers.names().map(|n| n.to_string()).collect()
+    }
+
+    /// Return a dict mapping each codepoint of the default layer to the list
+    /// of glyph names that have it.
+    #[pyo3(text_signature = "($self, /)")]
+    fn unicode_map(&self) -> BTreeMap<u32, Vec<String>> {
+        let mut map: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+        for glyph in self.font.default_layer().iter() {
+            for cp in &glyph.codepoints {
+                map.entry(*cp as u32)
+                    .or_default()
+                    .push(glyph.name.to_string());
+            }
+        }
+        map
+    }
+
+    /// The fontinfo as a dict with ufoLib2's attribute names, leaving out
+    /// unset attributes.
+    #[getter]
+    fn info(&self, py: Python) -> PyObject {
+        self.font
+            .font_info
+            .as_ref()
+            .map_or(PyDict::new(py).into(), |info| info.to_object(py))
+    }
+
+    /// The kerning as a dict of `(first, second) -> value`.
+    #[getter]
+    fn kerning(&self, py: Python) -> PyObject {
+        wrap_kerning(self.font.kerning.as_ref(), py)
+    }
+
+    /// The groups as a dict of group name to list of glyph names.
+    #[getter]
+    fn groups(&self, py: Python) -> PyObject {
+        self.font
+            .groups
+            .as_ref()
+            .map_or(PyDict::new(py).into(), |g| g.to_object(py))
+    }
+
+    #[getter]
+    fn lib(&self, py: Python) -> PyObject {
+        self.font.lib.to_object(py)
+    }
+
+    #[getter]
+    fn features(&self) -> &str {
+        self.font.features.as_deref().unwrap_or("")
+    }
+
+    /// The well-known `public.*` keys of the lib as a dict of `glyph_order`,
+    /// `postscript_names`, `skip_export_glyphs`, `opentype_categories`,
+    /// `unicode_variation_sequences` (glyph names by variation selector and
+    /// base codepoint) and `object_libs`, each None if not set. Malformed
+    /// entries are left out.
+    #[getter]
+    fn public_lib(&self, py: Python) -> PyObject {
+        crate::public_lib::FontLib::new(&self.font.lib)
+            .0
+            .to_object(py)
+    }
+
+    /// The well-known `public.*` keys of the lib of the glyph `name` as a dict
+    /// of `mark_color` (a tuple of red, green, blue and alpha),
+    /// `vertical_origin` and `object_libs`, each None if not set or malformed.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, /, layer=None)")]
+    fn glyph_public_lib(
+        &self,
+        name: &str,
+        layer: Option<String>,
+        py: Python,
+    ) -> PyResult<PyObject> {
+        let layer = crate::layer_or_default(&self.font, layer.as_deref())?;
+        let glyph = layer
+            .get_glyph(name)
+            .ok_or_else(|| crate::Error::MissingGlyph(name.to_string()))?;
+        let lib = crate::public_lib::GlyphLib::new(&glyph.lib, layer.name(), name).0;
+        Ok(lib.to_object(py))
+    }
+
+    /// Convert the whole font to a ufoLib2 `Font`, using the objects from
+    /// `font_objects_module`.
+    #[pyo3(text_signature = "($self, font_objects_module, /)")]
+    fn to_ufolib2(&self, loader: &PyModule, py: Python) -> PyObject {
+        self.font.to_wrapped_object(loader, py)
+    }
+
+    /// Set the advance width of the glyph `name`.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, width, /, layer=None)")]
+    fn set_width(&mut self, name: &str, width: f32, layer: Option<String>) -> PyResult<()> {
+        self.glyph_mut(name, layer.as_deref())?.width = width;
+        Ok(())
+    }
+
+    /// Replace the unicodes of the glyph `name`.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, unicodes, /, layer=None)")]
+    fn set_unicodes(
+        &mut self,
+        name: &str,
+        unicodes: Vec<u32>,
+        layer: Option<String>,
+    ) -> PyResult<()> {
+        let codepoints = unicodes
+            .into_iter()
+            .map(|u| {
+                char::from_u32(u)
+                    .ok_or_else(|| PyValueError::new_err(format!("invalid codepoint {:#x}", u)))
+            })
+            .collect::<PyResult<Vec<char>>>()?;
+        self.glyph_mut(name, layer.as_deref())?.codepoints = codepoints;
+        Ok(())
+    }
+
+    /// Replace the contours of the glyph `name`.
+    ///
+    /// `contours` is a list of contours, each a list of `(x, y, type, smooth)`
+    /// points, where `type` is one of "move", "line", "curve", "qcurve" or
+    /// None for off-curve points.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, contours, /, layer=None)")]
+    fn set_contours(
+        &mut self,
+        name: &str,
+        contours: Vec<Vec<PointTuple>>,
+        layer: Option<String>,
+    ) -> PyResult<()> {
+        let contours = contours
+            .into_iter()
+            .map(|points| {
+                let points = points
+                    .into_iter()
+                    .map(|(x, y, typ, smooth)| {
+                        let typ = match typ {
+                            Some(typ) => typ.parse().map_err(|_| {
+                                PyValueError::new_err(format!("unknown point type '{}'", typ))
+                            })?,
+                            None => norad::PointType::OffCurve,
+                        };
+                        Ok(norad::ContourPoint::new(
+                            x, y, typ, smooth, None, None, None,
+                        ))
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                Ok(norad::Contour::new(points, None, None))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        self.glyph_mut(name, layer.as_deref())?.contours = contours;
+        Ok(())
+    }
+
+    /// Add an empty glyph called `name`.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, /, layer=None)")]
+    fn add_glyph(&mut self, name: &str, layer: Option<String>) -> PyResult<()> {
+        let layer = crate::layer_or_default_mut(&mut self.font, layer.as_deref())?;
+        if layer.contains_glyph(name) {
+            return Err(IondriveError::new_err(format!(
+                "glyph '{}' already exists",
+                name
+            )));
+        }
+        layer.insert_glyph(norad::Glyph::new_named(name));
+        Ok(())
+    }
+
+    /// Remove the glyph `name`.
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "($self, name, /, layer=None)")]
+    fn remove_glyph(&mut self, name: &str, layer: Option<String>) -> PyResult<()> {
+        crate::layer_or_default_mut(&mut self.font, layer.as_deref())?
+            .remove_glyph(name)
+            .ok_or_else(|| crate::Error::MissingGlyph(name.to_string()))?;
+        Ok(())
+    }
+
+    /// Rename glyphs from the keys of `mapping` to its values, in every
+    /// layer, along with the component base glyphs, kerning, groups,
+    /// `public.glyphOrder` and other `public.*` lib keys and the glyph names
+    /// in the features that refer to them. Raises `IondriveError` and changes
+    /// nothing if a glyph is missing or a new name is taken. `save` writes
+    /// the glyphs to files named after their new names.
+    #[pyo3(text_signature = "($self, mapping, /)")]
+    fn rename_glyphs(&mut self, mapping: BTreeMap<String, String>) -> PyResult<()> {
+        Ok(crate::rename::rename_glyphs(&mut self.font, &mapping)?)
+    }
+
+    /// Add an empty layer called `name`.
+    #[pyo3(text_signature = "($self, name, /)")]
+    fn add_layer(&mut self, name: &str) -> PyResult<()> {
+        self.font
+            .layers
+            .new_layer(name)
+            .map_err(crate::Error::Norad)?;
+        Ok(())
+    }
+
+    /// Remove the layer `name`. The default layer cannot be removed.
+    #[pyo3(text_signature = "($self, name, /)")]
+    fn remove_layer(&mut self, name: &str) -> PyResult<()> {
+        if self.font.default_layer().name().as_ref() == name {
+            return Err(IondriveError::new_err("cannot remove the default layer"));
+        }
+        self.font
+            .layers
+            .remove(name)
+            .ok_or_else(|| crate::Error::MissingLayer(name.to_string()))?;
+        Ok(())
+    }
+
+    /// Set the fontinfo attribute `key`, using ufoLib2's attribute names, or
+    /// unset it if `value` is None.
+    #[pyo3(text_signature = "($self, key, value, /)")]
+    fn set_info(&mut self, key: &str, value: &PyAny) -> PyResult<()> {
+        let value = if value.is_none() {
+            None
+        } else {
+            Some(plist::Value::from_wrapped_object(value)?)
+        };
+        let info = self.font.font_info.clone().unwrap_or_default();
+        let info = set_info_value(&info, key, value).map_err(|error| {
+            IondriveError::new_err(format!("cannot set fontinfo '{}': {}", key, error))
+        })?;
+        self.font.font_info = Some(info);
+        Ok(())
+    }
+
+    /// Set the kerning between `first` and `second`, or remove the pair if
+    /// `value` is None.
+    #[pyo3(text_signature = "($self, first, second, value, /)")]
+    fn set_kerning(&mut self, first: &str, second: &str, value: Option<f32>) {
+        let kerning = self.font.kerning.get_or_insert_with(Default::default);
+        match value {
+            Some(value) => {
+                kerning
+                    .entry(first.to_string())
+                    .or_default()
+                    .insert(second.to_string(), value);
+            }
+            None => {
+                if let Some(seconds) = kerning.get_mut(first) {
+                    seconds.remove(second);
+                    if seconds.is_empty() {
+                        kerning.remove(first);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Set the members of the group `name`, or remove the group if `members`
+    /// is None.
+    #[pyo3(text_signature = "($self, name, members, /)")]
+    fn set_group(&mut self, name: &str, members: Option<Vec<String>>) {
+        let groups = self.font.groups.get_or_insert_with(Default::default);
+        match members {
+            Some(members) => {
+                groups.insert(
+                    name.to_string(),
+                    members.into_iter().map(|m| m.into()).collect(),
+                );
+            }
+            None => {
+                groups.remove(name);
+            }
+        }
+    }
+
+    /// Save the font to `path`, or where it was opened from.
+    ///
+    /// Only files whose contents change are written. Returns the paths of the
+    /// written and removed files, relative to the UFO.
+    #[args(path = "None")]
+    #[pyo3(text_signature = "($self, /, path=None)")]
+    fn save(&mut self, path: Option<PathBuf>) -> PyResult<Vec<PathBuf>> {
+        let path = match path.or_else(|| self.path.clone()) {
+            Some(path) => path,
+            None => return Err(IondriveError::new_err("no path to save the font to")),
+        };
+        let changed = crate::save(&self.font, &path)?;
+        self.path = Some(path);
+        Ok(changed)
+    }
+}
@@ -0,0 +1,76 @@
+use super::{get_wrapped, FromWrappedPyObject, MyToPyObject, ToWrappedPyObject};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use pyo3::types::PyDict;
+
+impl ToWrappedPyObject for norad::Guideline {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        let cls = loader.getattr("Guideline").unwrap();
+        cls.call((), Some(guideline_kwargs(self, py)))
+            .unwrap()
+            .into()
+    }
+}
+
+/// Converts a guideline to a dict with the same keys as ufoLib2's `Guideline`.
+impl MyToPyObject for norad::Guideline {
+    fn to_object(&self, py: Python) -> PyObject {
+        guideline_kwargs(self, py).into()
+    }
+}
+
+fn guideline_kwargs<'p>(guideline: &norad::Guideline, py: Python<'p>) -> &'p PyDict {
+    let (x, y, angle) = match guideline.line {
+        norad::Line::Vertical(x) => (Some(x), None, None),
+        norad::Line::Horizontal(y) => (None, Some(y), None),
+        norad::Line::Angle { x, y, degrees } => (Some(x), Some(y), Some(degrees)),
+    };
+    [
+        ("name", guideline.name.to_object(py)),
+        ("x", x.to_object(py)),
+        ("y", y.to_object(py)),
+        ("angle", angle.to_object(py)),
+        (
+            "color",
+            guideline
+                .color
+                .as_ref()
+                .map(|c| c.to_rgba_string())
+                .to_object(py),
+        ),
+        (
+            "identifier",
+            guideline
+                .identifier()
+                .map_or(py.None(), |i| i.as_str().to_object(py)),
+        ),
+        // ("lib", guideline.lib().map_or(py.None(), |l| l.to_object(py))),
+    ]
+    .into_py_dict(py)
+}
+
+impl FromWrappedPyObject for norad::Guideline {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let x: Option<f32> = obj.getattr("x")?.extract()?;
+        let y: Option<f32> = obj.getattr("y")?.extract()?;
+        let angle: Option<f32> = obj.getattr("angle")?.extract()?;
+        let line = match (x, y, angle) {
+            (Some(x), None, None) => norad::Line::Vertical(x),
+            (None, Some(y), None) => norad::Line::Horizontal(y),
+            (Some(x), Some(y), Some(degrees)) => norad::Line::Angle { x, y, degrees },
+            _ => {
+                return Err(PyValueError::new_err(
+                    "a guideline needs x, y, or x, y and angle",
+                ))
+            }
+        };
+        Ok(norad::Guideline::new(
+            line,
+            obj.getattr("name")?.extract()?,
+            get_wrapped(obj, "color")?,
+            get_wrapped(obj, "identifier")?,
+            None,
+        ))
+    }
+}
@@ -0,0 +1,23 @@
+use super::MyToPyObject;
+use crate::hash::{to_hex, FontHashes};
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict};
+
+impl MyToPyObject for FontHashes {
+    fn to_object(&self, py: Python) -> PyObject {
+        let layers = PyDict::new(py);
+        for (layer, glyphs) in &self.layers {
+            let glyphs: Vec<_> = glyphs
+                .iter()
+                .map(|(name, digest)| (name, to_hex(digest)))
+                .collect();
+            layers.set_item(layer, glyphs.into_py_dict(py)).unwrap();
+        }
+        [
+            ("layers", layers.to_object(py)),
+            ("font", to_hex(&self.font).to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
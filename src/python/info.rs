@@ -0,0 +1,526 @@
+use super::{FromWrappedPyObject, MyToPyObject, ToWrappedPyObject};
+use norad::fontinfo::StyleMapStyle;
+use norad::IntegerOrFloat;
+use norad::NonNegativeIntegerOrFloat;
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use pyo3::types::PyDict;
+use pyo3::PyNativeType;
+
+impl MyToPyObject for StyleMapStyle {
+    fn to_object(&self, py: Python) -> PyObject {
+        match self {
+            StyleMapStyle::Regular => "regular".to_string(),
+            StyleMapStyle::Italic => "italic".to_string(),
+            StyleMapStyle::Bold => "bold".to_string(),
+            StyleMapStyle::BoldItalic => "bold italic".to_string(),
+        }
+        .to_object(py)
+    }
+}
+
+impl MyToPyObject for norad::fontinfo::GaspRangeRecord {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("rangeMaxPPEM", self.range_max_ppem.to_object(py)),
+            ("rangeGaspBehavior", self.range_gasp_behavior.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .to_object(py)
+    }
+}
+
+impl MyToPyObject for norad::fontinfo::GaspBehavior {
+    fn to_object(&self, py: Python) -> PyObject {
+        (*self as u8).to_object(py)
+    }
+}
+
+impl MyToPyObject for norad::fontinfo::Os2Panose {
+    fn to_object(&self, py: Python) -> PyObject {
+        vec![
+            self.family_type,
+            self.serif_style,
+            self.weight,
+            self.proportion,
+            self.contrast,
+            self.stroke_variation,
+            self.arm_style,
+            self.letterform,
+            self.midline,
+            self.x_height,
+        ]
+        .to_object(py)
+    }
+}
+
+impl MyToPyObject for norad::fontinfo::Os2FamilyClass {
+    fn to_object(&self, py: Python) -> PyObject {
+        vec![self.class_id, self.subclass_id].to_object(py)
+    }
+}
+
+impl MyToPyObject for norad::fontinfo::NameRecord {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("nameID", self.name_id.to_object(py)),
+            ("platformID", self.platform_id.to_object(py)),
+            ("encodingID", self.encoding_id.to_object(py)),
+            ("languageID", self.language_id.to_object(py)),
+            ("string", self.string.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .to_object(py)
+    }
+}
+
+impl MyToPyObject for IntegerOrFloat {
+    fn to_object(&self, py: Python) -> PyObject {
+        if self.is_integer() {
+            (self.get() as i64).to_object(py)
+        } else {
+            self.get().to_object(py)
+        }
+    }
+}
+
+impl MyToPyObject for NonNegativeIntegerOrFloat {
+    fn to_object(&self, py: Python) -> PyObject {
+        if self.is_integer() {
+            (self.get() as i64).to_object(py)
+        } else {
+            self.get().to_object(py)
+        }
+    }
+}
+
+/// Returns the fontinfo attributes as keyword arguments for ufoLib2's `Info`,
+/// taking the guidelines already converted.
+fn info_kwargs<'p>(info: &norad::FontInfo, guidelines: PyObject, py: Python<'p>) -> &'p PyDict {
+    [
+        ("ascender", info.ascender.to_object(py)),
+        ("capHeight", info.cap_height.to_object(py)),
+        ("copyright", info.copyright.to_object(py)),
+        ("descender", info.descender.to_object(py)),
+        ("familyName", info.family_name.to_object(py)),
+        ("guidelines", guidelines),
+        ("italicAngle", info.italic_angle.to_object(py)),
+        (
+            "macintoshFONDFamilyID",
+            info.macintosh_fond_family_id.to_object(py),
+        ),
+        ("macintoshFONDName", info.macintosh_fond_name.to_object(py)),
+        ("note", info.note.to_object(py)),
+        (
+            "openTypeGaspRangeRecords",
+            info.open_type_gasp_range_records.to_object(py),
+        ),
+        (
+            "openTypeHeadCreated",
+            info.open_type_head_created.to_object(py),
+        ),
+        ("openTypeHeadFlags", info.open_type_head_flags.to_object(py)),
+        (
+            "openTypeHeadLowestRecPPEM",
+            info.open_type_head_lowest_rec_ppem.to_object(py),
+        ),
+        (
+            "openTypeHheaAscender",
+            info.open_type_hhea_ascender.to_object(py),
+        ),
+        (
+            "openTypeHheaCaretOffset",
+            info.open_type_hhea_caret_offset.to_object(py),
+        ),
+        (
+            "openTypeHheaCaretSlopeRise",
+            info.open_type_hhea_caret_slope_rise.to_object(py),
+        ),
+        (
+            "openTypeHheaCaretSlopeRun",
+            info.open_type_hhea_caret_slope_run.to_object(py),
+        ),
+        (
+            "openTypeHheaDescender",
+            info.open_type_hhea_descender.to_object(py),
+        ),
+        (
+            "openTypeHheaLineGap",
+            info.open_type_hhea_line_gap.to_object(py),
+        ),
+        (
+            "openTypeNameCompatibleFullName",
+            info.open_type_name_compatible_full_name
+                .as_ref()
+                .to_object(py),
+        ),
+        (
+            "openTypeNameDescription",
+            info.open_type_name_description.to_object(py),
+        ),
+        (
+            "openTypeNameDesignerURL",
+            info.open_type_name_designer_url.to_object(py),
+        ),
+        (
+            "openTypeNameDesigner",
+            info.open_type_name_designer.to_object(py),
+        ),
+        (
+            "openTypeNameLicense",
+            info.open_type_name_license.to_object(py),
+        ),
+        (
+            "openTypeNameLicenseURL",
+            info.open_type_name_license_url.to_object(py),
+        ),
+        (
+            "openTypeNameManufacturer",
+            info.open_type_name_manufacturer.to_object(py),
+        ),
+        (
+            "openTypeNameManufacturerURL",
+            info.open_type_name_manufacturer_url.to_object(py),
+        ),
+        (
+            "openTypeNamePreferredFamilyName",
+            info.open_type_name_preferred_family_name.to_object(py),
+        ),
+        (
+            "openTypeNamePreferredSubfamilyName",
+            info.open_type_name_preferred_subfamily_name.to_object(py),
+        ),
+        (
+            "openTypeNameRecords",
+            info.open_type_name_records.to_object(py),
+        ),
+        (
+            "openTypeNameSampleText",
+            info.open_type_name_sample_text.to_object(py),
+        ),
+        (
+            "openTypeNameUniqueID",
+            info.open_type_name_unique_id.to_object(py),
+        ),
+        (
+            "openTypeNameVersion",
+            info.open_type_name_version.to_object(py),
+        ),
+        (
+            "openTypeNameWWSFamilyName",
+            info.open_type_name_wws_family_name.to_object(py),
+        ),
+        (
+            "openTypeNameWWSSubfamilyName",
+            info.open_type_name_wws_subfamily_name.to_object(py),
+        ),
+        (
+            "openTypeOS2CodePageRanges",
+            info.open_type_os2_code_page_ranges.to_object(py),
+        ),
+        (
+            "openTypeOS2FamilyClass",
+            info.open_type_os2_family_class.to_object(py),
+        ),
+        ("openTypeOS2Panose", info.open_type_os2_panose.to_object(py)),
+        (
+            "openTypeOS2Selection",
+            info.open_type_os2_selection.to_object(py),
+        ),
+        (
+            "openTypeOS2StrikeoutPosition",
+            info.open_type_os2_strikeout_position.to_object(py),
+        ),
+        (
+            "openTypeOS2StrikeoutSize",
+            info.open_type_os2_strikeout_size.to_object(py),
+        ),
+        (
+            "openTypeOS2SubscriptXOffset",
+            info.open_type_os2_subscript_x_offset.to_object(py),
+        ),
+        (
+            "openTypeOS2SubscriptXSize",
+            info.open_type_os2_subscript_x_size.to_object(py),
+        ),
+        (
+            "openTypeOS2SubscriptYOffset",
+            info.open_type_os2_subscript_y_offset.to_object(py),
+        ),
+        (
+            "openTypeOS2SubscriptYSize",
+            info.open_type_os2_subscript_y_size.to_object(py),
+        ),
+        (
+            "openTypeOS2SuperscriptXOffset",
+            info.open_type_os2_superscript_x_offset.to_object(py),
+        ),
+        (
+            "openTypeOS2SuperscriptXSize",
+            info.open_type_os2_superscript_x_size.to_object(py),
+        ),
+        (
+            "openTypeOS2SuperscriptYOffset",
+            info.open_type_os2_superscript_y_offset.to_object(py),
+        ),
+        (
+            "openTypeOS2SuperscriptYSize",
+            info.open_type_os2_superscript_y_size.to_object(py),
+        ),
+        ("openTypeOS2Type", info.open_type_os2_type.to_object(py)),
+        (
+            "openTypeOS2TypoAscender",
+            info.open_type_os2_typo_ascender.to_object(py),
+        ),
+        (
+            "openTypeOS2TypoDescender",
+            info.open_type_os2_typo_descender.to_object(py),
+        ),
+        (
+            "openTypeOS2TypoLineGap",
+            info.open_type_os2_typo_line_gap.to_object(py),
+        ),
+        (
+            "openTypeOS2UnicodeRanges",
+            info.open_type_os2_unicode_ranges.to_object(py),
+        ),
+        (
+            "openTypeOS2VendorID",
+            info.open_type_os2_vendor_id.to_object(py),
+        ),
+        (
+            "openTypeOS2WeightClass",
+            info.open_type_os2_weight_class.to_object(py),
+        ),
+        (
+            "openTypeOS2WidthClass",
+            info.open_type_os2_width_class
+                .map(|x| x as u8)
+                .to_object(py),
+        ),
+        (
+            "openTypeOS2WinAscent",
+            info.open_type_os2_win_ascent.to_object(py),
+        ),
+        (
+            "openTypeOS2WinDescent",
+            info.open_type_os2_win_descent.to_object(py),
+        ),
+        (
+            "openTypeVheaCaretOffset",
+            info.open_type_vhea_caret_offset.to_object(py),
+        ),
+        (
+            "openTypeVheaCaretSlopeRise",
+            info.open_type_vhea_caret_slope_rise.to_object(py),
+        ),
+        (
+            "openTypeVheaCaretSlopeRun",
+            info.open_type_vhea_caret_slope_run.to_object(py),
+        ),
+        (
+            "openTypeVheaVertTypoAscender",
+            info.open_type_vhea_vert_typo_ascender.to_object(py),
+        ),
+        (
+            "openTypeVheaVertTypoDescender",
+            info.open_type_vhea_vert_typo_descender.to_object(py),
+        ),
+        (
+            "openTypeVheaVertTypoLineGap",
+            info.open_type_vhea_vert_typo_line_gap.to_object(py),
+        ),
+        (
+            "postscriptBlueFuzz",
+            info.postscript_blue_fuzz.to_object(py),
+        ),
+        (
+            "postscriptBlueScale",
+            info.postscript_blue_scale.to_object(py),
+        ),
+        (
+            "postscriptBlueShift",
+            info.postscript_blue_shift.to_object(py),
+        ),
+        (
+            "postscriptBlueValues",
+            info.postscript_blue_values.to_object(py),
+        ),
+        (
+            "postscriptDefaultCharacter",
+            info.postscript_default_character.to_object(py),
+        ),
+        (
+            "postscriptDefaultWidthX",
+            info.postscript_default_width_x.to_object(py),
+        ),
+        (
+            "postscriptFamilyBlues",
+            info.postscript_family_blues.to_object(py),
+        ),
+        (
+            "postscriptFamilyOtherBlues",
+            info.postscript_family_other_blues.to_object(py),
+        ),
+        (
+            "postscriptFontName",
+            info.postscript_font_name.to_object(py),
+        ),
+        (
+            "postscriptForceBold",
+            info.postscript_force_bold.to_object(py),
+        ),
+        (
+            "postscriptFullName",
+            info.postscript_full_name.to_object(py),
+        ),
+        (
+            "postscriptIsFixedPitch",
+            info.postscript_is_fixed_pitch.to_object(py),
+        ),
+        (
+            "postscriptNominalWidthX",
+            info.postscript_nominal_width_x.to_object(py),
+        ),
+        (
+            "postscriptOtherBlues",
+            info.postscript_other_blues.to_object(py),
+        ),
+        (
+            "postscriptSlantAngle",
+            info.postscript_slant_angle.to_object(py),
+        ),
+        (
+            "postscriptStemSnapH",
+            info.postscript_stem_snap_h.to_object(py),
+        ),
+        (
+            "postscriptStemSnapV",
+            info.postscript_stem_snap_v.to_object(py),
+        ),
+        (
+            "postscriptUnderlinePosition",
+            info.postscript_underline_position.to_object(py),
+        ),
+        (
+            "postscriptUnderlineThickness",
+            info.postscript_underline_thickness.to_object(py),
+        ),
+        (
+            "postscriptUniqueID",
+            info.postscript_unique_id.to_object(py),
+        ),
+        (
+            "postscriptWeightName",
+            info.postscript_weight_name.to_object(py),
+        ),
+        (
+            "postscriptWindowsCharacterSet",
+            info.postscript_windows_character_set
+                .map(|x| x as u8)
+                .to_object(py),
+        ),
+        (
+            "styleMapFamilyName",
+            info.style_map_family_name.to_object(py),
+        ),
+        ("styleMapStyleName", info.style_map_style_name.to_object(py)),
+        ("styleName", info.style_name.to_object(py)),
+        ("trademark", info.trademark.to_object(py)),
+        ("unitsPerEm", info.units_per_em.to_object(py)),
+        ("versionMajor", info.version_major.to_object(py)),
+        ("versionMinor", info.version_minor.to_object(py)),
+        // ("woffMajorVersion", info.woff_major_version.to_object(py)),
+        // ("woffMinorVersion", info.woff_minor_version.to_object(py)),
+        ("xHeight", info.x_height.to_object(py)),
+        ("year", info.year.to_object(py)),
+    ]
+    .into_py_dict(py)
+}
+
+impl ToWrappedPyObject for norad::FontInfo {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        let cls = loader.getattr("Info").unwrap();
+        let kwargs = info_kwargs(self, self.guidelines.to_wrapped_object(loader, py), py);
+        cls.call((), Some(kwargs)).unwrap().into()
+    }
+}
+
+/// Converts fontinfo to a plain dict, leaving out unset attributes.
+impl MyToPyObject for norad::FontInfo {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = PyDict::new(py);
+        for (key, value) in info_kwargs(self, self.guidelines.to_object(py), py) {
+            if !value.is_none() {
+                d.set_item(key, value).unwrap();
+            }
+        }
+        d.into()
+    }
+}
+
+/// Reads the attributes that `ToWrappedPyObject` sets on ufoLib2's `Info`.
+impl FromWrappedPyObject for norad::FontInfo {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let py = obj.py();
+        let mut fields = plist::Dictionary::new();
+        for (key, _) in info_kwargs(&norad::FontInfo::default(), py.None(), py) {
+            let key: &str = key.extract()?;
+            let value = obj.getattr(key)?;
+            if value.is_none() {
+                continue;
+            }
+            let value = if key == "guidelines" {
+                let guidelines = Vec::<norad::Guideline>::from_wrapped_object(value)?;
+                plist::to_value(&guidelines).map_err(crate::Error::from)?
+            } else {
+                plist::Value::from_wrapped_object(value)?
+            };
+            fields.insert(key.to_string(), value);
+        }
+        let info: norad::FontInfo =
+            plist::from_value(&plist::Value::Dictionary(fields)).map_err(crate::Error::from)?;
+        info.validate().map_err(crate::Error::from)?;
+        Ok(info)
+    }
+}
+
+// TODO: Wrap all WOFF attributes. ufoLib2 does at the time of this writing not support them.
+
+//     // #[getter]
+//     // fn woffMetadataCopyright(&self) -> Option<WoffMetadataCopyright> {
+//     //     self.fontinfo.woff_metadata_copyright
+//     // }
+//     // #[getter]
+//     // fn woffMetadataCredits(&self) -> Option<WoffMetadataCredits> {
+//     //     self.fontinfo.woff_metadata_credits
+//     // }
+//     // #[getter]
+//     // fn woffMetadataDescription(&self) -> Option<WoffMetadataDescription> {
+//     //     self.fontinfo.woff_metadata_description
+//     // }
+//     // #[getter]
+//     // fn woffMetadataExtensions(&self) -> Option<Vec<WoffMetadataExtensionRecord>> {
+//     //     self.fontinfo.woff_metadata_extensions
+//     // }
+//     // #[getter]
+//     // fn woffMetadataLicense(&self) -> Option<WoffMetadataLicense> {
+//     //     self.fontinfo.woff_metadata_license
+//     // }
+//     // #[getter]
+//     // fn woffMetadataLicensee(&self) -> Option<WoffMetadataLicensee> {
+//     //     self.fontinfo.woff_metadata_licensee
+//     // }
+//     // #[getter]
+//     // fn woffMetadataTrademark(&self) -> Option<WoffMetadataTrademark> {
+//     //     self.fontinfo.woff_metadata_trademark
+//     // }
+//     // #[getter]
+//     // fn woffMetadataUniqueID(&self) -> Option<WoffMetadataUniqueID> {
+//     //     self.fontinfo.woff_metadata_unique_id
+//     // }
+//     // #[getter]
+//     // fn woffMetadataVendor(&self) -> Option<WoffMetadataVendor> {
+//     //     self.fontinfo.woff_metadata_vendor
+//     // }
+// }
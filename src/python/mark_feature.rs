@@ -0,0 +1,18 @@
+use super::MyToPyObject;
+use crate::mark_feature::MarkFeatures;
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for MarkFeatures {
+    fn to_object(&self, py: Python) -> PyObject {
+        [
+            ("mark_classes", self.mark_classes.to_object(py)),
+            ("bases", self.bases.to_object(py)),
+            ("ligatures", self.ligatures.to_object(py)),
+            ("marks", self.marks.to_object(py)),
+            ("fea", self.to_fea().to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
@@ -0,0 +1,36 @@
+use super::MyToPyObject;
+use crate::merge::Conflict;
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+impl MyToPyObject for Conflict {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match self {
+            Conflict::Glyph { layer, glyph } => vec![
+                ("layer", layer.to_object(py)),
+                ("glyph", glyph.to_object(py)),
+            ],
+            Conflict::Layer(name) => vec![("layer", name.to_object(py))],
+            Conflict::Lib { layer, key } => {
+                vec![("layer", layer.to_object(py)), ("key", key.to_object(py))]
+            }
+            Conflict::Info(key) => vec![("key", key.to_object(py))],
+            Conflict::Kerning { first, second } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+            ],
+            Conflict::Group(name) => vec![("group", name.to_object(py))],
+            Conflict::Features => vec![],
+            Conflict::File(path) => vec![("path", path.to_object(py))],
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
@@ -0,0 +1,1204 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use pyo3::types::PyDict;
+use pyo3::wrap_pyfunction;
+
+use crate::{bounds, components, decompose, kerning, validate};
+
+mod anchor;
+mod cmap;
+mod combine;
+mod compatibility;
+mod component;
+mod contour;
+mod contourpoint;
+mod diff;
+mod features;
+mod font;
+mod guideline;
+mod hash;
+mod info;
+mod mark_feature;
+mod merge;
+mod plist;
+mod public_lib;
+
+trait ToWrappedPyObject {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject;
+}
+
+trait MyToPyObject {
+    fn to_object(&self, py: Python) -> PyObject;
+}
+
+/// Conversion from ufoLib2 objects (or anything with the same attributes)
+/// back to norad types.
+trait FromWrappedPyObject: Sized {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self>;
+}
+
+impl<T> FromWrappedPyObject for Option<T>
+where
+    T: FromWrappedPyObject,
+{
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        if obj.is_none() {
+            Ok(None)
+        } else {
+            T::from_wrapped_object(obj).map(Some)
+        }
+    }
+}
+
+impl<T> FromWrappedPyObject for Vec<T>
+where
+    T: FromWrappedPyObject,
+{
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        obj.iter()?
+            .map(|item| T::from_wrapped_object(item?))
+            .collect()
+    }
+}
+
+/// Extracts the attribute `name` of `obj` as a norad type.
+fn get_wrapped<T: FromWrappedPyObject>(obj: &PyAny, name: &str) -> PyResult<T> {
+    T::from_wrapped_object(obj.getattr(name)?)
+}
+
+impl FromWrappedPyObject for norad::Identifier {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let identifier: &str = obj.extract()?;
+        norad::Identifier::new(identifier)
+            .map_err(|_| PyValueError::new_err(format!("invalid identifier '{}'", identifier)))
+    }
+}
+
+impl FromWrappedPyObject for norad::Color {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let color: &str = obj.extract()?;
+        color
+            .parse()
+            .map_err(|_| PyValueError::new_err(format!("invalid color '{}'", color)))
+    }
+}
+
+impl<T> ToWrappedPyObject for Option<T>
+where
+    T: ToWrappedPyObject,
+{
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        self.as_ref()
+            .map_or(py.None(), |x| x.to_wrapped_object(loader, py))
+    }
+}
+
+impl<T> ToWrappedPyObject for Vec<T>
+where
+    T: ToWrappedPyObject,
+{
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        self.iter()
+            .map(|x| x.to_wrapped_object(loader, py))
+            .collect::<Vec<PyObject>>()
+            .to_object(py)
+    }
+}
+
+impl<T> MyToPyObject for Vec<T>
+where
+    T: MyToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        self.iter()
+            .map(|x| x.to_object(py))
+            .collect::<Vec<PyObject>>()
+            .to_object(py)
+    }
+}
+impl<T> MyToPyObject for Option<T>
+where
+    T: MyToPyObject,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        self.as_ref().map_or(py.None(), |x| x.to_object(py))
+    }
+}
+
+impl MyToPyObject for Arc<str> {
+    fn to_object(&self, py: Python) -> PyObject {
+        Arc::clone(self).to_string().to_object(py)
+    }
+}
+
+impl<A, B> MyToPyObject for BTreeMap<A, B>
+where
+    A: ToPyObject + std::fmt::Debug,
+    B: MyToPyObject + std::fmt::Debug,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = PyDict::new(py);
+        for (k, v) in self.iter() {
+            d.set_item(k, v.to_object(py)).unwrap();
+        }
+        d.into()
+    }
+}
+
+impl ToWrappedPyObject for Arc<norad::Glyph> {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        let cls = loader.getattr("Glyph").unwrap();
+        let kwargs = [
+            ("name", self.name.to_object(py)),
+            ("width", self.width.to_object(py)),
+            ("height", self.height.to_object(py)),
+            (
+                "unicodes",
+                self.codepoints
+                    .iter()
+                    .map(|l| (*l as u32).to_object(py))
+                    .collect::<Vec<PyObject>>()
+                    .to_object(py),
+            ),
+            ("lib", self.lib.to_object(py)),
+            ("note", self.note.to_object(py)),
+            ("anchors", self.anchors.to_wrapped_object(loader, py)),
+            ("contours", self.contours.to_wrapped_object(loader, py)),
+            ("components", self.components.to_wrapped_object(loader, py)),
+            ("guidelines", self.guidelines.to_wrapped_object(loader, py)),
+        ]
+        .into_py_dict(py);
+        cls.call((), Some(kwargs)).unwrap().into()
+    }
+}
+
+impl ToWrappedPyObject for norad::Layer {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        wrap_layer(self, None, loader, py)
+    }
+}
+
+/// Wraps `layer`, with its glyphs ordered by `glyph_order` if given (see
+/// [`crate::public_lib::ordered_glyphs`]).
+fn wrap_layer(
+    layer: &norad::Layer,
+    glyph_order: Option<&[String]>,
+    loader: &PyModule,
+    py: Python,
+) -> PyObject {
+    let cls = loader.getattr("Layer").unwrap();
+    let glyphs: Vec<&Arc<norad::Glyph>> = match glyph_order {
+        Some(order) => crate::public_lib::ordered_glyphs(layer, order),
+        None => layer.iter().collect(),
+    };
+    let kwargs = [
+        ("name", layer.name().to_object(py)),
+        (
+            "glyphs",
+            glyphs
+                .into_iter()
+                .map(|g| g.to_wrapped_object(loader, py))
+                .collect::<Vec<PyObject>>()
+                .to_object(py),
+        ),
+        ("lib", layer.lib.to_object(py)),
+        (
+            "color",
+            layer
+                .color
+                .as_ref()
+                .map(|c| c.to_rgba_string())
+                .to_object(py),
+        ), // ()
+    ]
+    .into_py_dict(py);
+    cls.call((), Some(kwargs)).unwrap().into()
+}
+
+impl FromWrappedPyObject for norad::Glyph {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let name: &str = obj.getattr("name")?.extract()?;
+        let mut glyph = norad::Glyph::new_named(name);
+        glyph.width = obj.getattr("width")?.extract()?;
+        glyph.height = obj.getattr("height")?.extract()?;
+        glyph.codepoints = obj
+            .getattr("unicodes")?
+            .extract::<Vec<u32>>()?
+            .into_iter()
+            .map(|u| {
+                char::from_u32(u)
+                    .ok_or_else(|| PyValueError::new_err(format!("invalid codepoint {:#x}", u)))
+            })
+            .collect::<PyResult<_>>()?;
+        glyph.note = obj.getattr("note")?.extract()?;
+        glyph.lib = get_wrapped(obj, "lib")?;
+        glyph.anchors = get_wrapped(obj, "anchors")?;
+        glyph.contours = get_wrapped(obj, "contours")?;
+        glyph.components = get_wrapped(obj, "components")?;
+        glyph.guidelines = get_wrapped(obj, "guidelines")?;
+        Ok(glyph)
+    }
+}
+
+impl FromWrappedPyObject for norad::Layer {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let name: &str = obj.getattr("name")?.extract()?;
+        let mut layer = norad::Layer::new(name.into(), None);
+        for glyph in obj.iter()? {
+            layer.insert_glyph(norad::Glyph::from_wrapped_object(glyph?)?);
+        }
+        layer.color = get_wrapped(obj, "color")?;
+        layer.lib = get_wrapped(obj, "lib")?;
+        Ok(layer)
+    }
+}
+
+/// Which layers' glyphs the `load` functions order by `public.glyphOrder`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GlyphOrder {
+    Unordered,
+    DefaultLayer,
+    AllLayers,
+}
+
+impl GlyphOrder {
+    fn from_arg(arg: Option<&str>) -> PyResult<Self> {
+        match arg {
+            None => Ok(GlyphOrder::Unordered),
+            Some("default") => Ok(GlyphOrder::DefaultLayer),
+            Some("all") => Ok(GlyphOrder::AllLayers),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "glyph_order must be None, 'default' or 'all', not '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+fn wrap_layerset(
+    layers: &norad::LayerSet,
+    glyph_order: &[String],
+    order: GlyphOrder,
+    loader: &PyModule,
+    py: Python,
+) -> PyObject {
+    let default_name = layers.default_layer().name();
+    let wrapped_layers: Vec<PyObject> = layers
+        .iter()
+        .map(|l| {
+            let ordered = match order {
+                GlyphOrder::Unordered => false,
+                GlyphOrder::DefaultLayer => l.name() == default_name,
+                GlyphOrder::AllLayers => true,
+            };
+            wrap_layer(l, Some(glyph_order).filter(|_| ordered), loader, py)
+        })
+        .collect();
+
+    let cls = loader.getattr("LayerSet").unwrap();
+    cls.call_method(
+        "from_iterable",
+        (wrapped_layers, layers.default_layer().name().as_ref()),
+        None,
+    )
+    .unwrap()
+    .into()
+}
+
+fn wrap_kerning(kerning: Option<&norad::Kerning>, py: Python) -> PyObject {
+    match kerning {
+        Some(kerning) => {
+            let d = PyDict::new(py);
+            for (left, v) in kerning.iter() {
+                for (right, kern) in v.iter() {
+                    d.set_item((left, right).to_object(py), kern.to_object(py))
+                        .unwrap();
+                }
+            }
+            d.into()
+        }
+        None => PyDict::new(py).into(),
+    }
+}
+
+impl ToWrappedPyObject for norad::Font {
+    fn to_wrapped_object(&self, loader: &PyModule, py: Python) -> PyObject {
+        wrap_font(self, GlyphOrder::Unordered, loader, py)
+    }
+}
+
+/// Wraps `font`, with the glyphs of the layers selected by `order` ordered by
+/// the font's `public.glyphOrder`.
+fn wrap_font(font: &norad::Font, order: GlyphOrder, loader: &PyModule, py: Python) -> PyObject {
+    let cls = loader.getattr("Font").unwrap();
+    let glyph_order = match order {
+        GlyphOrder::Unordered => Vec::new(),
+        _ => crate::public_lib::FontLib::new(&font.lib)
+            .0
+            .glyph_order
+            .unwrap_or_default(),
+    };
+
+    let kwargs = [
+        ("lib", font.lib.to_object(py)),
+        (
+            "layers",
+            wrap_layerset(&font.layers, &glyph_order, order, loader, py),
+        ),
+        ("info", font.font_info.to_wrapped_object(loader, py)),
+        (
+            "features",
+            font.features
+                .as_ref()
+                .map_or("", |v| v.as_str())
+                .to_object(py),
+        ),
+        (
+            "groups",
+            font.groups
+                .as_ref()
+                .map_or(PyDict::new(py).to_object(py), |v| v.to_object(py)),
+        ),
+        ("kerning", wrap_kerning(font.kerning.as_ref(), py)),
+    ]
+    .into_py_dict(py);
+    cls.call((), Some(kwargs)).unwrap().into()
+}
+
+impl FromWrappedPyObject for norad::Font {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let layers = obj.getattr("layers")?;
+        let default_layer = layers.getattr("defaultLayer")?;
+        let default_name: &str = default_layer.getattr("name")?.extract()?;
+        let mut layerset = vec![norad::Layer::from_wrapped_object(default_layer)?];
+        for layer in layers.iter()? {
+            let layer = layer?;
+            if layer.getattr("name")?.extract::<&str>()? != default_name {
+                layerset.push(norad::Layer::from_wrapped_object(layer)?);
+            }
+        }
+
+        let mut kerning = norad::Kerning::new();
+        for (pair, value) in obj.getattr("kerning")?.downcast::<PyDict>()?.iter() {
+            let (first, second): (String, String) = pair.extract()?;
+            kerning
+                .entry(first)
+                .or_default()
+                .insert(second, value.extract()?);
+        }
+        let groups: HashMap<String, Vec<String>> = obj.getattr("groups")?.extract()?;
+        let features = obj.getattr("features")?;
+        let features: String = match features.extract() {
+            Ok(text) => text,
+            Err(_) => features.getattr("text")?.extract()?,
+        };
+
+        let mut font = norad::Font::new();
+        font.layers = norad::LayerSet::new(layerset);
+        font.font_info = Some(get_wrapped(obj, "info")?);
+        font.lib = get_wrapped(obj, "lib")?;
+        font.groups = Some(
+            groups
+                .into_iter()
+                .map(|(name, members)| (name, members.into_iter().map(|m| m.into()).collect()))
+                .collect(),
+        );
+        font.kerning = Some(kerning);
+        font.features = Some(features);
+        Ok(font)
+    }
+}
+
+impl MyToPyObject for validate::KerningIssue {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = [
+            ("kind", self.kind().to_object(py)),
+            ("message", self.to_string().to_object(py)),
+        ]
+        .into_py_dict(py);
+        let fields = match self {
+            validate::KerningIssue::MultipleGroups {
+                glyph,
+                side,
+                groups,
+            } => vec![
+                ("glyph", glyph.to_object(py)),
+                ("side", side.as_str().to_object(py)),
+                ("groups", groups.to_object(py)),
+            ],
+            validate::KerningIssue::MissingGroupMember { group, glyph } => vec![
+                ("group", group.to_object(py)),
+                ("glyph", glyph.to_object(py)),
+            ],
+            validate::KerningIssue::MissingGlyph {
+                first,
+                second,
+                glyph,
+            } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+                ("glyph", glyph.to_object(py)),
+            ],
+            validate::KerningIssue::MissingGroup {
+                first,
+                second,
+                group,
+            } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+                ("group", group.to_object(py)),
+            ],
+            validate::KerningIssue::ZeroValue { first, second } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+            ],
+            validate::KerningIssue::RedundantException {
+                first,
+                second,
+                value,
+                class_first,
+                class_second,
+            } => vec![
+                ("first", first.to_object(py)),
+                ("second", second.to_object(py)),
+                ("value", value.to_object(py)),
+                ("class_first", class_first.to_object(py)),
+                ("class_second", class_second.to_object(py)),
+            ],
+        };
+        for (key, value) in fields {
+            d.set_item(key, value).unwrap();
+        }
+        d.into()
+    }
+}
+
+create_exception!(readwrite_ufo_glif, IondriveError, PyException);
+
+impl From<crate::Error> for PyErr {
+    fn from(error: crate::Error) -> Self {
+        IondriveError::new_err(error.to_string())
+    }
+}
+
+/// Load and return a UFO from `path`, a UFO directory or `.ufoz` archive, using
+/// the objects from `font_objects_module`.
+///
+/// Malformed values of well-known `public.*` lib keys, e.g. an OpenType
+/// category that isn't one of the specified ones, are reported as
+/// `UserWarning`s. The same holds for the other `load` functions.
+///
+/// By default, the glyphs of each layer are in no particular order. With
+/// `glyph_order="default"`, the glyphs of the default layer are ordered by
+/// the font's `public.glyphOrder`, and with `glyph_order="all"` those of all
+/// layers: first the listed glyphs, then the unlisted ones sorted by name, as
+/// returned by `glyph_order()`. The other `load` functions take the same
+/// option.
+///
+/// The font objects module is the Python namespace containing the classes as
+/// exported by ufoLib2, typically this will be the module `ufoLib2.objects`.
+#[pyfunction(glyph_order = "None")]
+#[pyo3(text_signature = "(font_objects_module, path, /, glyph_order=None)")]
+fn load(
+    loader: &PyModule,
+    path: PathBuf,
+    glyph_order: Option<String>,
+    py: Python,
+) -> PyResult<PyObject> {
+    let order = GlyphOrder::from_arg(glyph_order.as_deref())?;
+    let ufo = crate::load(&path)?;
+    public_lib::warn_lib(py, &ufo)?;
+    Ok(wrap_font(&ufo, order, loader, py))
+}
+
+/// Load and return a UFO from the contents of its files, using the objects
+/// from `font_objects_module`.
+///
+/// `files` maps paths relative to the UFO, separated by "/" (e.g.
+/// "glyphs/a.glif"), to the file contents as bytes. It can be a dict or any
+/// other mapping. The files are parsed in memory, nothing is written to disk,
+/// and loaded as they would be from a UFO directory. UFO 1 is not supported.
+#[pyfunction(glyph_order = "None")]
+#[pyo3(text_signature = "(font_objects_module, files, /, glyph_order=None)")]
+fn load_from_files(
+    loader: &PyModule,
+    files: &PyAny,
+    glyph_order: Option<String>,
+    py: Python,
+) -> PyResult<PyObject> {
+    let order = GlyphOrder::from_arg(glyph_order.as_deref())?;
+    let mut contents = BTreeMap::new();
+    for item in files.call_method0("items")?.iter()? {
+        let (path, data): (String, &[u8]) = item?.extract()?;
+        contents.insert(path, data.to_vec());
+    }
+    let ufo = crate::load_from_files(&contents)?;
+    public_lib::warn_lib(py, &ufo)?;
+    Ok(wrap_font(&ufo, order, loader, py))
+}
+
+/// Load and return the UFO at `ufo_path` as it is in `revision` of the git
+/// repository at `repo_path`, using the objects from `font_objects_module`.
+///
+/// `ufo_path` is relative to the root of the repository and `revision` is
+/// anything git understands, e.g. a commit hash, branch or tag. The files are
+/// read from the object database and parsed in memory, as by
+/// `load_from_files`; nothing is checked out or written to disk.
+#[pyfunction(glyph_order = "None")]
+#[pyo3(
+    text_signature = "(font_objects_module, repo_path, revision, ufo_path, /, glyph_order=None)"
+)]
+fn load_from_git(
+    loader: &PyModule,
+    repo_path: PathBuf,
+    revision: &str,
+    ufo_path: &str,
+    glyph_order: Option<String>,
+    py: Python,
+) -> PyResult<PyObject> {
+    let order = GlyphOrder::from_arg(glyph_order.as_deref())?;
+    let ufo = crate::load_from_git(repo_path, revision, ufo_path)?;
+    public_lib::warn_lib(py, &ufo)?;
+    Ok(wrap_font(&ufo, order, loader, py))
+}
+
+/// Decompose the components of the glyphs in a layer of a font, given as a
+/// path to a UFO or a `RustFont`.
+///
+/// Returns a dict mapping glyph names to lists of `Contour` objects, made from
+/// the glyph's own contours followed by the flattened contours of all its
+/// (nested) components. Only glyphs with components are included. If
+/// `only_flipped_or_mixed` is true, only glyphs with a flipped component or
+/// with both contours and components are decomposed. Uses the default layer
+/// unless `layer` is given.
+#[pyfunction(layer = "None", only_flipped_or_mixed = "false")]
+#[pyo3(text_signature = "(font_objects_module, font, /, layer=None, only_flipped_or_mixed=False)")]
+fn decompose(
+    loader: &PyModule,
+    font: &PyAny,
+    layer: Option<String>,
+    only_flipped_or_mixed: bool,
+    py: Python,
+) -> PyResult<PyObject> {
+    let font = load_font_arg(font)?;
+    let layer = crate::layer_or_default(&font, layer.as_deref())?;
+    let filter = if only_flipped_or_mixed {
+        decompose::DecomposeFilter::FlippedOrMixed
+    } else {
+        decompose::DecomposeFilter::All
+    };
+    let decomposed = decompose::decompose_layer(layer, filter).map_err(crate::Error::Decompose)?;
+    let d = PyDict::new(py);
+    for (name, contours) in decomposed.iter() {
+        d.set_item(name.as_ref(), contours.to_wrapped_object(loader, py))?;
+    }
+    Ok(d.into())
+}
+
+/// Compute the bounds of the glyphs in a layer of a font, given as a path to a
+/// UFO or a `RustFont`.
+///
+/// Returns a dict mapping glyph names to `(xMin, yMin, xMax, yMax)` tuples,
+/// or None for glyphs without an outline. Components are included. Uses the
+/// default layer unless `layer` is given.
+#[pyfunction(layer = "None")]
+#[pyo3(text_signature = "(font, /, layer=None)")]
+fn bounds(font: &PyAny, layer: Option<String>, py: Python) -> PyResult<PyObject> {
+    let font = load_font_arg(font)?;
+    let layer = crate::layer_or_default(&font, layer.as_deref())?;
+    let d = PyDict::new(py);
+    for (name, rect) in bounds::layer_bounds(layer)? {
+        let rect = rect.map(|r| (r.x0, r.y0, r.x1, r.y1));
+        d.set_item(name.as_ref(), rect)?;
+    }
+    Ok(d.into())
+}
+
+/// Check the kerning and kerning groups of a font, given as a path to a UFO or
+/// a `RustFont`, for consistency.
+///
+/// Returns a list of dicts, one per issue, each with a `kind` and a `message`
+/// plus the fields specific to the kind. Kinds are `multiple_groups`,
+/// `missing_group_member`, `missing_glyph`, `missing_group`, `zero_value` and
+/// `redundant_exception`.
+#[pyfunction]
+#[pyo3(text_signature = "(font, /)")]
+fn check_kerning(font: &PyAny, py: Python) -> PyResult<PyObject> {
+    // A path is checked with its groups read unvalidated, so that overlapping
+    // groups are reported rather than failing the load.
+    let issues = match font.extract::<PyRef<font::RustFont>>() {
+        Ok(font) => validate::check_kerning(
+            font.font.kerning.as_ref().unwrap_or(&Default::default()),
+            font.font.groups.as_ref().unwrap_or(&Default::default()),
+            font.font.default_layer(),
+        ),
+        Err(_) => validate::check_ufo_kerning(font.extract::<PathBuf>()?)?,
+    };
+    Ok(issues.to_object(py))
+}
+
+/// Map codepoints to glyph names, as in a font's `cmap` table.
+///
+/// `font` is a path to a UFO, a `RustFont`, or a ufoLib2 `Font` or `Layer`.
+/// Uses the default layer unless `layer` is given. Returns a dict with the
+/// `mapping` from codepoints to glyph names and a list of `issues`, dicts with
+/// a `kind` and a `message`: `duplicate` for codepoints of several glyphs,
+/// `invalid` for surrogates and values above 0x10FFFF, and `name_mismatch`
+/// for glyphs whose name, like `uni0041` or `Aacute`, implies a codepoint
+/// they don't have.
+#[pyfunction(layer = "None")]
+#[pyo3(text_signature = "(font, /, layer=None)")]
+fn cmap(font: &PyAny, layer: Option<String>, py: Python) -> PyResult<PyObject> {
+    let layer = layer.as_deref();
+    let cmap = if let Ok(font) = font.extract::<PyRef<font::RustFont>>() {
+        crate::cmap::layer_cmap(crate::layer_or_default(&font.font, layer)?)
+    } else if let Ok(path) = font.extract::<PathBuf>() {
+        crate::cmap::ufo_cmap(path, layer)?
+    } else {
+        let glyphs = match layer {
+            Some(layer) => font.getattr("layers")?.get_item(layer)?,
+            None => font,
+        };
+        let glyphs = glyphs
+            .iter()?
+            .map(|glyph| {
+                let glyph = glyph?;
+                Ok((
+                    glyph.getattr("name")?.extract()?,
+                    glyph.getattr("unicodes")?.extract()?,
+                ))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        crate::cmap::build_cmap(glyphs)
+    };
+    Ok(cmap.to_object(py))
+}
+
+/// A font argument: borrowed from a `RustFont`, or loaded from a path.
+enum FontArg<'p> {
+    Borrowed(PyRef<'p, font::RustFont>),
+    Loaded(Box<norad::Font>),
+}
+
+impl std::ops::Deref for FontArg<'_> {
+    type Target = norad::Font;
+
+    fn deref(&self) -> &norad::Font {
+        match self {
+            FontArg::Borrowed(font) => &font.font,
+            FontArg::Loaded(font) => font,
+        }
+    }
+}
+
+/// Loads the font `obj` refers to: a `RustFont`, which is borrowed rather
+/// than copied, or a path to a UFO.
+fn load_font_arg(obj: &PyAny) -> PyResult<FontArg<'_>> {
+    match obj.extract::<PyRef<font::RustFont>>() {
+        Ok(font) => Ok(FontArg::Borrowed(font)),
+        Err(_) => Ok(FontArg::Loaded(Box::new(crate::load(
+            obj.extract::<PathBuf>()?,
+        )?))),
+    }
+}
+
+/// Returns `include_dir`, or if it is `None`, the directory containing the
+/// UFO the font argument `obj` was loaded from, where feature includes are
+/// resolved.
+fn include_dir_arg(obj: &PyAny, include_dir: Option<PathBuf>) -> PyResult<PathBuf> {
+    if let Some(include_dir) = include_dir {
+        return Ok(include_dir);
+    }
+    Ok(font_path_arg(obj)?
+        .map(|p| crate::features::include_dir(&p).to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(".")))
+}
+
+/// Returns the path of the UFO the font argument `obj` was loaded from, if
+/// any.
+fn font_path_arg(obj: &PyAny) -> PyResult<Option<PathBuf>> {
+    match obj.extract::<PyRef<font::RustFont>>() {
+        Ok(font) => Ok(font.path.clone()),
+        Err(_) => Ok(Some(obj.extract::<PathBuf>()?)),
+    }
+}
+
+/// Compare two fonts, each given as a path to a UFO or a `RustFont`.
+///
+/// Returns a dict with the added, removed and modified `layers` and their
+/// glyphs, each modified glyph with a list of `changes` (dicts with a `kind`,
+/// a `message` and the old and new values), and the changed `info`, `lib`,
+/// `kerning`, `groups` and `features`. `summary` is a human-readable version
+/// of the whole diff.
+#[pyfunction]
+#[pyo3(text_signature = "(old, new, /)")]
+fn diff(old: &PyAny, new: &PyAny, py: Python) -> PyResult<PyObject> {
+    let (old, new) = (load_font_arg(old)?, load_font_arg(new)?);
+    Ok(crate::diff::diff_fonts(&old, &new).to_object(py))
+}
+
+/// Check that the glyphs of several masters are compatible for
+/// interpolation. The masters are given as a list of paths to UFOs or
+/// `RustFont`s.
+///
+/// Every glyph of the default layer is compared with the same glyph of the
+/// first master that has it. Returns a list of dicts, one per problem, with
+/// the `glyph`, the index of the `master`, a `kind`, a `message` and the
+/// `contour`, `point` or `component` index and the `expected` and `found`
+/// values where they apply. Kinds are `missing_glyph`, `contour_count`,
+/// `point_count`, `point_type`, `component_count`, `component_base`,
+/// `anchors` and `start_point`.
+#[pyfunction]
+#[pyo3(text_signature = "(masters, /)")]
+fn check_compatibility(masters: Vec<&PyAny>, py: Python) -> PyResult<PyObject> {
+    let fonts = masters
+        .into_iter()
+        .map(load_font_arg)
+        .collect::<PyResult<Vec<_>>>()?;
+    let fonts: Vec<_> = fonts.iter().map(|font| &**font).collect();
+    Ok(crate::compatibility::check_fonts(&fonts).to_object(py))
+}
+
+/// Compute content hashes of a font, given as a path to a UFO or a `RustFont`.
+///
+/// Returns a dict with `layers`, mapping each layer name to a dict of glyph
+/// names and hex digests, and `font`, the digest of the whole font. The
+/// hashes ignore formatting, so they only change when the data does. If
+/// `with_components` is true, the hash of a composite glyph also covers its
+/// component base glyphs, recursively.
+#[pyfunction(with_components = "false")]
+#[pyo3(text_signature = "(font, /, with_components=False)")]
+fn hashes(font: &PyAny, with_components: bool, py: Python) -> PyResult<PyObject> {
+    let font = load_font_arg(font)?;
+    Ok(crate::hash::font_hashes(&font, with_components).to_object(py))
+}
+
+/// Generate mark positioning from the anchors of a font's default layer.
+///
+/// `font` is a path to a UFO or a `RustFont`. Glyphs with an anchor like
+/// `_top` are marks that attach to the `top` anchor of bases and other marks,
+/// and glyphs with anchors `top_1`, `top_2`, ... are ligatures. The font's
+/// `public.openTypeCategories` overrides this for the glyphs it lists.
+///
+/// Returns a dict with the structured attachments by anchor name:
+/// `mark_classes`, `bases` and `marks` map glyph names to `(x, y)` anchor
+/// positions, `ligatures` map glyph names to a list with a position or `None`
+/// per component. `fea` is the feature file code with the mark classes and
+/// the `mark` and `mkmk` features.
+#[pyfunction]
+#[pyo3(text_signature = "(font, /)")]
+fn mark_features(font: &PyAny, py: Python) -> PyResult<PyObject> {
+    let font = load_font_arg(font)?;
+    Ok(crate::mark_feature::mark_features(&font).to_object(py))
+}
+
+/// Generate the `kern` feature from a font's kerning and kerning groups.
+///
+/// `font` is a path to a UFO or a `RustFont`. Returns the feature file code
+/// with a glyph class per kerning group and the pairs ordered so that
+/// glyph-glyph pairs win over glyph-group and group-glyph exceptions (written
+/// as `enum` pairs), which win over group-group pairs. If `split_direction`
+/// is true, pairs whose groups have glyphs of right-to-left scripts, as found
+/// from the codepoints, go into a separate lookup along with their
+/// exceptions, and each lookup is registered only for the scripts of its
+/// direction and those of the features' `languagesystem` statements. Pairs
+/// and group members referring to glyphs or groups missing from the font are
+/// left out with a `UserWarning`.
+#[pyfunction(split_direction = "false")]
+#[pyo3(text_signature = "(font, /, split_direction=False)")]
+fn kern_feature(font: &PyAny, split_direction: bool, py: Python) -> PyResult<String> {
+    let font = load_font_arg(font)?;
+    let feature = crate::kern_feature::kern_feature(&font, split_direction);
+    let category = py.import("builtins")?.getattr("UserWarning")?;
+    for warning in &feature.warnings {
+        PyErr::warn(py, category, &warning.to_string(), 1)?;
+    }
+    Ok(feature.fea)
+}
+
+/// Resolve the includes of a font's features and check them.
+///
+/// `font` is a path to a UFO or a `RustFont`. `include()` paths are resolved
+/// relative to `include_dir`, by default the directory containing the UFO,
+/// as ufo2ft does. Returns a dict with the feature `text`, the included files
+/// inlined, and a list of `issues`, dicts with a `kind`, a `message`, the
+/// `file` and `line`: `include` for files that can't be read, `structure`
+/// for the first structural error like a missing `;` or an unclosed block,
+/// `unknown_keyword` for statements and blocks starting with a word that
+/// isn't a keyword, `missing_glyph` for glyph names that aren't in the
+/// default layer and `undefined_class` for classes used before they are
+/// defined. The feature code isn't fully parsed, so code without issues can
+/// still fail to compile.
+#[pyfunction(include_dir = "None")]
+#[pyo3(text_signature = "(font, /, include_dir=None)")]
+fn check_features(font: &PyAny, include_dir: Option<PathBuf>, py: Python) -> PyResult<PyObject> {
+    let include_dir = include_dir_arg(font, include_dir)?;
+    let font = load_font_arg(font)?;
+    let checked = crate::features::check_features(
+        font.features.as_deref().unwrap_or_default(),
+        font.default_layer(),
+        &include_dir,
+    );
+    Ok(checked.to_object(py))
+}
+
+/// Return the glyph names of a layer in the font's declared order.
+///
+/// `font` is a path to a UFO or a `RustFont`. Uses the default layer unless
+/// `layer` is given. The glyphs listed in the font's `public.glyphOrder` come
+/// first, in that order and without duplicates or glyphs missing from the
+/// layer, followed by the unlisted glyphs sorted by name. This is the order
+/// `load` uses with its `glyph_order` option.
+#[pyfunction(layer = "None")]
+#[pyo3(text_signature = "(font, /, layer=None)")]
+fn glyph_order(font: &PyAny, layer: Option<String>) -> PyResult<Vec<String>> {
+    let font = load_font_arg(font)?;
+    Ok(crate::public_lib::resolved_glyph_order(
+        &font,
+        layer.as_deref(),
+    )?)
+}
+
+/// Subset a font to some of its glyphs.
+///
+/// `font` is a path to a UFO or a `RustFont`. Keeps the glyphs named in
+/// `glyphs` and the glyphs of the default layer with one of the integer
+/// `codepoints`, plus `.notdef` and the component base glyphs they use,
+/// recursively, and removes all others from every layer. Kerning pairs,
+/// group members and the entries of `public.glyphOrder`,
+/// `public.skipExportGlyphs`, `public.postscriptNames` and other public lib
+/// keys that refer to removed glyphs are dropped, as are groups left empty.
+/// If `output` is given, the subset is saved there with the `data`
+/// directory and the images its glyphs use from the UFO `font` was loaded
+/// from; glyph images that UFO lacks are dropped.
+///
+/// Returns a dict with the subset `font` as a `RustFont`, the sorted names
+/// of the `removed` glyphs, the requested glyph names `missing` from the
+/// default layer and the `features` issues, as from `check_features`, for
+/// references to removed glyphs. The features are kept as they are, with
+/// includes resolved relative to `include_dir` for the check.
+#[pyfunction(
+    glyphs = "None",
+    codepoints = "None",
+    output = "None",
+    include_dir = "None"
+)]
+#[pyo3(text_signature = "(font, /, glyphs=None, codepoints=None, output=None, include_dir=None)")]
+fn subset(
+    font: &PyAny,
+    glyphs: Option<Vec<String>>,
+    codepoints: Option<Vec<u32>>,
+    output: Option<PathBuf>,
+    include_dir: Option<PathBuf>,
+    py: Python,
+) -> PyResult<PyObject> {
+    let include_dir = include_dir_arg(font, include_dir)?;
+    let source = font_path_arg(font)?;
+    let font = load_font_arg(font)?;
+    let mut subset = crate::subset::subset_font(
+        &font,
+        &glyphs.unwrap_or_default(),
+        &codepoints.unwrap_or_default(),
+        &include_dir,
+    );
+    if let Some(output) = &output {
+        let sources: Vec<&Path> = source.iter().map(PathBuf::as_path).collect();
+        crate::save::save_with_sources(&mut subset.font, output, &sources)?;
+    }
+    let font = font::RustFont {
+        font: subset.font,
+        path: output,
+    };
+    Ok([
+        ("font", Py::new(py, font)?.to_object(py)),
+        (
+            "removed",
+            subset.removed.into_iter().collect::<Vec<_>>().to_object(py),
+        ),
+        ("missing", subset.missing.to_object(py)),
+        ("features", MyToPyObject::to_object(&subset.features, py)),
+    ]
+    .into_py_dict(py)
+    .into())
+}
+
+/// Combine two fonts, such as a Latin font and a Greek extension.
+///
+/// `ours` and `theirs` are paths to UFOs or `RustFont`s. The glyphs, layers,
+/// kerning, groups and lib of theirs are added to ours, their glyphs and
+/// kerning scaled if the `unitsPerEm` differ. What both have but define
+/// differently is resolved by `policy`: `"keep"` keeps ours, `"replace"`
+/// takes theirs and `"rename"` adds their glyphs and groups under a new name
+/// ending in `suffix`, updating the references to them, and keeps ours for
+/// the rest. A conflicting glyph's kerning, groups and lib entries go with
+/// it. Their kerning groups lose the glyphs that a kerning group of ours on
+/// the same side has, a `group` conflict with the `glyphs`. If `output` is
+/// given, the result is saved there with the files of the `data` directories
+/// of the UFOs the fonts were loaded from and the images its glyphs use from
+/// their `images` directories, ours' where both have a file; glyph images
+/// neither has are dropped.
+///
+/// Returns a dict with the combined `font` as a `RustFont` and a list of the
+/// `conflicts`, dicts with a `kind`, a `message`, the `resolution`
+/// (`kept_ours`, `took_theirs` or `renamed`, with the new name under
+/// `renamed`) and the fields specific to the kind. Kinds are `glyph`,
+/// `codepoint`, `layer`, `kerning`, `group`, `lib` and `features`.
+#[pyfunction(policy = "\"keep\"", suffix = "None", output = "None")]
+#[pyo3(text_signature = "(ours, theirs, /, policy=\"keep\", suffix=None, output=None)")]
+fn combine(
+    ours: &PyAny,
+    theirs: &PyAny,
+    policy: &str,
+    suffix: Option<String>,
+    output: Option<PathBuf>,
+    py: Python,
+) -> PyResult<PyObject> {
+    let policy = match (policy, suffix) {
+        ("keep", _) => crate::combine::ConflictPolicy::Keep,
+        ("replace", _) => crate::combine::ConflictPolicy::Replace,
+        ("rename", Some(suffix)) if !suffix.is_empty() => {
+            crate::combine::ConflictPolicy::Rename(suffix)
+        }
+        ("rename", _) => return Err(PyValueError::new_err("policy 'rename' needs a suffix")),
+        (policy, _) => {
+            return Err(PyValueError::new_err(format!(
+                "policy must be 'keep', 'replace' or 'rename', not '{}'",
+                policy
+            )))
+        }
+    };
+    let sources = [font_path_arg(ours)?, font_path_arg(theirs)?];
+    let (ours, theirs) = (load_font_arg(ours)?, load_font_arg(theirs)?);
+    let mut combined = crate::combine::combine_fonts(&ours, &theirs, &policy);
+    if let Some(output) = &output {
+        let sources: Vec<&Path> = sources.iter().flatten().map(PathBuf::as_path).collect();
+        crate::save::save_with_sources(&mut combined.font, output, &sources)?;
+    }
+    let font = font::RustFont {
+        font: combined.font,
+        path: output,
+    };
+    Ok([
+        ("font", Py::new(py, font)?.to_object(py)),
+        (
+            "conflicts",
+            MyToPyObject::to_object(&combined.conflicts, py),
+        ),
+    ]
+    .into_py_dict(py)
+    .into())
+}
+
+/// Merge the UFOs `ours` and `theirs`, two edited versions of `base`, and
+/// save the result to `out`.
+///
+/// Changes made on one side are taken over. Where both sides changed the
+/// same thing differently, ours is kept and a conflict is reported. Returns
+/// a list of dicts, one per conflict, each with a `kind` and a `message` plus
+/// the fields specific to the kind. Kinds are `glyph`, `layer`, `lib`,
+/// `info`, `kerning`, `group` and `features`.
+#[pyfunction]
+#[pyo3(text_signature = "(base, ours, theirs, out, /)")]
+fn merge3(
+    base: PathBuf,
+    ours: PathBuf,
+    theirs: PathBuf,
+    out: PathBuf,
+    py: Python,
+) -> PyResult<PyObject> {
+    let conflicts = crate::merge::merge_ufos(base, ours, theirs, out)?;
+    Ok(conflicts.to_object(py))
+}
+
+/// Looks up kerning values between glyphs, resolving kerning groups.
+///
+/// Construct it from a font's `kerning` and `groups`, or load both from a UFO
+/// with `KerningLookup.from_ufo(path)`.
+#[pyclass(name = "KerningLookup")]
+#[pyo3(text_signature = "(kerning, groups, /)")]
+struct PyKerningLookup {
+    inner: kerning::KerningLookup,
+}
+
+#[pymethods]
+impl PyKerningLookup {
+    #[new]
+    fn new(kerning: HashMap<(String, String), f32>, groups: HashMap<String, Vec<String>>) -> Self {
+        let mut pairs = norad::Kerning::new();
+        for ((first, second), value) in kerning {
+            pairs.entry(first).or_default().insert(second, value);
+        }
+        let groups: norad::Groups = groups
+            .into_iter()
+            .map(|(name, members)| (name, members.into_iter().map(|m| m.into()).collect()))
+            .collect();
+        PyKerningLookup {
+            inner: kerning::KerningLookup::new(&pairs, &groups),
+        }
+    }
+
+    /// Load the kerning and groups of the UFO at `path`.
+    #[staticmethod]
+    #[pyo3(text_signature = "(path, /)")]
+    fn from_ufo(path: PathBuf) -> PyResult<Self> {
+        let ufo = crate::LoadOptions::none()
+            .groups(true)
+            .kerning(true)
+            .load(&path)?;
+        Ok(PyKerningLookup {
+            inner: kerning::KerningLookup::from_font(&ufo),
+        })
+    }
+
+    /// Return the kerning value between `first` and `second`, or None.
+    ///
+    /// Glyph-glyph pairs take precedence over glyph-group pairs, then
+    /// group-glyph pairs, then group-group pairs.
+    #[pyo3(text_signature = "($self, first, second, /)")]
+    fn get(&self, first: &str, second: &str) -> Option<f32> {
+        self.inner.get(first, second)
+    }
+
+    /// Return a dict of `(first, second) -> value` with all group kerning
+    /// resolved into glyph pairs.
+    #[pyo3(text_signature = "($self, /)")]
+    fn flatten(&self, py: Python) -> PyObject {
+        let d = PyDict::new(py);
+        for ((first, second), value) in self.inner.flatten() {
+            d.set_item((first, second), value).unwrap();
+        }
+        d.into()
+    }
+}
+
+/// The component references between the glyphs of a layer.
+///
+/// Construct it from a dict mapping glyph names to the base glyphs of their
+/// components, load it with `ComponentGraph.from_ufo(path, layer=None)` or get
+/// it from `RustFont.component_graph(layer=None)`.
+#[pyclass(name = "ComponentGraph")]
+#[pyo3(text_signature = "(components, /)")]
+struct PyComponentGraph {
+    inner: components::ComponentGraph,
+}
+
+#[pymethods]
+impl PyComponentGraph {
+    #[new]
+    fn new(components: BTreeMap<String, Vec<String>>) -> Self {
+        PyComponentGraph {
+            inner: components::ComponentGraph::from_components(components),
+        }
+    }
+
+    /// Load the graph of a layer of the UFO at `path`, the default layer
+    /// unless `layer` is given.
+    #[staticmethod]
+    #[args(layer = "None")]
+    #[pyo3(text_signature = "(path, /, layer=None)")]
+    fn from_ufo(path: PathBuf, layer: Option<String>) -> PyResult<Self> {
+        let ufo = crate::LoadOptions::none().layers(true).load(&path)?;
+        let layer = crate::layer_or_default(&ufo, layer.as_deref())?;
+        Ok(PyComponentGraph {
+            inner: components::ComponentGraph::new(layer),
+        })
+    }
+
+    /// Return the base glyphs of `glyph`'s components in order, or with
+    /// `recursive=True` all glyphs it uses through nested components.
+    #[args(recursive = "false")]
+    #[pyo3(text_signature = "($self, glyph, /, recursive=False)")]
+    fn bases(&self, glyph: &str, recursive: bool) -> Vec<String> {
+        if recursive {
+            self.inner
+                .all_bases(glyph)
+                .into_iter()
+                .map(String::from)
+                .collect()
+        } else {
+            self.inner.bases(glyph).to_vec()
+        }
+    }
+
+    /// Return the glyphs using `glyph` as a component, or with
+    /// `recursive=True` also those using it through nested components, i.e.
+    /// everything to rebuild when `glyph` changes.
+    #[args(recursive = "false")]
+    #[pyo3(text_signature = "($self, glyph, /, recursive=False)")]
+    fn users(&self, glyph: &str, recursive: bool) -> Vec<String> {
+        let users = if recursive {
+            self.inner.all_users(glyph)
+        } else {
+            self.inner.users(glyph)
+        };
+        users.into_iter().map(String::from).collect()
+    }
+
+    /// Return all glyph names ordered so that base glyphs come before the
+    /// composites using them. Raises `IondriveError` on component cycles.
+    #[pyo3(text_signature = "($self, /)")]
+    fn topological_order(&self) -> PyResult<Vec<String>> {
+        let order = self.inner.topological_order().map_err(crate::Error::from)?;
+        Ok(order.into_iter().map(String::from).collect())
+    }
+
+    /// Return the component cycles as lists of glyph names whose first and
+    /// last names are the same glyph.
+    #[pyo3(text_signature = "($self, /)")]
+    fn cycles(&self) -> Vec<Vec<String>> {
+        self.inner.cycles()
+    }
+
+    /// Return `(glyph, base)` tuples for components whose base glyph is
+    /// missing.
+    #[pyo3(text_signature = "($self, /)")]
+    fn missing_bases(&self) -> Vec<(String, String)> {
+        self.inner
+            .missing_bases()
+            .into_iter()
+            .map(|(glyph, base)| (glyph.to_string(), base.to_string()))
+            .collect()
+    }
+}
+
+/// Iondrive is a glue library to load [Unified Font Object](ufo) files using norad.
+///
+/// The goal is to load data faster than can be done by Python and then pass it
+/// over to Python.
+///
+/// [ufo]: https://unifiedfontobject.org/
+#[pymodule]
+fn iondrive(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(load, m)?)?;
+    m.add_function(wrap_pyfunction!(load_from_files, m)?)?;
+    m.add_function(wrap_pyfunction!(load_from_git, m)?)?;
+    m.add_function(wrap_pyfunction!(decompose, m)?)?;
+    m.add_function(wrap_pyfunction!(bounds, m)?)?;
+
+    m.add_function(wrap_pyfunction!(check_kerning, m)?)?;
+    m.add_function(wrap_pyfunction!(cmap, m)?)?;
+    m.add_function(wrap_pyfunction!(diff, m)?)?;
+    m.add_function(wrap_pyfunction!(merge3, m)?)?;
+    m.add_function(wrap_pyfunction!(hashes, m)?)?;
+    m.add_function(wrap_pyfunction!(glyph_order, m)?)?;
+    m.add_function(wrap_pyfunction!(mark_features, m)?)?;
+    m.add_function(wrap_pyfunction!(kern_feature, m)?)?;
+    m.add_function(wrap_pyfunction!(check_features, m)?)?;
+    m.add_function(wrap_pyfunction!(subset, m)?)?;
+    m.add_function(wrap_pyfunction!(combine, m)?)?;
+    m.add_function(wrap_pyfunction!(check_compatibility, m)?)?;
+    m.add_class::<PyKerningLookup>()?;
+    m.add_class::<PyComponentGraph>()?;
+    m.add_class::<font::RustFont>()?;
+
+    m.add("IondriveError", py.get_type::<IondriveError>())?;
+
+    Ok(())
+}
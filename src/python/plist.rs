@@ -0,0 +1,141 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{FromWrappedPyObject, MyToPyObject};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{
+    IntoPyDict, PyBool, PyBytes, PyDateTime, PyDict, PyFloat, PyLong, PySequence, PyString,
+};
+use pyo3::PyNativeType;
+
+impl MyToPyObject for plist::Value {
+    fn to_object(&self, py: Python) -> PyObject {
+        match self {
+            plist::Value::String(s) => s.to_object(py),
+            plist::Value::Boolean(s) => s.to_object(py),
+            plist::Value::Data(s) => PyBytes::new(py, s).into(),
+            plist::Value::Real(s) => s.to_object(py),
+            plist::Value::Integer(s) => s.as_signed().to_object(py),
+            plist::Value::Uid(s) => s.get().to_object(py),
+            plist::Value::Array(s) => s
+                .iter()
+                .map(|v| v.to_object(py))
+                .collect::<Vec<PyObject>>()
+                .to_object(py),
+            plist::Value::Dictionary(s) => s.to_object(py),
+            // Python's datetime only has the years 1 to 9999.
+            plist::Value::Date(d) => {
+                date_to_py(d, py).unwrap_or_else(|_| d.to_xml_format().to_object(py))
+            }
+            _ => py.None(),
+        }
+    }
+}
+
+impl MyToPyObject for plist::Dictionary {
+    fn to_object(&self, py: Python) -> PyObject {
+        let d = PyDict::new(py);
+        for (k, v) in self.iter() {
+            d.set_item(k, v.to_object(py)).unwrap();
+        }
+        d.into()
+    }
+}
+
+/// Returns the Unix epoch as a naive `datetime`. Like plistlib, dates are
+/// represented as naive datetimes in UTC.
+fn epoch(py: Python<'_>) -> PyResult<&PyAny> {
+    py.import("datetime")?
+        .getattr("datetime")?
+        .call1((1970, 1, 1))
+}
+
+fn date_to_py(date: &plist::Date, py: Python) -> PyResult<PyObject> {
+    let micros = match SystemTime::from(*date).duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_micros() as i64,
+        Err(error) => -(error.duration().as_micros() as i64),
+    };
+    let delta = py
+        .import("datetime")?
+        .getattr("timedelta")?
+        .call((), Some([("microseconds", micros)].into_py_dict(py)))?;
+    Ok(epoch(py)?.call_method1("__add__", (delta,))?.into())
+}
+
+fn date_from_py(obj: &PyAny) -> PyResult<plist::Date> {
+    let py = obj.py();
+    let datetime = py.import("datetime")?;
+    let mut obj = obj;
+    if !obj.getattr("tzinfo")?.is_none() {
+        let utc = datetime.getattr("timezone")?.getattr("utc")?;
+        obj = obj.call_method1("astimezone", (utc,))?.call_method(
+            "replace",
+            (),
+            Some([("tzinfo", py.None())].into_py_dict(py)),
+        )?;
+    }
+    let micro = datetime
+        .getattr("timedelta")?
+        .call((), Some([("microseconds", 1)].into_py_dict(py)))?;
+    let micros: i64 = obj
+        .call_method1("__sub__", (epoch(py)?,))?
+        .call_method1("__floordiv__", (micro,))?
+        .extract()?;
+    let time = if micros >= 0 {
+        UNIX_EPOCH + Duration::from_micros(micros as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_micros(micros.unsigned_abs())
+    };
+    Ok(time.into())
+}
+
+impl FromWrappedPyObject for plist::Value {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        if let Ok(b) = obj.downcast::<PyBool>() {
+            Ok(plist::Value::Boolean(b.is_true()))
+        } else if let Ok(i) = obj.downcast::<PyLong>() {
+            Ok(plist::Value::Integer(i.extract::<i64>()?.into()))
+        } else if let Ok(f) = obj.downcast::<PyFloat>() {
+            Ok(plist::Value::Real(f.value()))
+        } else if let Ok(s) = obj.downcast::<PyString>() {
+            Ok(plist::Value::String(s.to_str()?.to_string()))
+        } else if let Ok(b) = obj.downcast::<PyBytes>() {
+            Ok(plist::Value::Data(b.as_bytes().to_vec()))
+        } else if obj.is_instance::<PyDateTime>()? {
+            Ok(plist::Value::Date(date_from_py(obj)?))
+        } else if obj.downcast::<PyDict>().is_ok() || obj.hasattr("__attrs_attrs__")? {
+            Ok(plist::Value::Dictionary(
+                plist::Dictionary::from_wrapped_object(obj)?,
+            ))
+        } else if let Ok(seq) = obj.downcast::<PySequence>() {
+            Ok(plist::Value::Array(Vec::from_wrapped_object(seq)?))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "cannot store {} in a property list",
+                obj.get_type().name()?
+            )))
+        }
+    }
+}
+
+/// Converts a dict, or an attrs class such as ufoLib2's `NameRecord`, whose
+/// attributes become the keys. Attributes that are None are left out.
+impl FromWrappedPyObject for plist::Dictionary {
+    fn from_wrapped_object(obj: &PyAny) -> PyResult<Self> {
+        let mut dict = plist::Dictionary::new();
+        if let Ok(d) = obj.downcast::<PyDict>() {
+            for (k, v) in d.iter() {
+                dict.insert(k.extract()?, plist::Value::from_wrapped_object(v)?);
+            }
+        } else {
+            for attribute in obj.getattr("__attrs_attrs__")?.iter()? {
+                let name: &str = attribute?.getattr("name")?.extract()?;
+                let value = obj.getattr(name)?;
+                if !value.is_none() {
+                    dict.insert(name.to_string(), plist::Value::from_wrapped_object(value)?);
+                }
+            }
+        }
+        Ok(dict)
+    }
+}
@@ -0,0 +1,55 @@
+use super::MyToPyObject;
+use crate::public_lib::{FontLib, GlyphLib};
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict};
+
+/// Issues a `UserWarning` for every malformed `public.*` value in the libs of
+/// `font`.
+pub(super) fn warn_lib(py: Python, font: &norad::Font) -> PyResult<()> {
+    let category = py.import("builtins")?.getattr("UserWarning")?;
+    for warning in crate::public_lib::check_font(font) {
+        PyErr::warn(py, category, &warning.to_string(), 2)?;
+    }
+    Ok(())
+}
+
+impl MyToPyObject for FontLib {
+    fn to_object(&self, py: Python) -> PyObject {
+        let categories = self.opentype_categories.as_ref().map(|categories| {
+            let d = PyDict::new(py);
+            for (glyph, category) in categories {
+                d.set_item(glyph, category.as_str()).unwrap();
+            }
+            d
+        });
+        [
+            ("glyph_order", self.glyph_order.to_object(py)),
+            ("postscript_names", self.postscript_names.to_object(py)),
+            ("skip_export_glyphs", self.skip_export_glyphs.to_object(py)),
+            ("opentype_categories", categories.to_object(py)),
+            (
+                "unicode_variation_sequences",
+                self.unicode_variation_sequences.to_object(py),
+            ),
+            ("object_libs", self.object_libs.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
+
+impl MyToPyObject for GlyphLib {
+    fn to_object(&self, py: Python) -> PyObject {
+        let mark_color = self
+            .mark_color
+            .as_ref()
+            .map(|c| (c.red, c.green, c.blue, c.alpha));
+        [
+            ("mark_color", mark_color.to_object(py)),
+            ("vertical_origin", self.vertical_origin.to_object(py)),
+            ("object_libs", self.object_libs.to_object(py)),
+        ]
+        .into_py_dict(py)
+        .into()
+    }
+}
@@ -0,0 +1,269 @@
+//! Renaming glyphs throughout a font.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use norad::{Font, Kerning, Plist};
+use plist::Value;
+
+use crate::public_lib::{
+    GLYPH_ORDER_KEY, OPENTYPE_CATEGORIES_KEY, POSTSCRIPT_NAMES_KEY, SKIP_EXPORT_GLYPHS_KEY,
+    UNICODE_VARIATION_SEQUENCES_KEY,
+};
+use crate::Error;
+
+/// Reasons a set of glyph renames can't be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameError {
+    /// A glyph to rename is in no layer.
+    MissingGlyph(String),
+    /// A new name is empty or contains control characters.
+    InvalidName(String),
+    /// A new name is that of a glyph that isn't renamed itself.
+    Exists { glyph: String, new_name: String },
+    /// Two glyphs would get the same name.
+    Duplicate {
+        glyphs: (String, String),
+        new_name: String,
+    },
+}
+
+impl std::fmt::Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameError::MissingGlyph(name) => write!(f, "no glyph named '{}'", name),
+            RenameError::InvalidName(name) => write!(f, "invalid glyph name '{}'", name),
+            RenameError::Exists { glyph, new_name } => write!(
+                f,
+                "can't rename '{}' to '{}': a glyph with that name exists",
+                glyph, new_name
+            ),
+            RenameError::Duplicate { glyphs, new_name } => write!(
+                f,
+                "can't rename both '{}' and '{}' to '{}'",
+                glyphs.0, glyphs.1, new_name
+            ),
+        }
+    }
+}
+
+/// Returns `name` with a new name from `rename` if it has one.
+fn renamed<'a>(name: &'a str, rename: &'a BTreeMap<String, String>) -> &'a str {
+    rename.get(name).map_or(name, |n| n.as_str())
+}
+
+/// Renames the glyphs and groups of kerning pairs.
+pub(crate) fn rename_kerning(kerning: &mut Kerning, rename: &BTreeMap<String, String>) {
+    *kerning = std::mem::take(kerning)
+        .into_iter()
+        .map(|(first, seconds)| {
+            let seconds = seconds
+                .into_iter()
+                .map(|(second, value)| (renamed(&second, rename).to_string(), value))
+                .collect();
+            (renamed(&first, rename).to_string(), seconds)
+        })
+        .collect();
+}
+
+/// Renames glyphs in the glyph lists and dictionaries of the well-known keys
+/// of a font lib.
+fn rename_lib(lib: &mut Plist, rename: &BTreeMap<String, String>) {
+    let rename_value = |value: &mut Value| {
+        if let Some(new) = value.as_string().and_then(|name| rename.get(name)) {
+            *value = Value::String(new.clone());
+        }
+    };
+    for key in [GLYPH_ORDER_KEY, SKIP_EXPORT_GLYPHS_KEY] {
+        if let Some(items) = lib.get_mut(key).and_then(Value::as_array_mut) {
+            items.iter_mut().for_each(rename_value);
+        }
+    }
+    for key in [POSTSCRIPT_NAMES_KEY, OPENTYPE_CATEGORIES_KEY] {
+        if let Some(dict) = lib.get_mut(key).and_then(Value::as_dictionary_mut) {
+            *dict = std::mem::take(dict)
+                .into_iter()
+                .map(|(name, value)| (renamed(&name, rename).to_string(), value))
+                .collect();
+        }
+    }
+    let sequences = lib
+        .get_mut(UNICODE_VARIATION_SEQUENCES_KEY)
+        .and_then(Value::as_dictionary_mut);
+    for bases in sequences.into_iter().flat_map(|s| s.values_mut()) {
+        if let Some(bases) = bases.as_dictionary_mut() {
+            bases.values_mut().for_each(rename_value);
+        }
+    }
+}
+
+/// Renames glyphs without checking the new names, which must not be taken.
+pub(crate) fn apply(font: &mut Font, rename: &BTreeMap<String, String>) {
+    if let Some(features) = font.features.as_mut() {
+        *features = crate::features::rename_glyphs(features, font.layers.default_layer(), rename);
+    }
+    let names: Vec<String> = font.layers.names().map(|n| n.to_string()).collect();
+    for name in names {
+        let layer = match font.layers.get_mut(&name) {
+            Some(layer) => layer,
+            None => continue,
+        };
+        // Remove all glyphs first, so that names can be swapped.
+        let glyphs: Vec<_> = rename
+            .iter()
+            .filter_map(|(old, new)| Some((layer.remove_glyph(old)?, new)))
+            .collect();
+        for (mut glyph, new) in glyphs {
+            Arc::make_mut(&mut glyph).name = new.as_str().into();
+            layer.insert_glyph(glyph);
+        }
+        for glyph in layer.iter_mut() {
+            for component in &mut glyph.components {
+                if let Some(new) = rename.get(&*component.base) {
+                    component.base = new.as_str().into();
+                }
+            }
+        }
+    }
+    if let Some(kerning) = font.kerning.as_mut() {
+        rename_kerning(kerning, rename);
+    }
+    for members in font.groups.iter_mut().flat_map(|g| g.values_mut()) {
+        for member in members.iter_mut() {
+            if let Some(new) = rename.get(&**member) {
+                *member = new.as_str().into();
+            }
+        }
+    }
+    rename_lib(&mut font.lib, rename);
+}
+
+/// Renames the glyphs of `font` from the keys of `rename` to its values, in
+/// every layer, along with the references to them: component base glyphs,
+/// kerning, group members, `public.glyphOrder`, `public.skipExportGlyphs`,
+/// `public.postscriptNames`, `public.openTypeCategories`,
+/// `public.unicodeVariationSequences` and the glyph names in the features,
+/// apart from included files. The glyphs get file names from their new
+/// names when saved.
+///
+/// Glyphs can swap names. Nothing is changed if a glyph is missing, a new
+/// name is invalid or would be that of another glyph.
+pub fn rename_glyphs(font: &mut Font, rename: &BTreeMap<String, String>) -> Result<(), Error> {
+    let existing: BTreeSet<&str> = font
+        .layers
+        .iter()
+        .flat_map(|layer| layer.iter().map(|g| &*g.name))
+        .collect();
+    let mut new_names: BTreeMap<&str, &str> = BTreeMap::new();
+    for (old, new) in rename {
+        if !existing.contains(old.as_str()) {
+            return Err(RenameError::MissingGlyph(old.clone()).into());
+        }
+        if new.is_empty() || new.chars().any(char::is_control) {
+            return Err(RenameError::InvalidName(new.clone()).into());
+        }
+        if new == old {
+            continue;
+        }
+        if existing.contains(new.as_str()) && renamed(new, rename) == new {
+            return Err(RenameError::Exists {
+                glyph: old.clone(),
+                new_name: new.clone(),
+            }
+            .into());
+        }
+        if let Some(other) = new_names.insert(new, old) {
+            return Err(RenameError::Duplicate {
+                glyphs: (other.to_string(), old.clone()),
+                new_name: new.clone(),
+            }
+            .into());
+        }
+    }
+    let rename: BTreeMap<String, String> = rename
+        .iter()
+        .filter(|(old, new)| old != new)
+        .map(|(old, new)| (old.clone(), new.clone()))
+        .collect();
+    apply(font, &rename);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use norad::{Component, Glyph};
+
+    use super::*;
+
+    fn font() -> Font {
+        let mut font = Font::new();
+        let mut aacute = Glyph::new_named("Aacute");
+        aacute
+            .components
+            .push(Component::new("A".into(), Default::default(), None, None));
+        let (mut a, mut b) = (Glyph::new_named("A"), Glyph::new_named("B"));
+        a.width = 100.0;
+        b.width = 200.0;
+        for glyph in [a, b, aacute] {
+            font.default_layer_mut().insert_glyph(glyph);
+        }
+        let mut kerning = Kerning::new();
+        kerning
+            .entry("A".into())
+            .or_default()
+            .insert("B".into(), -10.0);
+        font.kerning = Some(kerning);
+        font
+    }
+
+    fn rename(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn swaps_names() {
+        let mut font = font();
+
+        rename_glyphs(&mut font, &rename(&[("A", "B"), ("B", "A")])).unwrap();
+
+        let layer = font.default_layer();
+        assert_eq!(layer.get_glyph("A").unwrap().width, 200.0);
+        assert_eq!(&*layer.get_glyph("B").unwrap().name, "B");
+        assert_eq!(layer.get_glyph("B").unwrap().width, 100.0);
+        assert_eq!(&*layer.get_glyph("Aacute").unwrap().components[0].base, "B");
+        assert_eq!(font.kerning.unwrap()["B"]["A"], -10.0);
+    }
+
+    #[test]
+    fn errors_change_nothing() {
+        let mut font = font();
+        let original = font.clone();
+        let errors = [
+            (rename(&[("C", "D")]), RenameError::MissingGlyph("C".into())),
+            (
+                rename(&[("A", "B")]),
+                RenameError::Exists {
+                    glyph: "A".into(),
+                    new_name: "B".into(),
+                },
+            ),
+            (
+                rename(&[("A", "C"), ("B", "C")]),
+                RenameError::Duplicate {
+                    glyphs: ("A".into(), "B".into()),
+                    new_name: "C".into(),
+                },
+            ),
+        ];
+        for (mapping, expected) in errors {
+            match rename_glyphs(&mut font, &mapping) {
+                Err(Error::Rename(error)) => assert_eq!(error, expected),
+                other => panic!("unexpected {:?}", other),
+            }
+            assert!(font == original);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use norad::Font;
+
+use crate::files::Files;
+use crate::Error;
+
+/// Directories of a UFO that norad neither reads nor writes. They are left
+/// alone when saving over an existing UFO.
+const UNMANAGED_DIRS: [&str; 2] = ["data", "images"];
+
+/// Returns the paths of all files below `dir`, relative to it.
+pub(crate) fn files(dir: &Path) -> std::io::Result<BTreeSet<PathBuf>> {
+    fn walk(dir: &Path, prefix: &Path, out: &mut BTreeSet<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = prefix.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                walk(&entry.path(), &path, out)?;
+            } else {
+                out.insert(path);
+            }
+        }
+        Ok(())
+    }
+    let mut out = BTreeSet::new();
+    walk(dir, Path::new(""), &mut out)?;
+    Ok(out)
+}
+
+fn is_unmanaged(path: &Path) -> bool {
+    path.components()
+        .next()
+        .is_some_and(|c| UNMANAGED_DIRS.iter().any(|d| c.as_os_str() == *d))
+}
+
+/// Removes `dir` and its parents up to, but excluding, `root` while they
+/// are empty.
+fn remove_empty_dirs(root: &Path, dir: &Path) -> std::io::Result<()> {
+    let mut dir = dir;
+    while dir != root && dir.starts_with(root) {
+        if fs::read_dir(dir)?.next().is_some() {
+            break;
+        }
+        fs::remove_dir(dir)?;
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+/// Saves `font` to `path`, only touching the files whose contents change.
+///
+/// Unlike [`Font::save`], an existing UFO at `path` is updated rather than
+/// replaced: files that norad writes with the same contents are left as they
+/// are, files it no longer writes are removed and the `data` and `images`
+/// directories are kept. Returns the written and removed files, relative to
+/// `path`.
+///
+/// If `path` ends in `.ufoz`, the font is written to a zip archive instead,
+/// replacing any existing file but keeping its `data` and `images`
+/// directories, and all files of the UFO are returned.
+pub fn save(font: &Font, path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Error> {
+    let path = path.as_ref();
+    if crate::ufoz::is_ufoz(path) {
+        let resources = read_resources(&[path])?;
+        return crate::ufoz::save(font, path, &resources);
+    }
+    let staging = path.with_extension("ufo.iondrive-tmp");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let result = font
+        .save(&staging)
+        .map_err(Error::from)
+        .and_then(|_| update(&staging, path));
+    if staging.exists() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+/// Moves the files of the UFO written to `staging` that differ to `path`
+/// and removes the ones it doesn't have, or renames `staging` to `path` if
+/// there is nothing there yet.
+fn update(staging: &Path, path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let new_files = files(staging)?;
+    if !path.exists() {
+        fs::rename(staging, path)?;
+        return Ok(new_files.into_iter().collect());
+    }
+
+    let mut changed = Vec::new();
+    for file in &new_files {
+        let source = staging.join(file);
+        let target = path.join(file);
+        if target.is_file() && fs::read(&source)? == fs::read(&target)? {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&source, &target)?;
+        changed.push(file.clone());
+    }
+    for file in files(path)? {
+        if new_files.contains(&file) || is_unmanaged(&file) {
+            continue;
+        }
+        let target = path.join(&file);
+        fs::remove_file(&target)?;
+        if let Some(parent) = target.parent() {
+            remove_empty_dirs(path, parent)?;
+        }
+        changed.push(file);
+    }
+    changed.sort();
+    Ok(changed)
+}
+
+/// Reads the files of the `data` and `images` directories of the UFOs at
+/// `sources`, directories or `.ufoz` archives, keyed by their path relative
+/// to the UFO with `/` as separator. Where several have a file, the first
+/// one's is kept.
+fn read_resources(sources: &[&Path]) -> Result<Files, Error> {
+    let mut resources = Files::new();
+    for source in sources {
+        let files = if source.is_file() && crate::ufoz::is_ufoz(source) {
+            crate::ufoz::read(source)?
+                .into_iter()
+                .filter(|(name, _)| is_unmanaged(Path::new(name)))
+                .collect()
+        } else {
+            let mut files = Files::new();
+            for dir in UNMANAGED_DIRS {
+                let dir = source.join(dir);
+                if !dir.is_dir() {
+                    continue;
+                }
+                for file in self::files(&dir)? {
+                    let name = Path::new(dir.file_name().unwrap_or_default()).join(&file);
+                    let name = name.to_string_lossy().replace('\\', "/");
+                    files.insert(name, fs::read(dir.join(&file))?);
+                }
+            }
+            files
+        };
+        for (name, data) in files {
+            resources.entry(name).or_insert(data);
+        }
+    }
+    Ok(resources)
+}
+
+/// Leaves the images in `resources` that the glyphs of `font` use, and
+/// removes the images from the glyphs that aren't in `resources`.
+fn keep_used_images(font: &mut Font, resources: &mut Files) {
+    let mut used = BTreeSet::new();
+    let mut missing = Vec::new();
+    for layer in font.layers.iter() {
+        for glyph in layer.iter() {
+            let image = match &glyph.image {
+                Some(image) => format!("images/{}", image.file_name.to_string_lossy()),
+                None => continue,
+            };
+            if resources.contains_key(&image) {
+                used.insert(image);
+            } else {
+                missing.push((layer.name().clone(), glyph.name.clone()));
+            }
+        }
+    }
+    for (layer, glyph) in missing {
+        let layer = font.layers.get_mut(&layer);
+        if let Some(glyph) = layer.and_then(|l| l.get_glyph_mut(&glyph)) {
+            glyph.image = None;
+        }
+    }
+    resources.retain(|name, _| !name.starts_with("images/") || used.contains(name));
+}
+
+/// Saves `font` to `path` like [`save`], along with `resources`, the files
+/// of its `data` and `images` directories. Images in the `images` directory
+/// of an existing UFO at `path` that aren't in `resources` are removed.
+fn save_with_resources(font: &Font, path: &Path, resources: &Files) -> Result<Vec<PathBuf>, Error> {
+    if crate::ufoz::is_ufoz(path) {
+        return crate::ufoz::save(font, path, resources);
+    }
+    let mut changed = save(font, path)?;
+    changed.extend(write_resources(path, resources)?);
+    let images = path.join("images");
+    if images.is_dir() {
+        for file in files(&images)? {
+            let name = Path::new("images").join(&file);
+            if !resources.contains_key(&name.to_string_lossy().replace('\\', "/")) {
+                fs::remove_file(images.join(&file))?;
+                changed.push(name);
+            }
+        }
+        remove_empty_dirs(path, &images)?;
+    }
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+/// Writes the files of `resources` below `dir` whose contents differ, and
+/// returns their paths.
+pub(crate) fn write_resources(dir: &Path, resources: &Files) -> Result<Vec<PathBuf>, Error> {
+    let mut written = Vec::new();
+    for (name, data) in resources {
+        let file = PathBuf::from(name);
+        let target = dir.join(&file);
+        if target.is_file() && fs::read(&target)? == *data {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, data)?;
+        written.push(file);
+    }
+    Ok(written)
+}
+
+/// Saves `font`, made from the UFOs at `sources`, to `path` like [`save`],
+/// along with the `data` directories of the sources and the files of their
+/// `images` directories that its glyphs use. Glyph images that no source has
+/// are removed from the glyphs first, so none are left dangling.
+pub fn save_with_sources(
+    font: &mut Font,
+    path: impl AsRef<Path>,
+    sources: &[&Path],
+) -> Result<Vec<PathBuf>, Error> {
+    let mut resources = read_resources(sources)?;
+    keep_used_images(font, &mut resources);
+    save_with_resources(font, path.as_ref(), &resources)
+}
@@ -0,0 +1,205 @@
+//! Subsetting a font to some of its glyphs.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use norad::{Font, Plist};
+use plist::Value;
+
+use crate::components::ComponentGraph;
+use crate::features::{self, FeatureIssue};
+use crate::public_lib::{
+    GLYPH_ORDER_KEY, OPENTYPE_CATEGORIES_KEY, POSTSCRIPT_NAMES_KEY, SKIP_EXPORT_GLYPHS_KEY,
+    UNICODE_VARIATION_SEQUENCES_KEY,
+};
+use crate::Error;
+
+/// The glyph that is always kept, as fonts need it.
+pub const NOTDEF: &str = ".notdef";
+
+/// The result of subsetting a font.
+#[derive(Debug, Clone)]
+pub struct Subset {
+    pub font: Font,
+    /// The names of the glyphs removed from any layer.
+    pub removed: BTreeSet<String>,
+    /// The requested glyph names that are not in the default layer.
+    pub missing: Vec<String>,
+    /// The references of the features to removed glyphs. The features are
+    /// left as they are, so they need fixing before the subset compiles.
+    pub features: Vec<FeatureIssue>,
+}
+
+/// Returns the glyphs to keep: the requested ones and those of the default
+/// layer with one of `codepoints`, `.notdef`, and the component base glyphs
+/// they use in any layer, recursively.
+fn glyphs_to_keep(font: &Font, glyphs: &[String], codepoints: &[u32]) -> BTreeSet<String> {
+    let layer = font.default_layer();
+    let mut keep: BTreeSet<String> = glyphs
+        .iter()
+        .filter(|name| layer.contains_glyph(name))
+        .cloned()
+        .collect();
+    keep.extend(
+        layer
+            .iter()
+            .filter(|g| {
+                g.codepoints
+                    .iter()
+                    .any(|c| codepoints.contains(&(*c as u32)))
+            })
+            .map(|g| g.name.to_string()),
+    );
+    if layer.contains_glyph(NOTDEF) {
+        keep.insert(NOTDEF.to_string());
+    }
+
+    let graphs: Vec<ComponentGraph> = font.layers.iter().map(ComponentGraph::new).collect();
+    loop {
+        let bases: BTreeSet<String> = graphs
+            .iter()
+            .flat_map(|graph| keep.iter().flat_map(move |g| graph.all_bases(g)))
+            .filter(|base| !keep.contains(*base))
+            .map(|base| base.to_string())
+            .collect();
+        if bases.is_empty() {
+            return keep;
+        }
+        keep.extend(bases);
+    }
+}
+
+/// Removes the names in `removed` from `value`, if it is an array.
+fn filter_names(value: &mut Value, removed: &BTreeSet<String>) {
+    if let Some(items) = value.as_array_mut() {
+        items.retain(|item| item.as_string().is_none_or(|name| !removed.contains(name)));
+    }
+}
+
+/// Removes the `removed` glyphs from the well-known keys of a font lib.
+/// Values that don't have the specified form are left alone.
+fn filter_lib(lib: &mut Plist, removed: &BTreeSet<String>) {
+    for key in [GLYPH_ORDER_KEY, SKIP_EXPORT_GLYPHS_KEY] {
+        if let Some(value) = lib.get_mut(key) {
+            filter_names(value, removed);
+        }
+    }
+    for key in [POSTSCRIPT_NAMES_KEY, OPENTYPE_CATEGORIES_KEY] {
+        if let Some(dict) = lib.get_mut(key).and_then(Value::as_dictionary_mut) {
+            dict.retain(|name, _| !removed.contains(name));
+        }
+    }
+    let sequences = lib
+        .get_mut(UNICODE_VARIATION_SEQUENCES_KEY)
+        .and_then(Value::as_dictionary_mut);
+    for bases in sequences.into_iter().flat_map(|s| s.values_mut()) {
+        if let Some(bases) = bases.as_dictionary_mut() {
+            bases.retain(|_, glyph| glyph.as_string().is_none_or(|g| !removed.contains(g)));
+        }
+    }
+}
+
+/// Removes the `removed` glyphs from the groups, kerning and well-known lib
+/// keys of `font`, along with the groups this empties and the kerning pairs
+/// using them.
+pub(crate) fn remove_references(font: &mut Font, removed: &BTreeSet<String>) {
+    let mut emptied = BTreeSet::new();
+    if let Some(groups) = font.groups.as_mut() {
+        for (name, members) in groups.iter_mut() {
+            let before = members.len();
+            members.retain(|m| !removed.contains(m.as_ref()));
+            if members.is_empty() && before > 0 {
+                emptied.insert(name.clone());
+            }
+        }
+        groups.retain(|name, _| !emptied.contains(name));
+    }
+    let dropped = |name: &String| removed.contains(name) || emptied.contains(name);
+    if let Some(kerning) = font.kerning.as_mut() {
+        kerning.retain(|first, _| !dropped(first));
+        for seconds in kerning.values_mut() {
+            seconds.retain(|second, _| !dropped(second));
+        }
+        kerning.retain(|_, seconds| !seconds.is_empty());
+    }
+    filter_lib(&mut font.lib, removed);
+}
+
+/// Subsets `font` to the glyphs named in `glyphs` and those with one of
+/// `codepoints`, plus `.notdef` and their component base glyphs.
+///
+/// Every layer is pruned to these glyphs. Kerning pairs and group members
+/// referring to removed glyphs are dropped, as are groups emptied by the
+/// subset and the kerning pairs using them. The glyph lists and dictionaries
+/// of the well-known `public.*` font lib keys are filtered too. The
+/// features, with includes resolved relative to `include_dir`, are checked
+/// for references to removed glyphs.
+pub fn subset_font(
+    font: &Font,
+    glyphs: &[String],
+    codepoints: &[u32],
+    include_dir: &Path,
+) -> Subset {
+    let keep = glyphs_to_keep(font, glyphs, codepoints);
+    let missing = glyphs
+        .iter()
+        .filter(|name| !font.default_layer().contains_glyph(name))
+        .cloned()
+        .collect();
+
+    let mut font = font.clone();
+    let mut removed = BTreeSet::new();
+    let layer_names: Vec<String> = font.layers.names().map(|n| n.to_string()).collect();
+    for name in layer_names {
+        let layer = match font.layers.get_mut(&name) {
+            Some(layer) => layer,
+            None => continue,
+        };
+        let names: Vec<String> = layer
+            .iter()
+            .map(|g| g.name.to_string())
+            .filter(|name| !keep.contains(name))
+            .collect();
+        for name in names {
+            layer.remove_glyph(&name);
+            removed.insert(name);
+        }
+    }
+
+    remove_references(&mut font, &removed);
+
+    let features = font.features.as_deref().map_or_else(Vec::new, |text| {
+        features::check_features(text, font.default_layer(), include_dir)
+            .issues
+            .into_iter()
+            .filter(|issue| match issue {
+                FeatureIssue::MissingGlyph { glyph, .. } => removed.contains(glyph),
+                _ => false,
+            })
+            .collect()
+    });
+
+    Subset {
+        font,
+        removed,
+        missing,
+        features,
+    }
+}
+
+/// Loads the UFO at `path`, subsets it with [`subset_font`] and saves the
+/// result to `output`, with the `data` directory and the images the kept
+/// glyphs use; references to images the UFO lacks are dropped. Feature
+/// includes are resolved relative to the directory containing the UFO.
+pub fn subset_ufo(
+    path: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    glyphs: &[String],
+    codepoints: &[u32],
+) -> Result<Subset, Error> {
+    let path = path.as_ref();
+    let font = crate::load(path)?;
+    let mut subset = subset_font(&font, glyphs, codepoints, features::include_dir(path));
+    crate::save_with_sources(&mut subset.font, output, &[path])?;
+    Ok(subset)
+}
@@ -0,0 +1,197 @@
+//! Reading and writing UFOZ, a UFO stored in a zip archive.
+//!
+//! The archive holds a single top-level `<name>.ufo` directory with the
+//! files of the UFO.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use norad::{DataRequest, Font};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::files::Files;
+use crate::Error;
+
+/// Returns true if `path` names a UFOZ archive.
+pub(crate) fn is_ufoz(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ufoz"))
+}
+
+/// Reads the files of the archive at `path`, relative to its top-level
+/// directory.
+pub(crate) fn read(path: &Path) -> Result<Files, Error> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut files = Files::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)?;
+        files.insert(name, data);
+    }
+
+    let root = files
+        .keys()
+        .next()
+        .and_then(|name| name.split_once('/'))
+        .map(|(root, _)| format!("{}/", root))
+        .filter(|root| files.keys().all(|name| name.starts_with(root.as_str())));
+    match root {
+        Some(root) => Ok(files
+            .into_iter()
+            .map(|(name, data)| (name[root.len()..].to_string(), data))
+            .collect()),
+        None => Ok(files),
+    }
+}
+
+/// Loads the UFOZ archive at `path` the same way as the UFO directory it
+/// contains, parsing its files in memory.
+pub(crate) fn load(path: &Path, request: DataRequest) -> Result<Font, Error> {
+    crate::files::load(&read(path)?, request)
+}
+
+/// Writes `font` and the files of its `data` and `images` directories in
+/// `resources` to a UFOZ archive at `path`, replacing any existing file.
+/// Returns the paths of the files in the archive, relative to the UFO.
+pub(crate) fn save(font: &Font, path: &Path, resources: &Files) -> Result<Vec<PathBuf>, Error> {
+    let staging = path.with_extension("ufoz.iondrive-tmp");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let result = font
+        .save(&staging)
+        .map_err(Error::from)
+        .and_then(|_| crate::save::write_resources(&staging, resources))
+        .and_then(|_| write(&staging, path));
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn write(dir: &Path, path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let root = Path::new(path.file_stem().unwrap_or_default()).with_extension("ufo");
+    let files: Vec<_> = crate::save::files(dir)?.into_iter().collect();
+    let part = path.with_extension("ufoz.iondrive-part");
+    if let Err(error) = write_archive(dir, &root, &files, &part) {
+        let _ = fs::remove_file(&part);
+        return Err(error);
+    }
+    fs::rename(&part, path)?;
+    Ok(files)
+}
+
+fn write_archive(dir: &Path, root: &Path, files: &[PathBuf], path: &Path) -> Result<(), Error> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(File::create(path)?);
+    for file in files {
+        let name = root.join(file);
+        let name: Vec<_> = name
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect();
+        zip.start_file(name.join("/"), options)?;
+        zip.write_all(&fs::read(dir.join(file))?)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::load::tests::assert_same_font;
+
+    /// Zips the files of the UFO directory `ufo` into `path`, below a
+    /// top-level directory of the same name.
+    pub(crate) fn zip_dir(ufo: &Path, path: &Path) {
+        let root = Path::new(ufo.file_name().unwrap());
+        let files: Vec<_> = crate::save::files(ufo).unwrap().into_iter().collect();
+        write_archive(ufo, root, &files, path).unwrap();
+    }
+
+    fn assert_loads_like_dir(ufo: &Path) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("font.ufoz");
+        zip_dir(ufo, &path);
+        assert_same_font(&crate::load(&path).unwrap(), &crate::load(ufo).unwrap());
+    }
+
+    #[test]
+    fn loads_like_dir() {
+        for entry in fs::read_dir("tests/data").unwrap() {
+            let ufo = entry.unwrap().path();
+            if ufo.extension().is_some_and(|ext| ext == "ufo") {
+                assert_loads_like_dir(&ufo);
+            }
+        }
+    }
+
+    #[test]
+    fn loads_ufo2_like_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let ufo = dir.path().join("Old.ufo");
+        fs::create_dir_all(ufo.join("glyphs")).unwrap();
+        let plist = |body: &str| {
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <plist version=\"1.0\">\n{}\n</plist>\n",
+                body
+            )
+        };
+        let files = [
+            (
+                "metainfo.plist",
+                plist(
+                    "<dict><key>creator</key><string>test</string>\
+                     <key>formatVersion</key><integer>2</integer></dict>",
+                ),
+            ),
+            (
+                "glyphs/contents.plist",
+                plist("<dict><key>A</key><string>A_.glif</string></dict>"),
+            ),
+            (
+                "glyphs/A_.glif",
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <glyph name=\"A\" format=\"1\">\n\
+                 <advance width=\"500\"/>\n<unicode hex=\"0041\"/>\n</glyph>\n"
+                    .to_string(),
+            ),
+        ];
+        for (name, contents) in &files {
+            fs::write(ufo.join(name), contents).unwrap();
+        }
+        assert_loads_like_dir(&ufo);
+    }
+
+    #[test]
+    fn save_keeps_resources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("font.ufoz");
+        let mut font = crate::load("tests/data/MutatorSansBoldCondensed.ufo").unwrap();
+        let glyph = font.default_layer_mut().get_glyph_mut("A").unwrap();
+        glyph.image = Some(norad::Image {
+            file_name: "A.png".into(),
+            color: None,
+            transform: Default::default(),
+        });
+        let mut resources = Files::new();
+        resources.insert("data/com.example/notes.txt".into(), b"notes".to_vec());
+        resources.insert("images/A.png".into(), b"\x89PNG".to_vec());
+        save(&font, &path, &resources).unwrap();
+
+        let loaded = crate::load(&path).unwrap();
+        crate::save(&loaded, &path).unwrap();
+        let files = read(&path).unwrap();
+        for (name, data) in &resources {
+            assert_eq!(files.get(name), Some(data), "{}", name);
+        }
+        assert_same_font(&crate::load(&path).unwrap(), &loaded);
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use norad::{Font, Groups, Kerning, Layer};
+
+use crate::decompose::{decompose_glyph, DecomposeError};
+use crate::features::{self, FeatureIssue};
+use crate::kerning::{KerningLookup, KERN1_PREFIX, KERN2_PREFIX};
+use crate::public_lib::{self, LibWarning};
+use crate::{Error, LoadOptions};
+
+/// The side of a kerning pair a group applies to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    First,
+    Second,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::First => "first",
+            Side::Second => "second",
+        }
+    }
+}
+
+/// A problem found in a font's kerning or kerning groups.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KerningIssue {
+    /// A glyph is a member of several kerning groups of the same side.
+    MultipleGroups {
+        glyph: String,
+        side: Side,
+        groups: Vec<String>,
+    },
+    /// A kerning group lists a glyph that is not in the font.
+    MissingGroupMember { group: String, glyph: String },
+    /// A kerning pair refers to a glyph that is not in the font.
+    MissingGlyph {
+        first: String,
+        second: String,
+        glyph: String,
+    },
+    /// A kerning pair refers to a group that does not exist.
+    MissingGroup {
+        first: String,
+        second: String,
+        group: String,
+    },
+    /// A kerning pair with a value of zero that does not override anything.
+    ZeroValue { first: String, second: String },
+    /// A pair exception that has the same value as the class pair it
+    /// overrides, so removing it would not change anything.
+    RedundantException {
+        first: String,
+        second: String,
+        value: f32,
+        class_first: String,
+        class_second: String,
+    },
+}
+
+impl KerningIssue {
+    /// A short, stable name for the kind of issue.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            KerningIssue::MultipleGroups { .. } => "multiple_groups",
+            KerningIssue::MissingGroupMember { .. } => "missing_group_member",
+            KerningIssue::MissingGlyph { .. } => "missing_glyph",
+            KerningIssue::MissingGroup { .. } => "missing_group",
+            KerningIssue::ZeroValue { .. } => "zero_value",
+            KerningIssue::RedundantException { .. } => "redundant_exception",
+        }
+    }
+
+    /// Whether the issue is merely untidy rather than wrong.
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            KerningIssue::ZeroValue { .. } | KerningIssue::RedundantException { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for KerningIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KerningIssue::MultipleGroups {
+                glyph,
+                side,
+                groups,
+            } => write!(
+                f,
+                "glyph '{}' is in several {} side kerning groups: {}",
+                glyph,
+                side.as_str(),
+                groups.join(", ")
+            ),
+            KerningIssue::MissingGroupMember { group, glyph } => {
+                write!(f, "group '{}' contains missing glyph '{}'", group, glyph)
+            }
+            KerningIssue::MissingGlyph {
+                first,
+                second,
+                glyph,
+            } => write!(
+                f,
+                "pair ({}, {}) refers to missing glyph '{}'",
+                first, second, glyph
+            ),
+            KerningIssue::MissingGroup {
+                first,
+                second,
+                group,
+            } => write!(
+                f,
+                "pair ({}, {}) refers to missing group '{}'",
+                first, second, group
+            ),
+            KerningIssue::ZeroValue { first, second } => {
+                write!(f, "pair ({}, {}) has a value of zero", first, second)
+            }
+            KerningIssue::RedundantException {
+                first,
+                second,
+                value,
+                class_first,
+                class_second,
+            } => write!(
+                f,
+                "exception ({}, {}) duplicates the value {} of ({}, {})",
+                first, second, value, class_first, class_second
+            ),
+        }
+    }
+}
+
+/// Returns the pair an exception overrides and its value, following the
+/// lookup precedence glyph-group, group-glyph, group-group.
+fn class_pair(
+    kerning: &Kerning,
+    lookup: &KerningLookup,
+    first: &str,
+    second: &str,
+) -> Option<(String, String, f32)> {
+    let first_group = if first.starts_with(KERN1_PREFIX) {
+        None
+    } else {
+        lookup.first_group(first)
+    };
+    let second_group = if second.starts_with(KERN2_PREFIX) {
+        None
+    } else {
+        lookup.second_group(second)
+    };
+    let candidates = [
+        second_group.map(|s| (first, s)),
+        first_group.map(|f| (f, second)),
+        first_group.and_then(|f| second_group.map(|s| (f, s))),
+    ];
+    candidates.iter().flatten().find_map(|(f, s)| {
+        kerning
+            .get(*f)
+            .and_then(|v| v.get(*s))
+            .map(|value| (f.to_string(), s.to_string(), *value))
+    })
+}
+
+/// Checks that one side of the pair `(first, second)`, `name`, refers to an
+/// existing group or glyph.
+fn check_reference(
+    groups: &Groups,
+    layer: &Layer,
+    (first, second): (&str, &str),
+    name: &str,
+    prefix: &str,
+) -> Option<KerningIssue> {
+    if name.starts_with(prefix) {
+        if groups.contains_key(name) {
+            return None;
+        }
+        Some(KerningIssue::MissingGroup {
+            first: first.to_string(),
+            second: second.to_string(),
+            group: name.to_string(),
+        })
+    } else if layer.contains_glyph(name) {
+        None
+    } else {
+        Some(KerningIssue::MissingGlyph {
+            first: first.to_string(),
+            second: second.to_string(),
+            glyph: name.to_string(),
+        })
+    }
+}
+
+/// Checks kerning and kerning groups against each other and the glyphs in
+/// `layer`, which should be the font's default layer.
+///
+/// Issues are reported in a deterministic order: group issues first, then
+/// pair issues sorted by pair.
+pub fn check_kerning(kerning: &Kerning, groups: &Groups, layer: &Layer) -> Vec<KerningIssue> {
+    let mut issues = Vec::new();
+
+    let mut memberships: BTreeMap<(&str, bool), Vec<String>> = BTreeMap::new();
+    for (group, members) in groups {
+        let first = group.starts_with(KERN1_PREFIX);
+        if !first && !group.starts_with(KERN2_PREFIX) {
+            continue;
+        }
+        for member in members {
+            memberships
+                .entry((member.as_ref(), first))
+                .or_default()
+                .push(group.clone());
+            if !layer.contains_glyph(member) {
+                issues.push(KerningIssue::MissingGroupMember {
+                    group: group.clone(),
+                    glyph: member.to_string(),
+                });
+            }
+        }
+    }
+    for ((glyph, first), groups) in memberships {
+        if groups.len() > 1 {
+            issues.push(KerningIssue::MultipleGroups {
+                glyph: glyph.to_string(),
+                side: if first { Side::First } else { Side::Second },
+                groups,
+            });
+        }
+    }
+
+    let lookup = KerningLookup::new(kerning, groups);
+    for (first, seconds) in kerning {
+        for (second, value) in seconds {
+            let pair = (first.as_str(), second.as_str());
+            issues.extend(check_reference(groups, layer, pair, first, KERN1_PREFIX));
+            issues.extend(check_reference(groups, layer, pair, second, KERN2_PREFIX));
+            match class_pair(kerning, &lookup, first, second) {
+                Some((class_first, class_second, class_value))
+                    if (class_value - value).abs() < f32::EPSILON =>
+                {
+                    issues.push(KerningIssue::RedundantException {
+                        first: first.clone(),
+                        second: second.clone(),
+                        value: *value,
+                        class_first,
+                        class_second,
+                    })
+                }
+                None if *value == 0.0 => issues.push(KerningIssue::ZeroValue {
+                    first: first.clone(),
+                    second: second.clone(),
+                }),
+                _ => (),
+            }
+        }
+    }
+
+    issues
+}
+
+/// A glyph whose components can't be resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentIssue {
+    pub layer: String,
+    pub error: DecomposeError,
+}
+
+impl std::fmt::Display for ComponentIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "layer '{}': {}", self.layer, self.error)
+    }
+}
+
+/// Checks that the components of all glyphs in all layers can be resolved.
+pub fn check_components(font: &Font) -> Vec<ComponentIssue> {
+    let mut issues = Vec::new();
+    for layer in font.layers.iter() {
+        let mut glyphs: Vec<_> = layer.iter().filter(|g| !g.components.is_empty()).collect();
+        glyphs.sort_by(|a, b| a.name.cmp(&b.name));
+        for glyph in glyphs {
+            if let Err(error) = decompose_glyph(layer, glyph) {
+                issues.push(ComponentIssue {
+                    layer: layer.name().to_string(),
+                    error,
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// The results of validating a UFO.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub kerning: Vec<KerningIssue>,
+    pub components: Vec<ComponentIssue>,
+    /// Malformed `public.*` lib values, which are only warnings.
+    pub lib: Vec<LibWarning>,
+    pub features: Vec<FeatureIssue>,
+}
+
+impl ValidationReport {
+    /// Whether any of the issues found is an error rather than a warning.
+    pub fn has_errors(&self) -> bool {
+        !self.components.is_empty()
+            || !self.features.is_empty()
+            || self.kerning.iter().any(|i| !i.is_warning())
+    }
+
+    /// Whether no issues were found at all.
+    pub fn is_empty(&self) -> bool {
+        self.kerning.is_empty()
+            && self.components.is_empty()
+            && self.lib.is_empty()
+            && self.features.is_empty()
+    }
+}
+
+/// Loads the UFO at `path` along with its groups, which are read without
+/// validation.
+fn load_unvalidated_groups(path: &Path) -> Result<(Font, Groups), Error> {
+    // norad refuses to load overlapping kerning groups, which is one of the
+    // things we want to report, so groups.plist is read on its own.
+    let mut options = LoadOptions::new();
+    options.groups(false);
+    if path.is_file() && crate::ufoz::is_ufoz(path) {
+        let files = crate::ufoz::read(path)?;
+        let groups = match files.get("groups.plist") {
+            Some(data) => plist::from_bytes(data)?,
+            None => Groups::new(),
+        };
+        return Ok((options.load_from_files(&files)?, groups));
+    }
+    let font = options.load(path)?;
+    let groups_path = path.join("groups.plist");
+    let groups: Groups = if groups_path.exists() {
+        plist::from_file(&groups_path)?
+    } else {
+        Groups::new()
+    };
+    Ok((font, groups))
+}
+
+/// Loads the UFO at `path` and checks its kerning with [`check_kerning`].
+pub fn check_ufo_kerning(path: impl AsRef<Path>) -> Result<Vec<KerningIssue>, Error> {
+    let (font, groups) = load_unvalidated_groups(path.as_ref())?;
+    let kerning = font.kerning.clone().unwrap_or_default();
+    Ok(check_kerning(&kerning, &groups, font.default_layer()))
+}
+
+/// Loads the UFO at `path` and runs all checks on it.
+///
+/// An error is returned if the UFO can't be loaded at all.
+pub fn validate_ufo(path: impl AsRef<Path>) -> Result<ValidationReport, Error> {
+    let path = path.as_ref();
+    let (font, groups) = load_unvalidated_groups(path)?;
+    let kerning = font.kerning.clone().unwrap_or_default();
+    let features = features::check_features(
+        font.features.as_deref().unwrap_or_default(),
+        font.default_layer(),
+        features::include_dir(path),
+    );
+    Ok(ValidationReport {
+        kerning: check_kerning(&kerning, &groups, font.default_layer()),
+        components: check_components(&font),
+        lib: public_lib::check_font(&font),
+        features: features.issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies the UFO directory `ufo` into `dir` and returns the copy's path.
+    fn copy_ufo(ufo: &Path, dir: &Path) -> std::path::PathBuf {
+        let copy = dir.join(ufo.file_name().unwrap());
+        for file in crate::save::files(ufo).unwrap() {
+            let dest = copy.join(&file);
+            std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+            std::fs::copy(ufo.join(&file), dest).unwrap();
+        }
+        copy
+    }
+
+    #[test]
+    fn validates_ufoz_like_dir() {
+        for entry in std::fs::read_dir("tests/data").unwrap() {
+            let ufo = entry.unwrap().path();
+            if ufo.extension().is_some_and(|ext| ext == "ufo") {
+                // Feature includes are resolved relative to the parent
+                // directory, so the archive and the UFO have to share it.
+                let dir = tempfile::tempdir().unwrap();
+                let ufo = copy_ufo(&ufo, dir.path());
+                let path = dir.path().join("font.ufoz");
+                crate::ufoz::tests::zip_dir(&ufo, &path);
+                assert_eq!(validate_ufo(&path).unwrap(), validate_ufo(&ufo).unwrap());
+            }
+        }
+    }
+}
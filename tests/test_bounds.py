@@ -0,0 +1,32 @@
+import math
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+UFOS = [
+    Path("tests/data/MutatorSansBoldCondensed.ufo"),
+    Path("tests/data/SourceSans_ExtraLight.ufo"),
+]
+
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_bounds(path: Path) -> None:
+    font = ufoLib2.Font.open(path)
+    bounds = iondrive.bounds(path)
+
+    assert bounds.keys() == font.keys()
+    for glyph in font:
+        expected = glyph.getBounds(font.layers.defaultLayer)
+        if expected is None:
+            assert bounds[glyph.name] is None
+        else:
+            for a, b in zip(expected, bounds[glyph.name]):
+                assert math.isclose(a, b, rel_tol=1e-05, abs_tol=1e-03)
+
+
+def test_bounds_of_rust_font() -> None:
+    path = UFOS[0]
+    assert iondrive.bounds(iondrive.RustFont.open(path)) == iondrive.bounds(path)
@@ -0,0 +1,58 @@
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+
+def test_check_kerning(tmp_path: Path) -> None:
+    font = ufoLib2.Font()
+    for name in ("A", "Aacute", "V", "W"):
+        font.newGlyph(name)
+    font.groups["public.kern1.A"] = ["A", "Aacute"]
+    font.groups["public.kern1.A_alt"] = ["A"]
+    font.groups["public.kern2.V"] = ["V", "W", "Y"]
+    font.kerning[("public.kern1.A", "public.kern2.V")] = -20
+    font.kerning[("Aacute", "public.kern2.V")] = -20
+    font.kerning[("A", "B")] = -5
+    font.kerning[("public.kern1.gone", "V")] = -5
+    font.kerning[("W", "A")] = 0
+    # Overlapping kerning groups don't pass ufoLib's validation.
+    font.save(tmp_path / "Kerning.ufo", validate=False)
+
+    issues = iondrive.check_kerning(tmp_path / "Kerning.ufo")
+
+    assert [(i["kind"], i.get("glyph"), i.get("group")) for i in issues] == [
+        ("missing_group_member", "Y", "public.kern2.V"),
+        ("multiple_groups", "A", None),
+        ("missing_glyph", "B", None),
+        ("redundant_exception", None, None),
+        ("zero_value", None, None),
+        ("missing_group", None, "public.kern1.gone"),
+    ]
+    multiple = issues[1]
+    assert multiple["side"] == "first"
+    assert multiple["groups"] == ["public.kern1.A", "public.kern1.A_alt"]
+    redundant = issues[3]
+    assert (redundant["first"], redundant["second"]) == (
+        "Aacute",
+        "public.kern2.V",
+    )
+    assert (redundant["class_first"], redundant["class_second"]) == (
+        "public.kern1.A",
+        "public.kern2.V",
+    )
+
+
+def test_check_kerning_clean() -> None:
+    path = Path("tests/data/MutatorSansBoldCondensed.ufo")
+    assert iondrive.check_kerning(path) == []
+
+
+def test_check_kerning_rust_font() -> None:
+    font = iondrive.RustFont.open(Path("tests/data/MutatorSansBoldCondensed.ufo"))
+    font.set_kerning("A", "missing", -5)
+    issues = iondrive.check_kerning(font)
+    assert [(i["kind"], i.get("glyph")) for i in issues] == [
+        ("missing_glyph", "missing")
+    ]
@@ -0,0 +1,52 @@
+import shutil
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_cmap() -> None:
+    result = iondrive.cmap(UFO)
+    assert result["mapping"][0x41] == "A"
+    assert result["mapping"][0xC1] == "Aacute"
+    assert result["issues"] == []
+    assert iondrive.cmap(iondrive.RustFont.open(UFO)) == result
+    assert iondrive.cmap(ufoLib2.Font.open(UFO)) == result
+
+
+def test_cmap_issues(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    glif = path / "glyphs" / "B_.glif"
+    surrogate = '<unicode hex="0042"/><unicode hex="D800"/>'
+    glif.write_text(glif.read_text().replace('<unicode hex="0042"/>', surrogate))
+    font = ufoLib2.Font.open(path, lazy=True)
+    font["C"].unicodes = [0x41]
+    font.save()
+
+    result = iondrive.cmap(path)
+    assert result["mapping"][0x41] == "A"
+    assert 0xD800 not in result["mapping"]
+    issues = {i["kind"]: i for i in result["issues"]}
+    invalid = issues["invalid"]
+    assert (invalid["glyph"], invalid["codepoint"]) == ("B", 0xD800)
+    assert issues["duplicate"]["glyphs"] == ["A", "C"]
+    mismatch = issues["name_mismatch"]
+    assert (mismatch["glyph"], mismatch["implied"], mismatch["codepoints"]) == (
+        "C",
+        0x43,
+        [0x41],
+    )
+
+
+def test_cmap_ufolib2_layer() -> None:
+    font = ufoLib2.Font()
+    layer = font.newLayer("other")
+    layer.newGlyph("uni0041").unicodes = [0x61]
+    layer.newGlyph("u1F600").unicodes = [0x1F600]
+    result = iondrive.cmap(font, layer="other")
+    assert result["mapping"] == {0x61: "uni0041", 0x1F600: "u1F600"}
+    assert [i["kind"] for i in result["issues"]] == ["name_mismatch"]
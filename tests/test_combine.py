@@ -0,0 +1,144 @@
+import shutil
+import xml.etree.ElementTree as ET
+from pathlib import Path
+
+import pytest
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def extension() -> iondrive.RustFont:
+    font = iondrive.RustFont.open(UFO)
+    font.set_width("A", 1234)
+    font.add_glyph("Alpha")
+    font.set_unicodes("Alpha", [0x391])
+    font.set_kerning("A", "V", -99)
+    font.set_group("public.kern1.@MMK_L_A", ["A", "Alpha"])
+    return font
+
+
+def glyph_width(ufo: Path, file_name: str) -> float:
+    advance = ET.parse(ufo / "glyphs" / file_name).find("advance")
+    return float(advance.get("width", 0))
+
+
+def test_combine_keep() -> None:
+    result = iondrive.combine(UFO, extension())
+
+    font = result["font"]
+    assert "Alpha" in font.glyph_names()
+    assert font.kerning[("A", "V")] != -99
+    assert font.groups["public.kern1.@MMK_L_A"] == ["A"]
+    glyph = [c for c in result["conflicts"] if c["kind"] == "glyph"]
+    assert glyph == [
+        {
+            "kind": "glyph",
+            "message": "glyph 'A': kept ours",
+            "resolution": "kept_ours",
+            "glyph": "A",
+        }
+    ]
+
+
+def test_combine_replace(tmp_path: Path) -> None:
+    output = tmp_path / "combined.ufo"
+
+    result = iondrive.combine(UFO, extension(), policy="replace", output=output)
+
+    assert result["font"].kerning[("A", "V")] == -99
+    assert glyph_width(output, "A_.glif") == 1234
+    assert result["font"].groups["public.kern1.@MMK_L_A"] == ["A", "Alpha"]
+    assert [c["kind"] for c in result["conflicts"]] == ["glyph"]
+
+
+def test_combine_rename() -> None:
+    result = iondrive.combine(UFO, extension(), policy="rename", suffix=".ext")
+
+    font = result["font"]
+    assert "A.ext" in font.glyph_names()
+    assert font.kerning[("A.ext", "V")] == -99
+    assert font.groups["public.kern1.@MMK_L_A.ext"] == ["A.ext", "Alpha"]
+    assert font.unicode_map()[0x41] == ["A"]
+    conflicts = {c["kind"]: c for c in result["conflicts"]}
+    assert conflicts["glyph"]["renamed"] == "A.ext"
+    assert conflicts["codepoint"]["ours"] == "A"
+    assert conflicts["codepoint"]["theirs"] == "A.ext"
+    assert font.lib["public.glyphOrder"][-2:] == ["A.ext", "Alpha"]
+
+
+@pytest.mark.parametrize(
+    "policy, their_group, group",
+    [
+        ("rename", "public.kern1.@MMK_L_A", "public.kern1.@MMK_L_A.ext"),
+        ("keep", "public.kern1.Alpha", "public.kern1.Alpha"),
+    ],
+)
+def test_combine_shared_kerning_group_member(
+    policy: str, their_group: str, group: str, tmp_path: Path
+) -> None:
+    theirs = iondrive.RustFont.open(UFO)
+    theirs.add_glyph("Alpha")
+    theirs.set_group("public.kern1.@MMK_L_A", None)
+    theirs.set_group(their_group, ["A", "Alpha"])
+    theirs.set_kerning(their_group, "V", -50)
+
+    output = tmp_path / "combined.ufo"
+
+    result = iondrive.combine(
+        UFO, theirs, policy=policy, suffix=".ext", output=output
+    )
+
+    font = iondrive.RustFont.open(output)
+    assert font.groups["public.kern1.@MMK_L_A"] == ["A"]
+    assert font.groups[group] == ["Alpha"]
+    assert font.kerning[(group, "V")] == -50
+    assert [c for c in result["conflicts"] if "glyphs" in c] == [
+        {
+            "kind": "group",
+            "message": f"glyphs A of group '{group}': kept ours",
+            "resolution": "kept_ours",
+            "group": group,
+            "glyphs": ["A"],
+        }
+    ]
+
+
+def test_combine_output_keeps_data_and_images(tmp_path: Path) -> None:
+    theirs = tmp_path / "theirs.ufo"
+    shutil.copytree(UFO, theirs)
+    (theirs / "data").mkdir()
+    (theirs / "data" / "notes.txt").write_text("notes")
+    (theirs / "images").mkdir()
+    (theirs / "images" / "alpha.png").write_bytes(b"png")
+    font = iondrive.RustFont.open(theirs)
+    font.add_glyph("Alpha")
+    font.save()
+    glif = theirs / "glyphs" / "A_lpha.glif"
+    image = '  <image fileName="alpha.png"/>\n</glyph>'
+    glif.write_text(glif.read_text().replace("</glyph>", image))
+    output = tmp_path / "combined.ufo"
+
+    iondrive.combine(UFO, theirs, output=output)
+
+    assert (output / "data" / "notes.txt").read_text() == "notes"
+    assert (output / "images" / "alpha.png").read_bytes() == b"png"
+    assert "alpha.png" in (output / "glyphs" / "A_lpha.glif").read_text()
+
+
+def test_combine_scales_to_units_per_em(tmp_path: Path) -> None:
+    theirs = iondrive.RustFont.open(UFO)
+    theirs.set_info("unitsPerEm", 500)
+    theirs.add_glyph("Alpha")
+    theirs.set_width("Alpha", 300)
+    output = tmp_path / "combined.ufo"
+
+    iondrive.combine(UFO, theirs, output=output)
+
+    assert glyph_width(output, "A_lpha.glif") == 600
+
+
+def test_combine_rename_needs_suffix() -> None:
+    with pytest.raises(ValueError):
+        iondrive.combine(UFO, UFO, policy="rename")
@@ -0,0 +1,45 @@
+import shutil
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_compatible() -> None:
+    font = iondrive.RustFont.open(UFO)
+    assert iondrive.check_compatibility([UFO, font, UFO]) == []
+
+
+def test_incompatible(tmp_path: Path) -> None:
+    path = tmp_path / "other.ufo"
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    # Move the start point of the first contour to its second point.
+    points = font["A"].contours[0].points
+    points.append(points.pop(0))
+    font["A"].contours[1].points[2].type = "curve"
+    font["B"].clearContours()
+    font["Aacute"].components.reverse()
+    del font["C"]
+    font.save()
+
+    problems = iondrive.check_compatibility([UFO, path])
+    found = {(p["glyph"], p["master"], p["kind"]) for p in problems}
+    assert found == {
+        ("A", 1, "start_point"),
+        ("A", 1, "point_type"),
+        ("B", 1, "contour_count"),
+        ("Aacute", 1, "component_base"),
+        ("C", 1, "missing_glyph"),
+    }
+    by_kind = {p["kind"]: p for p in problems if p["glyph"] == "A"}
+    assert (by_kind["start_point"]["contour"], by_kind["start_point"]["point"]) == (
+        0,
+        3,
+    )
+    point_type = by_kind["point_type"]
+    assert (point_type["contour"], point_type["point"]) == (1, 2)
+    assert (point_type["expected"], point_type["found"]) == ("line", "curve")
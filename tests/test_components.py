@@ -0,0 +1,33 @@
+from pathlib import Path
+
+import pytest
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_from_ufo() -> None:
+    graph = iondrive.ComponentGraph.from_ufo(UFO)
+    assert graph.bases("Aacute") == ["A", "acute"]
+    assert graph.users("A") == ["Aacute", "Adieresis"]
+    assert graph.users("dieresis", recursive=True) == ["Adieresis"]
+    order = graph.topological_order()
+    assert order.index("A") < order.index("Aacute")
+    assert graph.cycles() == []
+    assert graph.missing_bases() == []
+
+    font = iondrive.RustFont.open(UFO)
+    assert font.component_graph().users("A") == ["Aacute", "Adieresis"]
+
+
+def test_cycles_and_missing_bases() -> None:
+    graph = iondrive.ComponentGraph(
+        {"a": ["b"], "b": ["c"], "c": ["a"], "d": ["x", "a"], "e": ["e"]}
+    )
+    assert graph.cycles() == [["a", "b", "c", "a"], ["e", "e"]]
+    assert graph.missing_bases() == [("d", "x")]
+    assert graph.bases("d", recursive=True) == ["a", "b", "c", "x"]
+    assert graph.users("c", recursive=True) == ["a", "b", "c", "d"]
+    with pytest.raises(iondrive.IondriveError, match="a -> b -> c -> a"):
+        graph.topological_order()
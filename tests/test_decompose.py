@@ -0,0 +1,95 @@
+from pathlib import Path
+
+import pytest
+import ufoLib2
+from ufoLib2.objects import Component, Contour, Point
+
+import iondrive
+
+
+def square(x: float, y: float, size: float) -> Contour:
+    return Contour(
+        points=[
+            Point(x, y, "line"),
+            Point(x + size, y, "line"),
+            Point(x + size, y + size, "line"),
+            Point(x, y + size, "line"),
+        ]
+    )
+
+
+def coords(contour: Contour) -> list:
+    return [(p.x, p.y, p.type) for p in contour.points]
+
+
+@pytest.fixture
+def ufo_path(tmp_path: Path) -> Path:
+    font = ufoLib2.Font()
+    font.newGlyph("dot").contours.append(square(0, 0, 10))
+    colon = font.newGlyph("colon")
+    colon.components.append(Component("dot", (1, 0, 0, 1, 0, 0)))
+    colon.components.append(Component("dot", (1, 0, 0, 1, 0, 100)))
+    # Nested and scaled: two colons side by side, the second one mirrored.
+    double = font.newGlyph("doublecolon")
+    double.components.append(Component("colon", (2, 0, 0, 2, 50, 0)))
+    double.components.append(Component("colon", (-1, 0, 0, 1, 300, 0)))
+    mixed = font.newGlyph("mixed")
+    mixed.contours.append(square(500, 500, 5))
+    mixed.components.append(Component("dot", (1, 0, 0, 1, 0, 0)))
+
+    path = tmp_path / "Decompose.ufo"
+    font.save(path)
+    return path
+
+
+def test_decompose_nested(ufo_path: Path) -> None:
+    decomposed = iondrive.decompose(ufoLib2.objects, ufo_path)
+
+    assert set(decomposed.keys()) == {"colon", "doublecolon", "mixed"}
+    assert [coords(c) for c in decomposed["colon"]] == [
+        coords(square(0, 0, 10)),
+        coords(square(0, 100, 10)),
+    ]
+    assert [coords(c) for c in decomposed["doublecolon"]] == [
+        coords(square(50, 0, 20)),
+        coords(square(50, 200, 20)),
+        [(300, 0, "line"), (290, 0, "line"), (290, 10, "line"), (300, 10, "line")],
+        [
+            (300, 100, "line"),
+            (290, 100, "line"),
+            (290, 110, "line"),
+            (300, 110, "line"),
+        ],
+    ]
+    assert [coords(c) for c in decomposed["mixed"]] == [
+        coords(square(500, 500, 5)),
+        coords(square(0, 0, 10)),
+    ]
+
+
+def test_decompose_only_flipped_or_mixed(ufo_path: Path) -> None:
+    decomposed = iondrive.decompose(
+        ufoLib2.objects, ufo_path, only_flipped_or_mixed=True
+    )
+
+    assert set(decomposed.keys()) == {"doublecolon", "mixed"}
+
+
+def test_decompose_missing_base(tmp_path: Path) -> None:
+    font = ufoLib2.Font()
+    font.newGlyph("a").components.append(Component("b", (1, 0, 0, 1, 0, 0)))
+    font.save(tmp_path / "MissingBase.ufo")
+
+    with pytest.raises(iondrive.IondriveError, match="missing base glyph 'b'"):
+        iondrive.decompose(ufoLib2.objects, tmp_path / "MissingBase.ufo")
+
+
+def test_decompose_cycle(tmp_path: Path) -> None:
+    font = ufoLib2.Font()
+    font.newGlyph("a").components.append(Component("b", (1, 0, 0, 1, 0, 0)))
+    font.newGlyph("b").components.append(Component("c", (1, 0, 0, 1, 0, 0)))
+    font.newGlyph("c").components.append(Component("a", (1, 0, 0, 1, 0, 0)))
+    font.save(tmp_path / "Cycle.ufo")
+
+    with pytest.raises(iondrive.IondriveError, match="component cycle"):
+        iondrive.decompose(ufoLib2.objects, tmp_path / "Cycle.ufo")
@@ -0,0 +1,64 @@
+import shutil
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_identical() -> None:
+    diff = iondrive.diff(UFO, iondrive.RustFont.open(UFO))
+    assert diff["layers"] == []
+    assert diff["info"] == []
+    assert diff["kerning"] == []
+    assert diff["groups"] == []
+    assert diff["features"] is None
+    assert diff["summary"] == ""
+
+
+def test_changes(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    font["A"].width = 123
+    font["B"].unicodes = [0x42, 0x62]
+    font["C"].lib["com.example.key"] = 1
+    del font["D"]
+    font.newGlyph("new")
+    font.info.familyName = "Edited"
+    font.kerning[("A", "T")] = -42
+    font.groups["other"] = ["A", "B"]
+    font.features.text += "\n# comment\n"
+    font.save()
+
+    diff = iondrive.diff(UFO, path)
+    (layer,) = diff["layers"]
+    assert layer["status"] == "modified"
+    glyphs = {g["name"]: g for g in layer["glyphs"]}
+    assert glyphs["D"]["status"] == "removed"
+    assert glyphs["new"]["status"] == "added"
+    (width,) = glyphs["A"]["changes"]
+    assert (width["kind"], width["new"]) == ("width", 123)
+    (unicodes,) = glyphs["B"]["changes"]
+    assert unicodes["new"] == [0x42, 0x62]
+    (lib,) = glyphs["C"]["changes"]
+    assert (lib["kind"], lib["key"], lib["old"], lib["new"]) == (
+        "lib",
+        "com.example.key",
+        None,
+        1,
+    )
+    assert diff["info"] == [
+        {"key": "familyName", "old": "MutatorMathTest", "new": "Edited"}
+    ]
+    assert [(k["first"], k["second"], k["new"]) for k in diff["kerning"]] == [
+        ("A", "T", -42)
+    ]
+    assert diff["groups"] == [
+        {"name": "other", "status": "added", "added": ["A", "B"], "removed": []}
+    ]
+    assert diff["features"]["new"].endswith("# comment\n")
+    assert "- glyph 'D'" in diff["summary"]
+    assert "width 740 -> 123" in diff["summary"]
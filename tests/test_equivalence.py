@@ -1,4 +1,8 @@
+import datetime
 import math
+import shutil
+import subprocess
+import zipfile
 from pathlib import Path
 
 import pytest
@@ -24,7 +28,106 @@ def close_enough(a: float, b: float) -> bool:
 def test_equivalence(path: Path) -> None:
     font = ufoLib2.Font.open(path)
     id_font = iondrive.load(ufoLib2.objects, path)
+    assert_equivalent(font, id_font)
 
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_equivalence_ufoz(path: Path, tmp_path: Path) -> None:
+    ufoz = tmp_path / f"{path.stem}.ufoz"
+    with zipfile.ZipFile(ufoz, "w") as archive:
+        for file in sorted(path.rglob("*")):
+            if file.is_file():
+                archive.write(file, Path(path.name) / file.relative_to(path))
+    font = ufoLib2.Font.open(path)
+    id_font = iondrive.load(ufoLib2.objects, ufoz)
+    assert_equivalent(font, id_font)
+
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_equivalence_files(path: Path) -> None:
+    files = {
+        file.relative_to(path).as_posix(): file.read_bytes()
+        for file in path.rglob("*")
+        if file.is_file()
+    }
+    font = ufoLib2.Font.open(path)
+    id_font = iondrive.load_from_files(ufoLib2.objects, files)
+    assert_equivalent(font, id_font)
+
+
+def test_load_from_files_missing_file() -> None:
+    with pytest.raises(iondrive.IondriveError):
+        iondrive.load_from_files(ufoLib2.objects, {"fontinfo.plist": b""})
+
+
+def git(repo: Path, *args: str) -> None:
+    subprocess.run(
+        ["git", "-c", "user.name=test", "-c", "user.email=test@example.com", *args],
+        cwd=repo,
+        check=True,
+        capture_output=True,
+    )
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git is not installed")
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_equivalence_git(path: Path, tmp_path: Path) -> None:
+    git(tmp_path, "init")
+    shutil.copytree(path, tmp_path / "sources" / path.name)
+    git(tmp_path, "add", "-A")
+    git(tmp_path, "commit", "-m", "Add font")
+    font = ufoLib2.Font.open(path)
+    id_font = iondrive.load_from_git(
+        ufoLib2.objects, tmp_path, "HEAD", f"sources/{path.name}"
+    )
+    assert_equivalent(font, id_font)
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git is not installed")
+def test_load_from_git_revisions(tmp_path: Path) -> None:
+    git(tmp_path, "init")
+    ufo = tmp_path / "font.ufo"
+    shutil.copytree(UFOS[0], ufo)
+    git(tmp_path, "add", "-A")
+    git(tmp_path, "commit", "-m", "Add font")
+    font = ufoLib2.Font.open(ufo)
+    font["A"].width = 999
+    font.save()
+    git(tmp_path, "commit", "-am", "Change A")
+
+    old = iondrive.load_from_git(ufoLib2.objects, tmp_path, "HEAD~1", "font.ufo")
+    new = iondrive.load_from_git(ufoLib2.objects, tmp_path, "HEAD", "font.ufo")
+    assert old["A"].width != 999
+    assert new["A"].width == 999
+    with pytest.raises(iondrive.IondriveError):
+        iondrive.load_from_git(ufoLib2.objects, tmp_path, "HEAD", "missing.ufo")
+    with pytest.raises(iondrive.IondriveError):
+        iondrive.load_from_git(ufoLib2.objects, tmp_path, "no-such-branch", "font.ufo")
+
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_round_trip(path: Path) -> None:
+    font = ufoLib2.Font.open(path)
+    round_tripped = iondrive.RustFont.from_ufolib2(font).to_ufolib2(ufoLib2.objects)
+    assert_equivalent(font, round_tripped)
+
+    id_font = iondrive.load(ufoLib2.objects, path)
+    round_tripped = iondrive.RustFont.from_ufolib2(id_font).to_ufolib2(ufoLib2.objects)
+    assert_equivalent(id_font, round_tripped)
+
+
+def test_round_trip_modified() -> None:
+    font = ufoLib2.Font.open(UFOS[0])
+    font.lib["com.example.date"] = datetime.datetime(2021, 2, 3, 4, 5, 6)
+    font.lib["com.example.data"] = b"\x00\x01"
+    font["A"].width = 1234
+    font["A"].unicodes = [0x41, 0x61]
+    font.info.familyName = "Modified"
+    round_tripped = iondrive.RustFont.from_ufolib2(font).to_ufolib2(ufoLib2.objects)
+    assert_equivalent(font, round_tripped)
+
+
+def assert_equivalent(font: ufoLib2.Font, id_font: ufoLib2.Font) -> None:
     assert font.lib == id_font.lib
     assert font.groups == id_font.groups
     assert font.kerning == id_font.kerning
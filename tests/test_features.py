@@ -0,0 +1,76 @@
+import shutil
+from pathlib import Path
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+FEATURES = """\
+languagesystem DFLT dflt;
+include(classes.fea);
+@UC = [A-C E Aacute];
+feature liga {
+    sub A B by Agrave;
+    sub @UC' lookup foo @LC;
+} liga;
+"""
+
+
+def copy_ufo(tmp_path: Path, features: str) -> Path:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    (path / "features.fea").write_text(features)
+    return path
+
+
+def test_check_features(tmp_path: Path) -> None:
+    path = copy_ufo(tmp_path, FEATURES)
+    (tmp_path / "classes.fea").write_text("@LOW = [a S];\n")
+
+    checked = iondrive.check_features(path)
+    assert "include" not in checked["text"]
+    assert "@LOW = [a S];" in checked["text"]
+    issues = [
+        (i["kind"], i["line"], i.get("glyph", i.get("class")))
+        for i in checked["issues"]
+    ]
+    assert issues == [
+        ("missing_glyph", 1, "a"),
+        ("missing_glyph", 5, "Agrave"),
+        ("undefined_class", 6, "LC"),
+    ]
+    assert checked["issues"][0]["file"].endswith("classes.fea")
+
+
+def test_include_and_structure_errors(tmp_path: Path) -> None:
+    path = copy_ufo(tmp_path, "include(missing.fea);\nfeature liga {\n} calt;\n")
+    font = iondrive.RustFont.open(path)
+    kinds = [(i["kind"], i["line"]) for i in iondrive.check_features(font)["issues"]]
+    assert kinds == [("include", 1), ("structure", 3)]
+
+    (tmp_path / "elsewhere").mkdir()
+    (tmp_path / "elsewhere" / "missing.fea").write_text("")
+    checked = iondrive.check_features(font, include_dir=tmp_path / "elsewhere")
+    assert [i["kind"] for i in checked["issues"]] == ["structure"]
+
+
+def test_unknown_keywords_and_substitutions(tmp_path: Path) -> None:
+    features = (
+        "languagesytem DFLT dflt;\n"
+        "table OS/2 {\n    TypoAscender 800;\n} OS/2;\n"
+        "anon sbit {\n  anything { goes\n} sbit;\n"
+        "feature liga {\n    subs A by B;\n} liga;\n"
+    )
+    path = copy_ufo(tmp_path, features)
+    checked = iondrive.check_features(path)
+    issues = [(i["kind"], i["line"], i.get("keyword")) for i in checked["issues"]]
+    assert issues == [
+        ("unknown_keyword", 1, "languagesytem"),
+        ("unknown_keyword", 9, "subs"),
+    ]
+
+    features = "feature liga {\n    sub f i bye f_i;\n} liga;\n"
+    path = copy_ufo(tmp_path / "sub", features)
+    issues = iondrive.check_features(path)["issues"]
+    assert [(i["kind"], i["line"]) for i in issues] == [("structure", 2)]
+    assert "'by'" in issues[0]["message"]
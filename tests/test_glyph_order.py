@@ -0,0 +1,43 @@
+import shutil
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+@pytest.fixture
+def reordered(tmp_path: Path) -> Path:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    font.lib["public.glyphOrder"] = ["B", "missing", "A", "B"]
+    font.save()
+    return path
+
+
+def test_glyph_order(reordered: Path) -> None:
+    order = iondrive.glyph_order(reordered)
+    names = set(ufoLib2.Font.open(reordered).keys())
+    assert order[:2] == ["B", "A"]
+    assert order[2:] == sorted(names - {"A", "B"})
+
+    background = iondrive.glyph_order(iondrive.RustFont.open(reordered), "background")
+    assert background == ["S.closed"]
+
+
+def test_load_ordered(reordered: Path) -> None:
+    order = iondrive.glyph_order(reordered)
+
+    font = iondrive.load(ufoLib2.objects, reordered, glyph_order="default")
+    assert list(font.layers.defaultLayer.keys()) == order
+
+    font = iondrive.load(ufoLib2.objects, reordered, glyph_order="all")
+    for layer in font.layers:
+        assert list(layer.keys()) == iondrive.glyph_order(reordered, layer.name)
+
+    with pytest.raises(ValueError):
+        iondrive.load(ufoLib2.objects, reordered, glyph_order="alphabetical")
@@ -0,0 +1,40 @@
+import shutil
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_hashes_ignore_formatting(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    glif = path / "glyphs" / "A_.glif"
+    glif.write_text("".join(l.strip() for l in glif.read_text().splitlines()))
+
+    hashes = iondrive.hashes(UFO)
+    assert iondrive.hashes(path) == hashes
+    assert iondrive.hashes(iondrive.RustFont.open(UFO)) == hashes
+    assert set(hashes["layers"]) == {"foreground", "background"}
+    assert len(hashes["font"]) == 64
+
+
+def test_hashes_change(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    font["A"].width += 1
+    font.save()
+
+    old = iondrive.hashes(UFO)["layers"]["foreground"]
+    new = iondrive.hashes(path)["layers"]["foreground"]
+    assert [name for name in old if old[name] != new[name]] == ["A"]
+
+    old = iondrive.hashes(UFO, with_components=True)["layers"]["foreground"]
+    new = iondrive.hashes(path, with_components=True)["layers"]["foreground"]
+    changed = {name for name in old if old[name] != new[name]}
+    assert {"A", "Aacute", "Adieresis"} <= changed
+    assert "B" not in changed
+    assert iondrive.hashes(UFO)["font"] != iondrive.hashes(path)["font"]
@@ -0,0 +1,97 @@
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+
+@pytest.fixture
+def font(tmp_path: Path) -> Path:
+    font = ufoLib2.Font()
+    for name in ["A", "O", "Q", "V", "W", "beh-ar"]:
+        font.newGlyph(name)
+    font.newGlyph("alef-ar").unicodes = [0x0627]
+    font.newGlyph("alef-ar.fina")
+    font.groups["public.kern1.O"] = ["O", "Q", "missing"]
+    font.groups["public.kern2.V"] = ["V", "W"]
+    font.kerning[("public.kern1.O", "public.kern2.V")] = -40
+    font.kerning[("O", "public.kern2.V")] = -30
+    font.kerning[("public.kern1.O", "V")] = -20
+    font.kerning[("O", "W")] = -10
+    font.kerning[("A", "Z")] = -50
+    font.kerning[("alef-ar.fina", "beh-ar")] = 15.5
+    path = tmp_path / "font.ufo"
+    font.save(path)
+    return path
+
+
+def test_kern_feature(font: Path) -> None:
+    with pytest.warns(UserWarning) as record:
+        fea = iondrive.kern_feature(font)
+    messages = [str(w.message) for w in record]
+    assert any("missing glyph 'Z'" in m for m in messages)
+    assert any("'missing'" in m for m in messages)
+
+    assert "@kern1.O = [O Q];" in fea
+    assert "@kern2.V = [V W];" in fea
+    lines = [line.strip() for line in fea.splitlines()]
+    exceptions = lines.index("pos O W -10;") + 2
+    # The exceptions leave out what the glyph pair already covers.
+    assert lines[exceptions:] == [
+        "enum pos O [V] -30;",
+        "enum pos [Q] V -20;",
+        "pos @kern1.O @kern2.V -40;",
+        "} kern_pairs;",
+        "} kern;",
+    ]
+    assert "pos alef-ar.fina beh-ar 16;" in lines
+
+
+def test_split_direction(font: Path) -> None:
+    with pytest.warns(UserWarning):
+        fea = iondrive.kern_feature(iondrive.RustFont.open(font), split_direction=True)
+    ltr, rtl = fea.split("lookup kern_rtl {")
+    assert "pos O W -10;" in ltr
+    assert "alef-ar.fina" not in ltr
+    assert "pos alef-ar.fina beh-ar <16 0 16 0>;" in rtl
+
+
+def test_split_direction_mixed_group(tmp_path: Path) -> None:
+    font = iondrive.RustFont()
+    for name in ["A", "O", "V", "W", "alef-ar"]:
+        font.add_glyph(name)
+    font.set_unicodes("alef-ar", [0x0627])
+    font.set_group("public.kern1.A", ["A", "alef-ar"])
+    font.set_group("public.kern2.V", ["V", "W"])
+    font.set_kerning("public.kern1.A", "public.kern2.V", -40)
+    font.set_kerning("A", "public.kern2.V", -20)
+    font.set_kerning("A", "W", -10)
+    font.set_kerning("O", "V", -5)
+    path = tmp_path / "font.ufo"
+    font.save(path)
+    (path / "features.fea").write_text(
+        "languagesystem DFLT dflt;\n"
+        "languagesystem latn dflt;\n"
+        "languagesystem latn TRK;\n"
+    )
+
+    fea = iondrive.kern_feature(path, split_direction=True)
+
+    ltr, rtl = fea.split("lookup kern_rtl {")
+    assert "pos O V -5;" in ltr
+    # The exceptions stay with the group pair they override.
+    assert "pos A W <-10 0 -10 0>;" in rtl
+    assert "enum pos A [V] <-20 0 -20 0>;" in rtl
+    assert "pos @kern1.A @kern2.V <-40 0 -40 0>;" in rtl
+    assert rtl.endswith(
+        "feature kern {\n"
+        "    script DFLT;\n"
+        "    lookup kern_ltr;\n"
+        "    script arab;\n"
+        "    lookup kern_rtl;\n"
+        "    script latn;\n"
+        "    lookup kern_ltr;\n"
+        "    language TRK;\n"
+        "} kern;\n"
+    )
@@ -0,0 +1,52 @@
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+GROUPS = {
+    "public.kern1.A": ["A", "Aacute"],
+    "public.kern2.V": ["V", "W"],
+    "other": ["A", "V"],
+}
+KERNING = {
+    ("A", "V"): -10,
+    ("A", "public.kern2.V"): -7,
+    ("public.kern1.A", "V"): -5,
+    ("public.kern1.A", "public.kern2.V"): -1,
+}
+
+
+@pytest.fixture
+def lookup() -> iondrive.KerningLookup:
+    return iondrive.KerningLookup(KERNING, GROUPS)
+
+
+def test_get_precedence(lookup: iondrive.KerningLookup) -> None:
+    assert lookup.get("A", "V") == -10
+    assert lookup.get("A", "W") == -7
+    assert lookup.get("Aacute", "V") == -5
+    assert lookup.get("Aacute", "W") == -1
+    assert lookup.get("B", "V") is None
+    assert lookup.get("public.kern1.A", "public.kern2.V") == -1
+
+
+def test_flatten(lookup: iondrive.KerningLookup) -> None:
+    assert lookup.flatten() == {
+        ("A", "V"): -10,
+        ("A", "W"): -7,
+        ("Aacute", "V"): -5,
+        ("Aacute", "W"): -1,
+    }
+
+
+def test_from_ufo_matches_python() -> None:
+    path = Path("tests/data/MutatorSansBoldCondensed.ufo")
+    font = ufoLib2.Font.open(path)
+    from_ufo = iondrive.KerningLookup.from_ufo(path)
+    from_objects = iondrive.KerningLookup(font.kerning, font.groups)
+
+    assert from_ufo.flatten() == from_objects.flatten()
+    for (first, second), value in font.kerning.items():
+        assert from_ufo.get(first, second) == value
@@ -0,0 +1,48 @@
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+
+@pytest.fixture
+def font(tmp_path: Path) -> Path:
+    font = ufoLib2.Font()
+    font.newGlyph("A").appendAnchor({"name": "top", "x": 250, "y": 700})
+    acute = font.newGlyph("acutecomb")
+    acute.appendAnchor({"name": "_top", "x": 100, "y": 500})
+    acute.appendAnchor({"name": "top", "x": 100.25, "y": 720.5})
+    f_i = font.newGlyph("f_i")
+    f_i.appendAnchor({"name": "top_1", "x": 100, "y": 700})
+    f_i.appendAnchor({"name": "bottom_2", "x": 300, "y": 0})
+    font.newGlyph("B").appendAnchor({"name": "bottom", "x": 250, "y": 0})
+    path = tmp_path / "font.ufo"
+    font.save(path)
+    return path
+
+
+def test_mark_features(font: Path) -> None:
+    marks = iondrive.mark_features(font)
+    assert marks["mark_classes"] == {"top": {"acutecomb": (100, 500)}}
+    assert marks["bases"] == {"top": {"A": (250, 700)}}
+    assert marks["ligatures"] == {"top": {"f_i": [(100, 700), None]}}
+    assert marks["marks"] == {"top": {"acutecomb": (100.25, 720.5)}}
+
+    fea = marks["fea"]
+    assert "markClass acutecomb <anchor 100 500> @MC_top;" in fea
+    assert "pos base A <anchor 250 700> mark @MC_top;" in fea
+    assert "pos ligature f_i <anchor 100 700> mark @MC_top" in fea
+    assert "ligComponent <anchor NULL>;" in fea
+    assert "pos mark acutecomb <anchor 100 721> mark @MC_top;" in fea
+    assert "bottom" not in fea
+
+
+def test_opentype_categories(font: Path) -> None:
+    ufo = ufoLib2.Font.open(font)
+    ufo.lib["public.openTypeCategories"] = {"A": "component", "f_i": "base"}
+    ufo.save()
+    marks = iondrive.mark_features(iondrive.RustFont.open(font))
+    assert marks["bases"] == {}
+    assert marks["ligatures"] == {}
+    assert "mark2base" not in marks["fea"]
@@ -0,0 +1,52 @@
+import shutil
+from pathlib import Path
+
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def edit(path: Path, **widths: int) -> ufoLib2.Font:
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    for name, width in widths.items():
+        font[name].width = width
+    return font
+
+
+def test_merge3(tmp_path: Path) -> None:
+    ours = edit(tmp_path / "ours.ufo", A=100, D=300)
+    ours.newGlyph("ours")
+    ours.kerning[("A", "T")] = -10
+    ours.save()
+    theirs = edit(tmp_path / "theirs.ufo", B=200, D=400)
+    theirs.newGlyph("theirs")
+    del theirs["C"]
+    theirs.info.familyName = "Theirs"
+    theirs.save()
+
+    out = tmp_path / "merged.ufo"
+    conflicts = iondrive.merge3(UFO, ours.path, theirs.path, out)
+    assert [(c["kind"], c["glyph"]) for c in conflicts] == [("glyph", "D")]
+
+    merged = ufoLib2.Font.open(out)
+    assert merged["A"].width == 100
+    assert merged["B"].width == 200
+    assert merged["D"].width == 300
+    assert "C" not in merged
+    assert "ours" in merged and "theirs" in merged
+    assert merged.kerning[("A", "T")] == -10
+    assert merged.info.familyName == "Theirs"
+
+
+def test_merge3_clean(tmp_path: Path) -> None:
+    ours = edit(tmp_path / "ours.ufo", A=100)
+    ours.save()
+    theirs = edit(tmp_path / "theirs.ufo", A=100)
+    theirs.save()
+
+    out = tmp_path / "merged.ufo"
+    assert iondrive.merge3(UFO, ours.path, theirs.path, out) == []
+    assert ufoLib2.Font.open(out)["A"].width == 100
@@ -0,0 +1,49 @@
+import shutil
+from pathlib import Path
+
+import pytest
+import ufoLib2
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_public_lib() -> None:
+    font = iondrive.RustFont.open(UFO)
+    lib = font.public_lib
+    assert lib["glyph_order"] == ufoLib2.Font.open(UFO).lib["public.glyphOrder"]
+    assert lib["opentype_categories"] is None
+    glyph_lib = font.glyph_public_lib("Aacute")
+    assert glyph_lib["mark_color"] == pytest.approx((0.6567, 0.6903, 1, 1))
+    assert glyph_lib["vertical_origin"] is None
+
+
+def test_malformed_values_warn(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    font = ufoLib2.Font.open(path)
+    font.lib["public.openTypeCategories"] = {"A": "base", "B": "letter"}
+    font.lib["public.unicodeVariationSequences"] = {"FE00": {"0041": "A"}}
+    font.lib["public.skipExportGlyphs"] = ["C", 1]
+    font["A"].lib["public.markColor"] = "red"
+    font["A"].lib["public.verticalOrigin"] = 880
+    font.save()
+
+    with pytest.warns(UserWarning) as record:
+        rust_font = iondrive.RustFont.open(path)
+    messages = [str(w.message) for w in record]
+    assert any("category of 'B'" in m for m in messages)
+    assert any("public.skipExportGlyphs" in m for m in messages)
+    assert any("public.markColor" in m and "'A'" in m for m in messages)
+
+    lib = rust_font.public_lib
+    assert lib["opentype_categories"] == {"A": "base"}
+    assert lib["unicode_variation_sequences"] == {0xFE00: {0x41: "A"}}
+    assert lib["skip_export_glyphs"] == ["C"]
+    glyph_lib = rust_font.glyph_public_lib("A")
+    assert glyph_lib["mark_color"] is None
+    assert glyph_lib["vertical_origin"] == 880
+
+    with pytest.warns(UserWarning, match="category of 'B'"):
+        iondrive.load(ufoLib2.objects, path)
@@ -0,0 +1,68 @@
+import plistlib
+import shutil
+from pathlib import Path
+
+import pytest
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+FEATURES = """\
+@UC = [A-C Aacute \\B];
+markClass acute <anchor 0 0> @TOP;
+lookup A {
+    sub A by B;
+} A;
+feature liga {
+    pos base A <anchor 100 200> mark @TOP; # A
+} liga;
+"""
+
+
+def test_rename_glyphs(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    (path / "features.fea").write_text(FEATURES)
+    font = iondrive.RustFont.open(path)
+    kerning, order = font.kerning, font.lib["public.glyphOrder"]
+    mapping = {"A": "B", "B": "A", "acute": "acutecomb"}
+
+    font.rename_glyphs(mapping)
+
+    assert font.kerning[("B", "V")] == kerning[("A", "V")]
+    assert font.groups["public.kern1.@MMK_L_A"] == ["B"]
+    assert font.lib["public.glyphOrder"] == [mapping.get(n, n) for n in order]
+    assert font.features == (
+        FEATURES.replace("A-C", "B-C")
+        .replace("\\B", "\\A")
+        .replace("acute <", "acutecomb <")
+        .replace("sub A by B", "sub B by A")
+        .replace("base A", "base B")
+    )
+
+    font.save()
+    with open(path / "glyphs" / "contents.plist", "rb") as fp:
+        contents = plistlib.load(fp)
+    assert contents["acutecomb"] == "acutecomb.glif"
+    assert "acute" not in contents
+    assert not (path / "glyphs" / "acute.glif").exists()
+    assert "acutecomb" in (path / "glyphs" / "A_acute.glif").read_text()
+
+
+@pytest.mark.parametrize(
+    "mapping, message",
+    [
+        ({"nothing": "x"}, "no glyph named 'nothing'"),
+        ({"A": "B"}, "a glyph with that name exists"),
+        ({"A": "new", "B": "new"}, "can't rename both 'A' and 'B' to 'new'"),
+        ({"A": ""}, "invalid glyph name"),
+    ],
+)
+def test_rename_glyphs_errors(mapping: dict, message: str) -> None:
+    font = iondrive.RustFont.open(UFO)
+
+    with pytest.raises(iondrive.IondriveError, match=message):
+        font.rename_glyphs(mapping)
+
+    assert "A" in font.glyph_names() and "new" not in font.glyph_names()
@@ -0,0 +1,138 @@
+import shutil
+from pathlib import Path
+
+import pytest
+import ufoLib2
+import ufoLib2.objects
+
+import iondrive
+
+UFOS = [
+    Path("tests/data/MutatorSansBoldCondensed.ufo"),
+    Path("tests/data/SourceSans_ExtraLight.ufo"),
+]
+
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_accessors(path: Path) -> None:
+    expected = ufoLib2.Font.open(path)
+    font = iondrive.RustFont.open(path)
+
+    assert font.layer_names() == expected.layers.keys()
+    for layer in expected.layers:
+        assert sorted(font.glyph_names(layer.name)) == sorted(layer.keys())
+    assert sorted(font.glyph_names()) == sorted(expected.keys())
+
+    unicodes = {}
+    for glyph in expected:
+        for cp in glyph.unicodes:
+            unicodes.setdefault(cp, []).append(glyph.name)
+    assert {cp: sorted(n) for cp, n in font.unicode_map().items()} == {
+        cp: sorted(n) for cp, n in unicodes.items()
+    }
+
+    assert font.kerning == expected.kerning
+    assert font.groups == expected.groups
+    assert font.info["familyName"] == expected.info.familyName
+    assert font.info["unitsPerEm"] == expected.info.unitsPerEm
+
+
+@pytest.mark.parametrize("path", UFOS, ids=lambda p: p.name)
+def test_to_ufolib2(path: Path) -> None:
+    expected = ufoLib2.Font.open(path)
+    font = iondrive.RustFont.open(path).to_ufolib2(ufoLib2.objects)
+    assert isinstance(font, ufoLib2.Font)
+    assert font.info == expected.info
+    assert font.kerning == expected.kerning
+    assert font.layers.keys() == expected.layers.keys()
+    for layer in expected.layers:
+        assert font.layers[layer.name].keys() == layer.keys()
+
+
+def test_missing_layer() -> None:
+    font = iondrive.RustFont.open(UFOS[0])
+    with pytest.raises(iondrive.IondriveError):
+        font.glyph_names("no such layer")
+
+
+def test_empty() -> None:
+    font = iondrive.RustFont()
+    assert font.glyph_names() == []
+    assert font.kerning == {}
+    assert font.groups == {}
+    assert font.features == ""
+
+
+def test_edit_and_save(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFOS[0], path)
+    font = iondrive.RustFont.open(path)
+    font.save()
+    assert font.save() == []
+
+    font.set_width("A", 123)
+    font.set_unicodes("B", [0x42, 0x62])
+    font.set_contours("C", [[(0, 0, "line", False), (10, 0, "line", False)]])
+    font.add_glyph("new")
+    font.remove_glyph("D")
+    font.set_info("familyName", "Edited")
+    font.set_info("styleName", None)
+    font.set_kerning("A", "T", -42)
+    font.set_group("other", ["A", "B"])
+    font.add_layer("extra")
+    changed = font.save()
+    assert "glyphs/D_.glif" in changed
+    assert "glyphs/new.glif" in changed
+    assert "glyphs/E_.glif" not in changed
+
+    result = ufoLib2.Font.open(path)
+    assert result["A"].width == 123
+    assert result["B"].unicodes == [0x42, 0x62]
+    assert [(p.x, p.y) for p in result["C"].contours[0]] == [(0, 0), (10, 0)]
+    assert "new" in result and "D" not in result
+    assert result.info.familyName == "Edited"
+    assert result.info.styleName is None
+    assert result.kerning[("A", "T")] == -42
+    assert result.groups["other"] == ["A", "B"]
+    assert "extra" in result.layers
+
+
+def test_save_ufoz(tmp_path: Path) -> None:
+    font = iondrive.RustFont.open(UFOS[0])
+    font.set_width("A", 123)
+    path = tmp_path / "font.ufoz"
+    assert "glyphs/A_.glif" in font.save(path)
+
+    result = iondrive.RustFont.open(path)
+    assert result.glyph_names() == font.glyph_names()
+    assert result.kerning == font.kerning
+    assert result.to_ufolib2(ufoLib2.objects)["A"].width == 123
+    assert list(tmp_path.iterdir()) == [path]
+
+
+def test_edit_errors() -> None:
+    font = iondrive.RustFont.open(UFOS[0])
+    with pytest.raises(iondrive.IondriveError):
+        font.set_width("no such glyph", 100)
+    with pytest.raises(iondrive.IondriveError):
+        font.add_glyph("A")
+    with pytest.raises(iondrive.IondriveError):
+        font.set_info("noSuchAttribute", 1)
+    with pytest.raises(iondrive.IondriveError):
+        font.remove_layer(font.layer_names()[0])
+    with pytest.raises(ValueError):
+        font.set_contours("A", [[(0, 0, "bogus", False)]])
+    with pytest.raises(iondrive.IondriveError):
+        iondrive.RustFont().save()
+
+
+def test_date_out_of_range(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFOS[0], path)
+    lib = (path / "lib.plist").read_text()
+    date = "<key>com.example.date</key><date>0000-01-01T00:00:00Z</date>"
+    (path / "lib.plist").write_text(lib.replace("<dict>", "<dict>" + date, 1))
+
+    font = iondrive.RustFont.open(path)
+
+    assert font.lib["com.example.date"] == "0000-01-01T00:00:00Z"
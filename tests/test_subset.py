@@ -0,0 +1,75 @@
+import plistlib
+import shutil
+from pathlib import Path
+
+import iondrive
+
+UFO = Path("tests/data/MutatorSansBoldCondensed.ufo")
+
+
+def test_subset_pulls_in_components() -> None:
+    result = iondrive.subset(UFO, glyphs=["Aacute", "nothing"], codepoints=[0x42])
+
+    assert result["font"].glyph_names() == ["A", "Aacute", "B", "acute"]
+    assert "Adieresis" in result["removed"]
+    assert "A" not in result["removed"]
+    assert result["missing"] == ["nothing"]
+
+
+def test_subset_filters_kerning_groups_and_lib() -> None:
+    font = iondrive.subset(UFO, glyphs=["A", "B"])["font"]
+
+    assert font.groups == {
+        "public.kern1.@MMK_L_A": ["A"],
+        "public.kern2.@MMK_R_A": ["A"],
+    }
+    assert font.kerning == {("B", "A"): -20}
+    assert font.lib["public.glyphOrder"] == ["A", "B"]
+
+
+def test_subset_reports_features(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    (path / "features.fea").write_text("feature liga {\n    sub A B by C;\n} liga;\n")
+
+    result = iondrive.subset(path, glyphs=["A", "B"])
+
+    assert [(i["kind"], i["line"], i["glyph"]) for i in result["features"]] == [
+        ("missing_glyph", 2, "C")
+    ]
+
+
+def test_subset_writes_output(tmp_path: Path) -> None:
+    output = tmp_path / "subset.ufo"
+
+    result = iondrive.subset(UFO, codepoints=[0x41], output=output)
+
+    with open(output / "glyphs" / "contents.plist", "rb") as fp:
+        assert sorted(plistlib.load(fp)) == ["A"]
+    assert result["font"].glyph_names() == ["A"]
+    result["font"].save()
+    assert (output / "glyphs" / "A_.glif").exists()
+
+
+def test_subset_output_keeps_data_and_used_images(tmp_path: Path) -> None:
+    path = tmp_path / "font.ufo"
+    shutil.copytree(UFO, path)
+    (path / "data").mkdir()
+    (path / "data" / "notes.txt").write_text("notes")
+    (path / "images").mkdir()
+    for name in ["a.png", "c.png"]:
+        (path / "images" / name).write_bytes(b"png")
+    for glif, image in [("A_", "a.png"), ("B_", "gone.png"), ("C_", "c.png")]:
+        glif = path / "glyphs" / f"{glif}.glif"
+        text = glif.read_text().replace(
+            "<advance", f'<image fileName="{image}"/>\n  <advance', 1
+        )
+        glif.write_text(text)
+    output = tmp_path / "subset.ufo"
+
+    iondrive.subset(path, glyphs=["A", "B"], output=output)
+
+    assert (output / "data" / "notes.txt").read_text() == "notes"
+    assert sorted(p.name for p in (output / "images").iterdir()) == ["a.png"]
+    assert "a.png" in (output / "glyphs" / "A_.glif").read_text()
+    assert "<image" not in (output / "glyphs" / "B_.glif").read_text()